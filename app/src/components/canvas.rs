@@ -1,5 +1,13 @@
 use leptos::prelude::*;
 use robotics_lib::boids::Boid;
+use robotics_lib::dstar::GridMap;
+use robotics_lib::ekf_slam::{EkfSlam, RangeBearing, SlamNoise};
+use robotics_lib::exploration::{cell_state, find_frontiers, CellState, Explorer};
+use robotics_lib::grid_search::GridSearch;
+use robotics_lib::mapgen::{random_rects, rooms_and_corridors};
+use robotics_lib::math::{wrap_angle, Matrix, Pose, Vec2};
+use robotics_lib::particle_filter::{ParticleFilter, RangeSensor};
+use robotics_lib::rng::Rng;
 use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 use web_sys::CanvasRenderingContext2d;
@@ -10,11 +18,282 @@ thread_local! {
 
 struct SimState {
     flock: Vec<Boid>,
+    slam: SlamDemo,
+    localise: LocaliseDemo,
+    explore: ExploreDemo,
     ctx: CanvasRenderingContext2d,
     width: f32,
     height: f32,
 }
 
+// EKF-SLAM showcase, in pixels: a robot circling the middle of the screen
+// maps the beacons it passes with noisy range-bearing readings
+const SLAM_SPEED: f32 = 120.0;
+const SLAM_TURN_RATE: f32 = 0.5;
+const SLAM_RANGE: f32 = 220.0;
+// Beacons closer than this are not reported: bearings swing too fast there
+// for the linearised filter, and a close pass starts a duplicate landmark
+const SLAM_MIN_RANGE: f32 = 30.0;
+// Noise the simulation adds to motion and readings
+const SLAM_NOISE: SlamNoise = SlamNoise {
+    speed: 100.0,
+    turn_rate: 0.0025,
+    range: 16.0,
+    bearing: 0.001,
+};
+// The filter assumes twice the true noise variances, since EKF-SLAM grows
+// overconfident over many laps
+const SLAM_NOISE_MARGIN: f32 = 2.0;
+// The demo makes about 300 readings a second, so the default gate would
+// start a spurious landmark every few minutes
+const SLAM_NEW_LANDMARK_GATE: f32 = 40.0;
+// Seconds before the map is dropped and the filter restarted from its own
+// pose estimate and covariance: ten laps. This bounds the round-off that
+// builds up in the f32 covariance; the pose keeps whatever drift it has.
+const SLAM_RESTART: f32 = 10.0 * std::f32::consts::TAU / SLAM_TURN_RATE;
+
+struct SlamDemo {
+    slam: EkfSlam,
+    truth: Pose,
+    beacons: Vec<Vec2>,
+    rng: Rng,
+    elapsed: f32,
+}
+
+impl SlamDemo {
+    fn new(width: f32, height: f32) -> Self {
+        let mut rng = Rng::new(7);
+        let centre = Vec2::new(width / 2.0, height / 2.0);
+        let radius = SLAM_SPEED / SLAM_TURN_RATE;
+        let beacons = (0..18)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / 18.0;
+                let r = radius * rng.range(0.4, 1.6);
+                centre + Vec2::new(angle.cos(), angle.sin()) * r
+            })
+            .collect();
+        let truth = Pose::new(centre.x, centre.y - radius, 0.0);
+        Self {
+            slam: Self::filter(truth, Matrix::zeros()),
+            truth,
+            beacons,
+            rng,
+            elapsed: 0.0,
+        }
+    }
+
+    // Empty map with the robot at `pose`, uncertain by `covariance`
+    fn filter(pose: Pose, covariance: Matrix<3, 3>) -> EkfSlam {
+        let mut slam = EkfSlam::new(pose, covariance);
+        slam.new_landmark_gate = SLAM_NEW_LANDMARK_GATE;
+        slam.noise = SlamNoise {
+            speed: SLAM_NOISE.speed * SLAM_NOISE_MARGIN,
+            turn_rate: SLAM_NOISE.turn_rate * SLAM_NOISE_MARGIN,
+            range: SLAM_NOISE.range * SLAM_NOISE_MARGIN,
+            bearing: SLAM_NOISE.bearing * SLAM_NOISE_MARGIN,
+        };
+        slam
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.elapsed += dt;
+        if self.elapsed >= SLAM_RESTART {
+            self.elapsed = 0.0;
+            self.slam = Self::filter(self.slam.pose(), self.slam.pose_covariance());
+        }
+        let noise = SLAM_NOISE;
+        let v = SLAM_SPEED + noise.speed.sqrt() * self.rng.gaussian();
+        let w = SLAM_TURN_RATE + noise.turn_rate.sqrt() * self.rng.gaussian();
+        let t = self.truth;
+        self.truth = Pose::new(
+            t.x + v * t.theta.cos() * dt,
+            t.y + v * t.theta.sin() * dt,
+            wrap_angle(t.theta + w * dt),
+        );
+        self.slam.predict(SLAM_SPEED, SLAM_TURN_RATE, dt);
+
+        let scan: Vec<RangeBearing> = self
+            .beacons
+            .iter()
+            .map(|&b| RangeBearing::observe(self.truth, b))
+            .filter(|z| z.range > SLAM_MIN_RANGE && z.range < SLAM_RANGE)
+            .map(|z| {
+                RangeBearing::new(
+                    z.range + noise.range.sqrt() * self.rng.gaussian(),
+                    wrap_angle(z.bearing + noise.bearing.sqrt() * self.rng.gaussian()),
+                )
+            })
+            .collect();
+        self.slam.observe_all(&scan);
+    }
+}
+
+// Monte Carlo localisation showcase, in cells: a robot shuttles between two
+// corners of a cluttered map while a particle filter that starts with no
+// idea where it is tracks it from range scans and noisy odometry
+const LOCALISE_CELL: f64 = 6.0;
+// Seconds per cell driven
+const LOCALISE_STEP: f32 = 0.1;
+// Legs driven before the particles are scattered over the map again
+const LOCALISE_LEGS: usize = 4;
+// Brightness levels of the particle cloud, each drawn as a single path
+const LOCALISE_SHADES: usize = 4;
+
+struct LocaliseDemo {
+    map: GridMap,
+    filter: ParticleFilter,
+    ends: [(usize, usize); 2],
+    route: Vec<Pose>,
+    next: usize,
+    truth: Pose,
+    odom: Pose,
+    legs: usize,
+    rng: Rng,
+    timer: f32,
+}
+
+impl LocaliseDemo {
+    fn new() -> Self {
+        let generated = (11..)
+            .map(|seed| random_rects(40, 30, 0.15, 5, seed))
+            .find(|g| g.connected)
+            .unwrap();
+        let map = generated.map;
+        let filter = ParticleFilter::new(&map, RangeSensor::new(24, std::f32::consts::TAU, 8.0), 3);
+        let route = Self::route(&map, generated.start, generated.goal);
+        Self {
+            truth: route[0],
+            map,
+            filter,
+            ends: [generated.start, generated.goal],
+            route,
+            next: 1,
+            odom: Pose::new(0.0, 0.0, 0.0),
+            legs: 0,
+            rng: Rng::new(5),
+            timer: 0.0,
+        }
+    }
+
+    // Poses at the cells of a planned path, each facing the next cell
+    fn route(map: &GridMap, from: (usize, usize), to: (usize, usize)) -> Vec<Pose> {
+        let cells = GridSearch::default()
+            .plan(map, from, to)
+            .map(|p| p.cells)
+            .unwrap_or_else(|| vec![from]);
+        let mut heading = 0.0;
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| {
+                if let Some(&(nx, ny)) = cells.get(i + 1) {
+                    heading = (ny as f32 - y as f32).atan2(nx as f32 - x as f32);
+                }
+                Pose::new(x as f32 + 0.5, y as f32 + 0.5, heading)
+            })
+            .collect()
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.timer += dt;
+        if self.timer < LOCALISE_STEP {
+            return;
+        }
+        self.timer = 0.0;
+        if self.next >= self.route.len() {
+            // Turn round on the spot, and every few legs forget everything
+            self.ends.swap(0, 1);
+            self.route = Self::route(&self.map, self.ends[0], self.ends[1]);
+            self.next = 0;
+            self.legs += 1;
+            if self.legs.is_multiple_of(LOCALISE_LEGS) {
+                self.filter.reset_uniform();
+            }
+        }
+        let (a, b) = (self.truth, self.route[self.next]);
+        self.next += 1;
+        self.truth = b;
+
+        // The same motion in the robot frame with a few percent error
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let (forward, left) = (
+            dx * a.theta.cos() + dy * a.theta.sin(),
+            -dx * a.theta.sin() + dy * a.theta.cos(),
+        );
+        let scale = 1.0 + 0.03 * self.rng.gaussian();
+        let turn = wrap_angle(b.theta - a.theta) + 0.02 * self.rng.gaussian();
+        let o = self.odom;
+        let (s, c) = (o.theta.sin(), o.theta.cos());
+        let odom = Pose::new(
+            o.x + (c * forward - s * left) * scale,
+            o.y + (s * forward + c * left) * scale,
+            wrap_angle(o.theta + turn),
+        );
+
+        let max_range = self.filter.sensor.max_range;
+        let scan: Vec<f32> = self
+            .filter
+            .sensor
+            .scan(&self.map, b)
+            .into_iter()
+            .map(|r| {
+                if r < max_range {
+                    (r + 0.05 * self.rng.gaussian()).max(0.0)
+                } else {
+                    r
+                }
+            })
+            .collect();
+        self.filter.step(self.odom, odom, &scan);
+        self.odom = odom;
+    }
+}
+
+// Frontier exploration showcase, in cells: a robot maps a hidden set of
+// rooms by itself, one frontier per round, then moves on to a fresh map
+const EXPLORE_CELL: f64 = 5.0;
+// Seconds per exploration round, and spent showing the finished map
+const EXPLORE_ROUND: f32 = 0.4;
+const EXPLORE_PAUSE: f32 = 3.0;
+
+struct ExploreDemo {
+    truth: GridMap,
+    explorer: Explorer,
+    seed: u64,
+    timer: f32,
+    done: bool,
+}
+
+impl ExploreDemo {
+    fn new(seed: u64) -> Self {
+        let generated = rooms_and_corridors(48, 32, 8, 9, seed);
+        let mut explorer = Explorer::new(48, 32, generated.start, 6.0);
+        explorer.sense(&generated.map);
+        Self {
+            truth: generated.map,
+            explorer,
+            seed,
+            timer: 0.0,
+            done: false,
+        }
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.timer += dt;
+        if self.done {
+            if self.timer >= EXPLORE_PAUSE {
+                *self = Self::new(self.seed + 1);
+            }
+            return;
+        }
+        if self.timer < EXPLORE_ROUND {
+            return;
+        }
+        self.timer = 0.0;
+        self.done = !self.explorer.step(&self.truth);
+    }
+}
+
 // Export this function for JS to call
 #[wasm_bindgen]
 pub fn animation_tick() {
@@ -31,8 +310,15 @@ pub fn animation_tick() {
             boid.edges(s.width, s.height);
         }
 
+        s.slam.step(1.0 / 60.0);
+        s.localise.step(1.0 / 60.0);
+        s.explore.step(1.0 / 60.0);
+
         // Render
         render(&s.ctx, &s.flock, s.width as f64, s.height as f64);
+        render_slam(&s.ctx, &s.slam);
+        render_localise(&s.ctx, &s.localise, s.width as f64, s.height as f64);
+        render_explore(&s.ctx, &s.explore, s.height as f64);
     });
 }
 
@@ -107,6 +393,180 @@ fn render(ctx: &CanvasRenderingContext2d, flock: &[Boid], w: f64, h: f64) {
     }
 }
 
+fn render_slam(ctx: &CanvasRenderingContext2d, demo: &SlamDemo) {
+    // True beacons
+    ctx.set_stroke_style_str("rgba(255,255,255,0.35)");
+    ctx.set_line_width(1.0);
+    for b in &demo.beacons {
+        let (x, y) = (b.x as f64, b.y as f64);
+        ctx.begin_path();
+        ctx.move_to(x - 3.0, y - 3.0);
+        ctx.line_to(x + 3.0, y + 3.0);
+        ctx.move_to(x + 3.0, y - 3.0);
+        ctx.line_to(x - 3.0, y + 3.0);
+        ctx.stroke();
+    }
+
+    // Mapped landmarks with their 3-sigma ellipses
+    ctx.set_stroke_style_str("rgba(0,255,100,0.8)");
+    for landmark in demo.slam.landmarks() {
+        let (major, minor, angle) = landmark.ellipse(3.0);
+        ctx.begin_path();
+        let _ = ctx.ellipse(
+            landmark.position.x as f64,
+            landmark.position.y as f64,
+            major.max(1.0) as f64,
+            minor.max(1.0) as f64,
+            angle as f64,
+            0.0,
+            std::f64::consts::TAU,
+        );
+        ctx.stroke();
+    }
+
+    // Estimated pose
+    let pose = demo.slam.pose();
+    ctx.save();
+    let _ = ctx.translate(pose.x as f64, pose.y as f64);
+    let _ = ctx.rotate(pose.theta as f64);
+    ctx.begin_path();
+    ctx.move_to(10.0, 0.0);
+    ctx.line_to(-6.0, 5.0);
+    ctx.line_to(-6.0, -5.0);
+    ctx.close_path();
+    ctx.set_fill_style_str("rgba(0,255,100,0.9)");
+    ctx.fill();
+    ctx.restore();
+}
+
+// Bottom-right panel: the map, the true robot and the particle cloud
+fn render_localise(ctx: &CanvasRenderingContext2d, demo: &LocaliseDemo, w: f64, h: f64) {
+    let cell = LOCALISE_CELL;
+    let (pw, ph) = (demo.map.width as f64 * cell, demo.map.height as f64 * cell);
+    ctx.save();
+    let _ = ctx.translate(w - pw - 16.0, h - ph - 16.0);
+    ctx.set_fill_style_str("#000");
+    ctx.fill_rect(0.0, 0.0, pw, ph);
+    ctx.set_fill_style_str("rgba(255,255,255,0.35)");
+    for y in 0..demo.map.height {
+        for x in 0..demo.map.width {
+            if demo.map.is_obstacle(x, y) {
+                ctx.fill_rect(x as f64 * cell, y as f64 * cell, cell, cell);
+            }
+        }
+    }
+    ctx.set_stroke_style_str("rgba(0,255,100,0.4)");
+    ctx.set_line_width(1.0);
+    ctx.stroke_rect(0.0, 0.0, pw, ph);
+
+    // True robot under the cloud
+    ctx.begin_path();
+    let _ = ctx.arc(
+        demo.truth.x as f64 * cell,
+        demo.truth.y as f64 * cell,
+        cell * 0.6,
+        0.0,
+        std::f64::consts::TAU,
+    );
+    ctx.set_fill_style_str("#fff");
+    ctx.fill();
+
+    render_particles(ctx, &demo.filter, cell);
+    ctx.restore();
+}
+
+// Bottom-left panel: what the explorer knows, its frontiers and the robot
+fn render_explore(ctx: &CanvasRenderingContext2d, demo: &ExploreDemo, h: f64) {
+    let cell = EXPLORE_CELL;
+    let map = &demo.explorer.map;
+    let (pw, ph) = (map.width as f64 * cell, map.height as f64 * cell);
+    ctx.save();
+    let _ = ctx.translate(16.0, h - ph - 16.0);
+    ctx.set_fill_style_str("#000");
+    ctx.fill_rect(0.0, 0.0, pw, ph);
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let fill = match cell_state(map, x, y) {
+                CellState::Free => "rgba(0,255,100,0.15)",
+                CellState::Occupied => "rgba(255,255,255,0.35)",
+                CellState::Unknown => continue,
+            };
+            ctx.set_fill_style_str(fill);
+            ctx.fill_rect(x as f64 * cell, y as f64 * cell, cell, cell);
+        }
+    }
+    ctx.set_fill_style_str("rgba(255,200,0,0.8)");
+    for frontier in find_frontiers(map, demo.explorer.min_frontier_size) {
+        for (x, y) in frontier.cells {
+            ctx.fill_rect(x as f64 * cell, y as f64 * cell, cell, cell);
+        }
+    }
+    ctx.set_stroke_style_str("rgba(0,255,100,0.4)");
+    ctx.set_line_width(1.0);
+    ctx.stroke_rect(0.0, 0.0, pw, ph);
+
+    let (x, y) = demo.explorer.position;
+    ctx.begin_path();
+    let _ = ctx.arc(
+        (x as f64 + 0.5) * cell,
+        (y as f64 + 0.5) * cell,
+        cell * 0.8,
+        0.0,
+        std::f64::consts::TAU,
+    );
+    ctx.set_fill_style_str("rgba(0,255,100,0.9)");
+    ctx.fill();
+    ctx.restore();
+}
+
+// A particle filter's cloud, `cell` pixels per grid cell: a short heading
+// tick per particle, brighter for heavier ones, and the estimate
+fn render_particles(ctx: &CanvasRenderingContext2d, filter: &ParticleFilter, cell: f64) {
+    let max = filter
+        .particles
+        .iter()
+        .map(|p| p.weight)
+        .fold(0.0f32, f32::max)
+        .max(f32::EPSILON);
+    let shade_of =
+        |weight: f32| ((weight / max * LOCALISE_SHADES as f32) as usize).min(LOCALISE_SHADES - 1);
+    ctx.set_line_width(1.0);
+    for shade in 0..LOCALISE_SHADES {
+        ctx.begin_path();
+        for p in filter
+            .particles
+            .iter()
+            .filter(|p| shade_of(p.weight) == shade)
+        {
+            let x = p.pose.x as f64 * cell;
+            let y = p.pose.y as f64 * cell;
+            let theta = p.pose.theta as f64;
+            ctx.move_to(x, y);
+            ctx.line_to(x + theta.cos() * cell * 0.5, y + theta.sin() * cell * 0.5);
+        }
+        ctx.set_stroke_style_str(&format!(
+            "rgba(255,80,80,{})",
+            0.15 + 0.6 * (shade + 1) as f64 / LOCALISE_SHADES as f64
+        ));
+        ctx.stroke();
+    }
+
+    // Estimate
+    let estimate = filter.estimate();
+    let x = estimate.x as f64 * cell;
+    let y = estimate.y as f64 * cell;
+    ctx.begin_path();
+    let _ = ctx.arc(x, y, cell * 0.4, 0.0, std::f64::consts::TAU);
+    ctx.move_to(x, y);
+    ctx.line_to(
+        x + (estimate.theta as f64).cos() * cell,
+        y + (estimate.theta as f64).sin() * cell,
+    );
+    ctx.set_stroke_style_str("#ff5050");
+    ctx.set_line_width(2.0);
+    ctx.stroke();
+}
+
 #[wasm_bindgen]
 pub fn init_simulation(canvas_id: &str) -> bool {
     let win = match web_sys::window() {
@@ -171,9 +631,16 @@ pub fn init_simulation(canvas_id: &str) -> bool {
         .into(),
     );
 
+    let slam = SlamDemo::new(width, height);
+    let localise = LocaliseDemo::new();
+    let explore = ExploreDemo::new(3);
+
     STATE.with(|s| {
         *s.borrow_mut() = Some(SimState {
             flock,
+            slam,
+            localise,
+            explore,
             ctx,
             width,
             height,
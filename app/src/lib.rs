@@ -72,8 +72,8 @@ pub fn App() -> impl IntoView {
                         </p>
                         <div class="grid grid-cols-1 md:grid-cols-3 gap-4 text-xs font-mono text-green-400 mt-8 text-left">
                            <div class="border border-green-900/30 p-3 bg-black/40">
-                               <strong class="block text-white mb-1">"> EKF"</strong>
-                               "State estimation with 80+ agents"
+                               <strong class="block text-white mb-1">"> EKF-SLAM"</strong>
+                               "Localization and mapping with covariances"
                            </div>
                            <div class="border border-green-900/30 p-3 bg-black/40">
                                <strong class="block text-white mb-1">"> APF"</strong>
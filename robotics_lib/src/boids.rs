@@ -1,4 +1,5 @@
 use crate::ekf::EKF;
+use crate::flow_field::FlowField;
 use crate::math::Vec2;
 use crate::physics::Particle;
 
@@ -140,6 +141,18 @@ impl Boid {
         steer.limit(self.particle.max_force)
     }
 
+    /// Steering force along a flow field towards its goal. Like seeking a
+    /// target, but routed around the field's obstacles.
+    pub fn follow_flow(&self, field: &FlowField) -> Vec2 {
+        let direction = field.steering(self.particle.position);
+        if direction.mag_sq() == 0.0 {
+            return Vec2::zero();
+        }
+        let desired = direction * self.particle.max_speed;
+        let steer = desired - self.particle.velocity;
+        steer.limit(self.particle.max_force)
+    }
+
     pub fn edges(&mut self, width: f32, height: f32) {
         if self.particle.position.x > width {
             self.particle.position.x = 0.0;
@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod tests {
     use crate::boids::Boid;
-    use crate::math::Vec2;
+    use crate::dstar::GridMap;
+    use crate::flow_field::FlowField;
+    use crate::map_io::MapInfo;
+    use crate::math::{Pose, Vec2};
     use alloc::vec;
     use alloc::vec::Vec;
 
@@ -266,6 +269,47 @@ mod tests {
         assert_eq!(boid.particle.position.y, 0.0);
     }
 
+    // ==================== FLOW FIELD ====================
+
+    #[test]
+    fn test_follow_flow_reaches_goal_around_wall() {
+        // 800x600 screen in 20 px cells, wall between the flock and the goal
+        let mut map = GridMap::new(40, 30);
+        for y in 0..24 {
+            map.set_obstacle(20, y, true);
+        }
+        let info = MapInfo::new(20.0, Pose::new(0.0, 0.0, 0.0));
+        let field = FlowField::new(map, (35, 5), info);
+        let goal = info.cell_to_world((35, 5));
+
+        let mut boids: Vec<Boid> = (0..10)
+            .map(|i| Boid::new(i, 60.0 + 10.0 * i as f32, 100.0))
+            .collect();
+        for _ in 0..1500 {
+            for boid in boids.iter_mut() {
+                let force = boid.follow_flow(&field);
+                boid.particle.apply_force(force);
+                boid.particle.update();
+            }
+        }
+        for boid in &boids {
+            assert!(
+                boid.particle.position.distance(&goal) < 60.0,
+                "{:?}",
+                boid.particle.position
+            );
+        }
+    }
+
+    #[test]
+    fn test_follow_flow_is_limited() {
+        let field = FlowField::new(GridMap::new(10, 10), (9, 9), MapInfo::default());
+        let boid = Boid::new(0, 1.5, 1.5);
+        let force = boid.follow_flow(&field);
+        assert!(force.mag() <= boid.particle.max_force + 1e-6);
+        assert!(force.mag() > 0.0);
+    }
+
     // ==================== CLONE ====================
 
     #[test]
@@ -0,0 +1,476 @@
+use crate::dstar::GridMap;
+use crate::grid_search::{Connectivity, GridSearch, Heuristic};
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::vec::Vec;
+
+type Cell = (usize, usize);
+
+/// A collision between two agents' timed paths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Agents `a` and `b` are both in `cell` at `time`.
+    Vertex {
+        a: usize,
+        b: usize,
+        cell: Cell,
+        time: usize,
+    },
+    /// Agents `a` and `b` swap cells between `time` and `time + 1`, with `a`
+    /// moving from `from` to `to`.
+    Edge {
+        a: usize,
+        b: usize,
+        from: Cell,
+        to: Cell,
+        time: usize,
+    },
+}
+
+/// A constraint added by the high-level search to split a conflict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// `agent` may not be in `cell` at `time`.
+    Vertex {
+        agent: usize,
+        cell: Cell,
+        time: usize,
+    },
+    /// `agent` may not move from `from` to `to` between `time` and `time + 1`.
+    Edge {
+        agent: usize,
+        from: Cell,
+        to: Cell,
+        time: usize,
+    },
+}
+
+impl Constraint {
+    pub fn agent(&self) -> usize {
+        match *self {
+            Constraint::Vertex { agent, .. } | Constraint::Edge { agent, .. } => agent,
+        }
+    }
+}
+
+/// Search effort of the last [`Cbs::solve`] call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CbsStats {
+    /// Constraint-tree nodes expanded (conflicts split).
+    pub high_level_expanded: usize,
+    /// Constraint-tree nodes created, including the root.
+    pub high_level_generated: usize,
+    /// Space-time states expanded by all low-level searches.
+    pub low_level_expanded: usize,
+    /// Conflicts between the root node's paths.
+    pub root_conflicts: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapfSolution {
+    /// One timed path per agent: `paths[i][t]` is agent i's cell at time t.
+    /// After its last entry an agent waits on its goal.
+    pub paths: Vec<Vec<Cell>>,
+    /// Sum over agents of the arrival time at their goal.
+    pub cost: usize,
+    /// Latest arrival time over all agents.
+    pub makespan: usize,
+    /// Constraints of the constraint-tree node that produced the solution.
+    pub constraints: Vec<Constraint>,
+}
+
+/// Cell of a timed path at time `t`; agents wait on their goal once done.
+/// None for an empty path.
+pub fn position(path: &[Cell], t: usize) -> Option<Cell> {
+    path.get(t.min(path.len().saturating_sub(1))).copied()
+}
+
+/// Every vertex and edge conflict between the given timed paths, earliest
+/// first. Empty paths conflict with nothing.
+pub fn find_conflicts(paths: &[Vec<Cell>]) -> Vec<Conflict> {
+    let makespan = paths.iter().map(|p| p.len()).max().unwrap_or(0);
+    let mut conflicts = Vec::new();
+    for t in 0..makespan {
+        for a in 0..paths.len() {
+            for b in a + 1..paths.len() {
+                let (pa, pb) = (&paths[a], &paths[b]);
+                let (Some(at), Some(bt)) = (position(pa, t), position(pb, t)) else {
+                    continue;
+                };
+                if at == bt {
+                    conflicts.push(Conflict::Vertex {
+                        a,
+                        b,
+                        cell: at,
+                        time: t,
+                    });
+                } else if t + 1 < makespan
+                    && position(pb, t + 1) == Some(at)
+                    && position(pa, t + 1) == Some(bt)
+                {
+                    conflicts.push(Conflict::Edge {
+                        a,
+                        b,
+                        from: at,
+                        to: bt,
+                        time: t,
+                    });
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+// Constraint-tree node
+struct CtNode {
+    constraints: Vec<Constraint>,
+    paths: Vec<Vec<Cell>>,
+    // Per-agent lower bounds reported by the low-level search
+    bounds: Vec<usize>,
+    cost: usize,
+    bound: usize,
+    conflicts: usize,
+}
+
+// Low-level space-time search node
+struct StNode {
+    cell: Cell,
+    time: usize,
+    conflicts: usize,
+    parent: usize,
+}
+
+/// Conflict-Based Search for multi-agent path finding on a `GridMap`.
+///
+/// Every move or wait takes one time step and agents stay on their goal after
+/// arriving. Cells below `LETHAL` are free; with 8-connectivity diagonal moves
+/// may not cut corners, and agents crossing diagonally are not treated as a
+/// conflict.
+///
+/// With `suboptimality` above 1 the solver runs ECBS: both levels use focal
+/// search and prefer candidates with fewer conflicts as long as their cost is
+/// within that factor of the lower bound. The returned cost is then at most
+/// `suboptimality` times the optimum. Plain CBS can need exponentially many
+/// nodes when agents have to cross on open ground, where ECBS stays fast.
+#[derive(Clone, Debug)]
+pub struct Cbs {
+    pub connectivity: Connectivity,
+    /// 1.0 for optimal CBS, greater than 1.0 for ECBS.
+    pub suboptimality: f32,
+    /// Give up after expanding this many constraint-tree nodes.
+    pub max_nodes: usize,
+    pub stats: CbsStats,
+}
+
+impl Default for Cbs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cbs {
+    pub fn new() -> Self {
+        Self {
+            connectivity: Connectivity::Four,
+            suboptimality: 1.0,
+            max_nodes: 10_000,
+            stats: CbsStats::default(),
+        }
+    }
+
+    pub fn ecbs(suboptimality: f32) -> Self {
+        Self {
+            suboptimality: suboptimality.max(1.0),
+            ..Self::new()
+        }
+    }
+
+    /// Plan collision-free timed paths for `(start, goal)` pairs. Returns
+    /// `None` if there is no solution or the node budget runs out.
+    pub fn solve(&mut self, map: &GridMap, agents: &[(Cell, Cell)]) -> Option<MapfSolution> {
+        self.stats = CbsStats::default();
+        let heuristics: Vec<Vec<usize>> = agents
+            .iter()
+            .map(|&(_, goal)| self.distances(map, goal))
+            .collect();
+
+        let mut root = CtNode {
+            constraints: Vec::new(),
+            paths: Vec::new(),
+            bounds: Vec::new(),
+            cost: 0,
+            bound: 0,
+            conflicts: 0,
+        };
+        for (i, &(start, goal)) in agents.iter().enumerate() {
+            let (path, bound) =
+                self.low_level(map, i, start, goal, &heuristics[i], &[], &root.paths)?;
+            root.paths.push(path);
+            root.bounds.push(bound);
+        }
+        self.finish(&mut root);
+        self.stats.root_conflicts = root.conflicts;
+        self.stats.high_level_generated = 1;
+
+        let mut open = alloc::vec![root];
+        while !open.is_empty() && self.stats.high_level_expanded < self.max_nodes {
+            // Focal selection: fewest conflicts among nodes within the bound
+            let lower = open.iter().map(|n| n.bound).min().unwrap_or(0);
+            let limit = (self.suboptimality * lower as f32) as usize;
+            let pick = (0..open.len())
+                .filter(|&i| open[i].cost <= limit.max(lower))
+                .min_by_key(|&i| (open[i].conflicts, open[i].cost))?;
+            let node = open.swap_remove(pick);
+            self.stats.high_level_expanded += 1;
+
+            let conflict = match find_conflicts(&node.paths).first() {
+                Some(&c) => c,
+                None => {
+                    return Some(MapfSolution {
+                        makespan: node.paths.iter().map(|p| p.len() - 1).max().unwrap_or(0),
+                        paths: node.paths,
+                        cost: node.cost,
+                        constraints: node.constraints,
+                    })
+                }
+            };
+
+            let split = match conflict {
+                Conflict::Vertex { a, b, cell, time } => [
+                    Constraint::Vertex {
+                        agent: a,
+                        cell,
+                        time,
+                    },
+                    Constraint::Vertex {
+                        agent: b,
+                        cell,
+                        time,
+                    },
+                ],
+                Conflict::Edge {
+                    a,
+                    b,
+                    from,
+                    to,
+                    time,
+                } => [
+                    Constraint::Edge {
+                        agent: a,
+                        from,
+                        to,
+                        time,
+                    },
+                    Constraint::Edge {
+                        agent: b,
+                        from: to,
+                        to: from,
+                        time,
+                    },
+                ],
+            };
+
+            for constraint in split {
+                let agent = constraint.agent();
+                let mut constraints = node.constraints.clone();
+                constraints.push(constraint);
+                let (start, goal) = agents[agent];
+                let mut others = node.paths.clone();
+                others[agent].clear();
+                if let Some((path, bound)) = self.low_level(
+                    map,
+                    agent,
+                    start,
+                    goal,
+                    &heuristics[agent],
+                    &constraints,
+                    &others,
+                ) {
+                    others[agent] = path;
+                    let mut bounds = node.bounds.clone();
+                    bounds[agent] = bound;
+                    let mut child = CtNode {
+                        constraints,
+                        paths: others,
+                        bounds,
+                        cost: 0,
+                        bound: 0,
+                        conflicts: 0,
+                    };
+                    self.finish(&mut child);
+                    self.stats.high_level_generated += 1;
+                    open.push(child);
+                }
+            }
+        }
+        None
+    }
+
+    fn finish(&self, node: &mut CtNode) {
+        node.cost = node.paths.iter().map(|p| p.len() - 1).sum();
+        node.bound = node.bounds.iter().sum();
+        node.conflicts = find_conflicts(&node.paths).len();
+    }
+
+    // Unit-cost distance to `goal` from every cell (BFS), usize::MAX if unreachable
+    fn distances(&self, map: &GridMap, goal: Cell) -> Vec<usize> {
+        let mut dist = alloc::vec![usize::MAX; map.width * map.height];
+        if map.is_obstacle(goal.0, goal.1) {
+            return dist;
+        }
+        let grid = GridSearch::new(self.connectivity, Heuristic::Zero);
+        let mut queue = VecDeque::new();
+        dist[map.index(goal.0, goal.1)] = 0;
+        queue.push_back(goal);
+        while let Some(cell) = queue.pop_front() {
+            let d = dist[map.index(cell.0, cell.1)];
+            for (next, _) in grid.neighbors(map, cell.0, cell.1) {
+                let idx = map.index(next.0, next.1);
+                if dist[idx] == usize::MAX {
+                    dist[idx] = d + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        dist
+    }
+
+    // Space-time focal search for one agent. Returns the path and a lower
+    // bound on its optimal cost. Empty entries in `others` are ignored.
+    #[allow(clippy::too_many_arguments)]
+    fn low_level(
+        &mut self,
+        map: &GridMap,
+        agent: usize,
+        start: Cell,
+        goal: Cell,
+        h: &[usize],
+        constraints: &[Constraint],
+        others: &[Vec<Cell>],
+    ) -> Option<(Vec<Cell>, usize)> {
+        if map.is_obstacle(start.0, start.1) || h[map.index(start.0, start.1)] == usize::MAX {
+            return None;
+        }
+
+        let mut vertex = BTreeSet::new();
+        let mut edge = BTreeSet::new();
+        let mut last_time = 0;
+        let mut goal_time = 0;
+        for c in constraints.iter().filter(|c| c.agent() == agent) {
+            match *c {
+                Constraint::Vertex { cell, time, .. } => {
+                    vertex.insert((cell, time));
+                    if cell == goal {
+                        goal_time = goal_time.max(time + 1);
+                    }
+                    last_time = last_time.max(time);
+                }
+                Constraint::Edge { from, to, time, .. } => {
+                    edge.insert((from, to, time));
+                    last_time = last_time.max(time);
+                }
+            }
+        }
+        if vertex.contains(&(start, 0)) {
+            return None;
+        }
+        // Past this point waiting cannot help, so longer paths are pointless
+        let horizon = last_time + map.width * map.height + 1;
+
+        let grid = GridSearch::new(self.connectivity, Heuristic::Zero);
+        let heuristic = |c: Cell| h[map.index(c.0, c.1)];
+        let mut nodes = alloc::vec![StNode {
+            cell: start,
+            time: 0,
+            conflicts: 0,
+            parent: usize::MAX,
+        }];
+        let mut seen = BTreeSet::new();
+        seen.insert((start, 0));
+
+        // `open` is ordered by f, `focal` holds open nodes with f <= bound
+        let f0 = heuristic(start);
+        let mut bound = self.focal_limit(f0);
+        let mut open = BTreeSet::new();
+        let mut focal = BTreeSet::new();
+        open.insert((f0, 0));
+        focal.insert((0, f0, usize::MAX, 0));
+
+        let mut successors = Vec::new();
+        while let Some(&(f_min, _)) = open.first() {
+            let limit = self.focal_limit(f_min);
+            if limit > bound {
+                for &(f, id) in open.range((bound + 1, 0)..=(limit, usize::MAX)) {
+                    focal.insert((nodes[id].conflicts, f, usize::MAX - nodes[id].time, id));
+                }
+                bound = limit;
+            }
+            let (_, f, _, id) = focal.pop_first()?;
+            open.remove(&(f, id));
+            self.stats.low_level_expanded += 1;
+
+            let (cell, time) = (nodes[id].cell, nodes[id].time);
+            if cell == goal && time >= goal_time {
+                let mut path = Vec::new();
+                let mut i = id;
+                while i != usize::MAX {
+                    path.push(nodes[i].cell);
+                    i = nodes[i].parent;
+                }
+                path.reverse();
+                return Some((path, f_min));
+            }
+            if time >= horizon {
+                continue;
+            }
+
+            successors.clear();
+            successors.push(cell);
+            successors.extend(grid.neighbors(map, cell.0, cell.1).map(|(c, _)| c));
+            for &next in &successors {
+                let t = time + 1;
+                if vertex.contains(&(next, t))
+                    || edge.contains(&(cell, next, time))
+                    || !seen.insert((next, t))
+                {
+                    continue;
+                }
+                let f = t + heuristic(next);
+                let conflicts = nodes[id].conflicts + step_conflicts(others, cell, next, time);
+                let child = nodes.len();
+                nodes.push(StNode {
+                    cell: next,
+                    time: t,
+                    conflicts,
+                    parent: id,
+                });
+                open.insert((f, child));
+                if f <= bound {
+                    focal.insert((conflicts, f, usize::MAX - t, child));
+                }
+            }
+        }
+        None
+    }
+
+    fn focal_limit(&self, f_min: usize) -> usize {
+        ((self.suboptimality * f_min as f32) as usize).max(f_min)
+    }
+}
+
+// Conflicts created by moving from `from` to `to` between `time` and `time + 1`
+fn step_conflicts(others: &[Vec<Cell>], from: Cell, to: Cell, time: usize) -> usize {
+    others
+        .iter()
+        .filter(|p| {
+            position(p, time + 1) == Some(to)
+                || (from != to
+                    && position(p, time) == Some(to)
+                    && position(p, time + 1) == Some(from))
+        })
+        .count()
+}
+
+#[cfg(test)]
+#[path = "cbs_tests.rs"]
+mod tests;
@@ -0,0 +1,246 @@
+#[cfg(test)]
+mod tests {
+    use crate::cbs::{find_conflicts, position, Cbs, Conflict, MapfSolution};
+    use crate::dstar::GridMap;
+    use crate::grid_search::Connectivity;
+    use alloc::vec::Vec;
+
+    type Cell = (usize, usize);
+
+    fn assert_valid(map: &GridMap, agents: &[(Cell, Cell)], solution: &MapfSolution) {
+        assert_eq!(solution.paths.len(), agents.len());
+        assert!(find_conflicts(&solution.paths).is_empty());
+        for (path, &(start, goal)) in solution.paths.iter().zip(agents) {
+            assert_eq!(path[0], start);
+            assert_eq!(*path.last().unwrap(), goal);
+            for w in path.windows(2) {
+                assert!(!map.is_obstacle(w[1].0, w[1].1));
+                let dx = (w[0].0 as isize - w[1].0 as isize).abs();
+                let dy = (w[0].1 as isize - w[1].1 as isize).abs();
+                assert!(dx <= 1 && dy <= 1);
+            }
+        }
+        let cost: usize = solution.paths.iter().map(|p| p.len() - 1).sum();
+        assert_eq!(solution.cost, cost);
+    }
+
+    // Row y = 1 is a corridor with a one-cell bay at (2, 0)
+    fn corridor_with_bay() -> GridMap {
+        let mut map = GridMap::new(5, 3);
+        for x in 0..5 {
+            map.set_obstacle(x, 0, x != 2);
+            map.set_obstacle(x, 2, true);
+        }
+        map
+    }
+
+    // ==================== CONFLICTS ====================
+
+    #[test]
+    fn test_find_vertex_conflict() {
+        let paths = alloc::vec![
+            alloc::vec![(0, 0), (1, 0), (2, 0)],
+            alloc::vec![(1, 1), (1, 0)],
+        ];
+        assert_eq!(
+            find_conflicts(&paths),
+            alloc::vec![Conflict::Vertex {
+                a: 0,
+                b: 1,
+                cell: (1, 0),
+                time: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_edge_conflict() {
+        let paths = alloc::vec![alloc::vec![(0, 0), (1, 0)], alloc::vec![(1, 0), (0, 0)]];
+        assert_eq!(
+            find_conflicts(&paths),
+            alloc::vec![Conflict::Edge {
+                a: 0,
+                b: 1,
+                from: (0, 0),
+                to: (1, 0),
+                time: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_conflict_with_agent_waiting_on_goal() {
+        // Agent 0 stops at (1, 0); agent 1 passes through later
+        let paths = alloc::vec![
+            alloc::vec![(0, 0), (1, 0)],
+            alloc::vec![(3, 0), (3, 0), (2, 0), (1, 0), (0, 0)],
+        ];
+        assert_eq!(position(&paths[0], 10), Some((1, 0)));
+        let conflicts = find_conflicts(&paths);
+        assert_eq!(conflicts.len(), 1);
+        assert!(matches!(conflicts[0], Conflict::Vertex { time: 3, .. }));
+    }
+
+    #[test]
+    fn test_empty_paths_conflict_with_nothing() {
+        assert_eq!(position(&[], 0), None);
+        let paths = alloc::vec![
+            alloc::vec![],
+            alloc::vec![(0, 0), (1, 0)],
+            alloc::vec![],
+            alloc::vec![(1, 0), (0, 0)],
+        ];
+        let conflicts = find_conflicts(&paths);
+        assert_eq!(conflicts.len(), 1);
+        assert!(matches!(conflicts[0], Conflict::Edge { a: 1, b: 3, .. }));
+    }
+
+    // ==================== CBS ====================
+
+    #[test]
+    fn test_single_agent_is_shortest_path() {
+        let mut map = GridMap::new(6, 6);
+        for y in 0..5 {
+            map.set_obstacle(3, y, true);
+        }
+        let agents = [((0, 0), (5, 0))];
+        let mut cbs = Cbs::new();
+        let solution = cbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &solution);
+        assert_eq!(solution.cost, 15);
+        assert_eq!(cbs.stats.high_level_expanded, 1);
+    }
+
+    #[test]
+    fn test_independent_agents_need_no_splitting() {
+        let map = GridMap::new(8, 8);
+        let agents = [((0, 0), (7, 0)), ((0, 7), (7, 7))];
+        let mut cbs = Cbs::new();
+        let solution = cbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &solution);
+        assert_eq!(solution.cost, 14);
+        assert_eq!(solution.makespan, 7);
+        assert_eq!(cbs.stats.root_conflicts, 0);
+        assert!(solution.constraints.is_empty());
+    }
+
+    #[test]
+    fn test_swap_in_corridor_uses_bay() {
+        let map = corridor_with_bay();
+        let agents = [((0, 1), (4, 1)), ((4, 1), (0, 1))];
+        let mut cbs = Cbs::new();
+        let solution = cbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &solution);
+        assert_eq!(solution.cost, 11);
+        assert!(solution.paths.iter().any(|p| p.contains(&(2, 0))));
+        assert!(cbs.stats.root_conflicts > 0);
+        assert!(cbs.stats.high_level_expanded > 1);
+        assert!(!solution.constraints.is_empty());
+    }
+
+    #[test]
+    fn test_agent_must_leave_goal_free() {
+        // Agent 0's goal is in the middle of agent 1's corridor
+        let map = corridor_with_bay();
+        let agents = [((1, 1), (2, 1)), ((0, 1), (4, 1))];
+        let mut cbs = Cbs::new();
+        let solution = cbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &solution);
+    }
+
+    #[test]
+    fn test_unsolvable_swap_hits_budget() {
+        let mut map = GridMap::new(4, 1);
+        map.set_obstacle(3, 0, true);
+        let agents = [((0, 0), (2, 0)), ((2, 0), (0, 0))];
+        let mut cbs = Cbs::new();
+        cbs.max_nodes = 200;
+        assert!(cbs.solve(&map, &agents).is_none());
+        assert_eq!(cbs.stats.high_level_expanded, 200);
+    }
+
+    #[test]
+    fn test_blocked_goal_is_none() {
+        let mut map = GridMap::new(4, 4);
+        map.set_obstacle(3, 3, true);
+        let mut cbs = Cbs::new();
+        assert!(cbs.solve(&map, &[((0, 0), (3, 3))]).is_none());
+    }
+
+    #[test]
+    fn test_eight_connected() {
+        let map = GridMap::new(5, 5);
+        let agents = [((0, 0), (4, 4)), ((4, 0), (0, 4)), ((0, 4), (4, 0))];
+        let mut cbs = Cbs::new();
+        cbs.connectivity = Connectivity::Eight;
+        let solution = cbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &solution);
+        assert!(solution.makespan <= 6);
+    }
+
+    // ==================== ECBS ====================
+
+    #[test]
+    fn test_ecbs_within_bound() {
+        // Four agents crossing a small room
+        let map = GridMap::new(6, 6);
+        let agents = [
+            ((0, 2), (5, 2)),
+            ((2, 0), (2, 5)),
+            ((5, 3), (0, 3)),
+            ((3, 5), (3, 0)),
+        ];
+
+        let mut cbs = Cbs::new();
+        let optimal = cbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &optimal);
+
+        let mut ecbs = Cbs::ecbs(1.5);
+        let bounded = ecbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &bounded);
+        assert!(bounded.cost >= optimal.cost);
+        assert!(bounded.cost as f32 <= 1.5 * optimal.cost as f32);
+        assert!(ecbs.stats.high_level_expanded <= cbs.stats.high_level_expanded);
+    }
+
+    #[test]
+    fn test_ecbs_resolves_crossing_quickly() {
+        // Both shortest paths must cross, which plain CBS only proves by
+        // exhausting every equal-cost split
+        let map = GridMap::new(10, 10);
+        let agents = [((0, 1), (9, 8)), ((1, 0), (8, 9))];
+        let mut ecbs = Cbs::ecbs(1.2);
+        let solution = ecbs.solve(&map, &agents).unwrap();
+        assert_valid(&map, &agents, &solution);
+        assert_eq!(solution.cost, 33);
+        assert!(ecbs.stats.high_level_expanded < 10);
+
+        let mut cbs = Cbs::new();
+        cbs.max_nodes = 100;
+        assert!(cbs.solve(&map, &agents).is_none());
+    }
+
+    #[test]
+    fn test_ecbs_many_agents() {
+        let mut map = GridMap::new(12, 12);
+        for &(x, y) in &[(3, 3), (3, 4), (8, 7), (8, 8), (5, 9), (6, 2)] {
+            map.set_obstacle(x, y, true);
+        }
+        // Each agent swaps to the mirrored cell
+        let agents: Vec<(Cell, Cell)> = (0..12)
+            .map(|i| ((0, i), (11, 11 - i)))
+            .chain((1..11).map(|i| ((i, 0), (11 - i, 11))))
+            .collect();
+        let mut ecbs = Cbs::ecbs(2.0);
+        let solution = ecbs
+            .solve(&map, &agents)
+            .expect("ECBS should solve 22 agents");
+        assert_valid(&map, &agents, &solution);
+        assert!(ecbs.stats.low_level_expanded > 0);
+    }
+
+    #[test]
+    fn test_ecbs_factor_clamped() {
+        assert_eq!(Cbs::ecbs(0.5).suboptimality, 1.0);
+    }
+}
@@ -0,0 +1,324 @@
+use crate::dstar::GridMap;
+use crate::math::Vec2;
+use alloc::vec::Vec;
+
+/// Collision queries for sampling-based planners in continuous space.
+pub trait CollisionChecker {
+    fn is_free(&self, p: Vec2) -> bool;
+
+    /// True if the whole straight segment from `a` to `b` is free.
+    fn segment_free(&self, a: Vec2, b: Vec2) -> bool;
+}
+
+/// A `GridMap` is a world with one unit per cell: point (x, y) lies in cell
+/// (floor(x), floor(y)). Segments are checked cell by cell (Amanatides-Woo).
+impl CollisionChecker for GridMap {
+    fn is_free(&self, p: Vec2) -> bool {
+        p.x >= 0.0 && p.y >= 0.0 && !self.is_obstacle(p.x as usize, p.y as usize)
+    }
+
+    fn segment_free(&self, a: Vec2, b: Vec2) -> bool {
+        if !self.is_free(a) || !self.is_free(b) {
+            return false;
+        }
+        let dir = b - a;
+        let (mut cx, mut cy) = (a.x as i64, a.y as i64);
+        let end = (b.x as i64, b.y as i64);
+        let step_x: i64 = if dir.x > 0.0 { 1 } else { -1 };
+        let step_y: i64 = if dir.y > 0.0 { 1 } else { -1 };
+
+        // Parametric distance to the next vertical / horizontal cell edge
+        let boundary = |c: i64, step: i64| (c + if step > 0 { 1 } else { 0 }) as f32;
+        let mut t_max_x = if dir.x != 0.0 {
+            (boundary(cx, step_x) - a.x) / dir.x
+        } else {
+            f32::INFINITY
+        };
+        let mut t_max_y = if dir.y != 0.0 {
+            (boundary(cy, step_y) - a.y) / dir.y
+        } else {
+            f32::INFINITY
+        };
+        let t_delta_x = if dir.x != 0.0 {
+            1.0 / dir.x.abs()
+        } else {
+            f32::INFINITY
+        };
+        let t_delta_y = if dir.y != 0.0 {
+            1.0 / dir.y.abs()
+        } else {
+            f32::INFINITY
+        };
+
+        let steps = (end.0 - cx).abs() + (end.1 - cy).abs();
+        for _ in 0..steps {
+            if t_max_x < t_max_y {
+                cx += step_x;
+                t_max_x += t_delta_x;
+            } else {
+                cy += step_y;
+                t_max_y += t_delta_y;
+            }
+            if cx < 0 || cy < 0 || self.is_obstacle(cx as usize, cy as usize) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, p: Vec2) -> bool {
+        p.distance_sq(&self.center) < self.radius * self.radius
+    }
+
+    pub fn intersects_segment(&self, a: Vec2, b: Vec2) -> bool {
+        segment_point_distance(a, b, self.center) < self.radius
+    }
+}
+
+/// Simple polygon given by its vertices in order (either winding).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub vertices: Vec<Vec2>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Vec2>) -> Self {
+        Self { vertices }
+    }
+
+    /// Axis-aligned rectangle from its min and max corners.
+    pub fn rect(min: Vec2, max: Vec2) -> Self {
+        Self::new(alloc::vec![
+            min,
+            Vec2::new(max.x, min.y),
+            max,
+            Vec2::new(min.x, max.y),
+        ])
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Strict interior test (even-odd ray casting).
+    pub fn contains(&self, p: Vec2) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a.y > p.y) != (b.y > p.y) {
+                let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if p.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    pub fn intersects_segment(&self, a: Vec2, b: Vec2) -> bool {
+        self.contains(a)
+            || self.contains(b)
+            || self.edges().any(|(p, q)| segments_intersect(a, b, p, q))
+    }
+}
+
+/// Shortest distance from `p` to the segment `a`-`b`.
+pub fn segment_point_distance(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.mag_sq();
+    let t = if len_sq > 0.0 {
+        ((p - a).dot(&ab) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    p.distance(&(a + ab * t))
+}
+
+/// True if segments `a`-`b` and `c`-`d` share at least one point.
+pub fn segments_intersect(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let d1 = (b - a).cross(&(c - a));
+    let d2 = (b - a).cross(&(d - a));
+    let d3 = (d - c).cross(&(a - c));
+    let d4 = (d - c).cross(&(b - c));
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    let on_segment = |p: Vec2, q: Vec2, r: Vec2| {
+        r.x >= p.x.min(q.x) && r.x <= p.x.max(q.x) && r.y >= p.y.min(q.y) && r.y <= p.y.max(q.y)
+    };
+    (d1 == 0.0 && on_segment(a, b, c))
+        || (d2 == 0.0 && on_segment(a, b, d))
+        || (d3 == 0.0 && on_segment(c, d, a))
+        || (d4 == 0.0 && on_segment(c, d, b))
+}
+
+/// A continuous world made of circle and polygon obstacles.
+#[derive(Clone, Debug, Default)]
+pub struct ObstacleSet {
+    pub circles: Vec<Circle>,
+    pub polygons: Vec<Polygon>,
+}
+
+impl ObstacleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CollisionChecker for ObstacleSet {
+    fn is_free(&self, p: Vec2) -> bool {
+        !self.circles.iter().any(|c| c.contains(p))
+            && !self.polygons.iter().any(|poly| poly.contains(p))
+    }
+
+    fn segment_free(&self, a: Vec2, b: Vec2) -> bool {
+        !self.circles.iter().any(|c| c.intersects_segment(a, b))
+            && !self
+                .polygons
+                .iter()
+                .any(|poly| poly.intersects_segment(a, b))
+    }
+}
+
+/// A rectangular world with polygon obstacles, for planners that work on
+/// the obstacle geometry itself (visibility graphs, navigation meshes).
+///
+/// Unlike [`ObstacleSet`], obstacle boundaries count as free: a segment may
+/// run along an edge or touch a vertex, only the interiors are blocked.
+/// Obstacles should be simple polygons that do not overlap. Because touching
+/// boundaries leave a zero-width gap, a wall meant to close off part of the
+/// world should overlap the bounds rather than end on them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolygonWorld {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub obstacles: Vec<Polygon>,
+}
+
+// Length tolerance for boundary contact
+const TOUCH_EPS: f32 = 1e-4;
+
+impl PolygonWorld {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self {
+            min,
+            max,
+            obstacles: Vec::new(),
+        }
+    }
+
+    pub fn with_obstacles(min: Vec2, max: Vec2, obstacles: Vec<Polygon>) -> Self {
+        Self {
+            min,
+            max,
+            obstacles,
+        }
+    }
+
+    pub fn in_bounds(&self, p: Vec2) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Corners of the bounds, every obstacle vertex and the points where
+    /// obstacle edges cross the bounds: the corners of the free space, plus
+    /// vertices outside it.
+    pub fn vertices(&self) -> Vec<Vec2> {
+        let bounds = Polygon::rect(self.min, self.max);
+        let mut out = bounds.vertices.clone();
+        for poly in &self.obstacles {
+            out.extend_from_slice(&poly.vertices);
+            for (p, q) in poly.edges() {
+                for (a, b) in bounds.edges() {
+                    let denom = (q - p).cross(&(b - a));
+                    if denom != 0.0 && segments_intersect(p, q, a, b) {
+                        out.push(p + (q - p) * ((a - p).cross(&(b - a)) / denom));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn on_boundary(poly: &Polygon, p: Vec2) -> bool {
+    poly.edges()
+        .any(|(a, b)| segment_point_distance(a, b, p) < TOUCH_EPS)
+}
+
+// True if the segment a-b enters the interior of the polygon. The segment is
+// split wherever it touches the boundary and each piece's midpoint tested,
+// so grazing a vertex or sliding along an edge is allowed.
+fn crosses_interior(poly: &Polygon, a: Vec2, b: Vec2) -> bool {
+    let d = b - a;
+    let len = d.mag();
+    if len < TOUCH_EPS {
+        return poly.contains(a) && !on_boundary(poly, a);
+    }
+    let t_eps = TOUCH_EPS / len;
+    let mut cuts = alloc::vec![0.0, 1.0];
+    for (p, q) in poly.edges() {
+        let e = q - p;
+        let denom = d.cross(&e);
+        let ap = p - a;
+        if denom.abs() > TOUCH_EPS * len * e.mag() {
+            let t = ap.cross(&e) / denom;
+            let u = ap.cross(&d) / denom;
+            let u_eps = TOUCH_EPS / e.mag();
+            if t < -t_eps || t > 1.0 + t_eps || u < -u_eps || u > 1.0 + u_eps {
+                continue;
+            }
+            if t > t_eps && t < 1.0 - t_eps && u > u_eps && u < 1.0 - u_eps {
+                // Crosses an edge away from its vertices
+                return true;
+            }
+            cuts.push(t.clamp(0.0, 1.0));
+        } else if ap.cross(&d).abs() <= TOUCH_EPS * len {
+            // Collinear: the overlap ends split the segment
+            for v in [p, q] {
+                cuts.push(((v - a).dot(&d) / (len * len)).clamp(0.0, 1.0));
+            }
+        }
+    }
+    cuts.sort_by(|x, y| x.total_cmp(y));
+    cuts.windows(2).any(|w| {
+        let m = a + d * ((w[0] + w[1]) * 0.5);
+        w[1] - w[0] > t_eps && poly.contains(m) && !on_boundary(poly, m)
+    })
+}
+
+impl CollisionChecker for PolygonWorld {
+    fn is_free(&self, p: Vec2) -> bool {
+        self.in_bounds(p)
+            && !self
+                .obstacles
+                .iter()
+                .any(|poly| poly.contains(p) && !on_boundary(poly, p))
+    }
+
+    fn segment_free(&self, a: Vec2, b: Vec2) -> bool {
+        self.is_free(a)
+            && self.is_free(b)
+            && !self
+                .obstacles
+                .iter()
+                .any(|poly| crosses_interior(poly, a, b))
+    }
+}
+
+#[cfg(test)]
+#[path = "collision_tests.rs"]
+mod tests;
@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::{
+        segment_point_distance, segments_intersect, Circle, CollisionChecker, ObstacleSet, Polygon,
+        PolygonWorld,
+    };
+    use crate::dstar::GridMap;
+    use crate::math::Vec2;
+
+    // ==================== GRIDMAP ====================
+
+    #[test]
+    fn test_gridmap_point_queries() {
+        let mut map = GridMap::new(10, 10);
+        map.set_obstacle(3, 4, true);
+        assert!(!map.is_free(Vec2::new(3.5, 4.2)));
+        assert!(map.is_free(Vec2::new(2.9, 4.2)));
+        assert!(!map.is_free(Vec2::new(-0.5, 1.0)));
+        assert!(!map.is_free(Vec2::new(10.5, 1.0)));
+    }
+
+    #[test]
+    fn test_gridmap_segment_crossing_wall() {
+        let mut map = GridMap::new(10, 10);
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        assert!(!map.segment_free(Vec2::new(1.5, 1.5), Vec2::new(8.5, 7.5)));
+        assert!(map.segment_free(Vec2::new(1.5, 1.5), Vec2::new(4.5, 9.5)));
+    }
+
+    #[test]
+    fn test_gridmap_segment_grazing_cell() {
+        let mut map = GridMap::new(10, 10);
+        map.set_obstacle(2, 1, true);
+        // Passes through cell (2, 1) only briefly
+        assert!(!map.segment_free(Vec2::new(0.5, 0.5), Vec2::new(4.5, 1.6)));
+        assert!(map.segment_free(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.9)));
+    }
+
+    #[test]
+    fn test_gridmap_segment_axis_aligned_and_reverse() {
+        let mut map = GridMap::new(10, 10);
+        map.set_obstacle(4, 2, true);
+        assert!(!map.segment_free(Vec2::new(8.5, 2.5), Vec2::new(0.5, 2.5)));
+        assert!(map.segment_free(Vec2::new(8.5, 3.5), Vec2::new(0.5, 3.5)));
+        assert!(map.segment_free(Vec2::new(1.5, 1.5), Vec2::new(1.5, 1.5)));
+    }
+
+    // ==================== PRIMITIVES ====================
+
+    #[test]
+    fn test_segment_point_distance() {
+        let a = Vec2::new(0.0, 0.0);
+        let b = Vec2::new(10.0, 0.0);
+        assert!((segment_point_distance(a, b, Vec2::new(5.0, 3.0)) - 3.0).abs() < 1e-6);
+        assert!((segment_point_distance(a, b, Vec2::new(-4.0, 3.0)) - 5.0).abs() < 1e-6);
+        assert!((segment_point_distance(a, a, Vec2::new(3.0, 4.0)) - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_segments_intersect() {
+        let o = Vec2::zero();
+        assert!(segments_intersect(
+            o,
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(2.0, 0.0)
+        ));
+        assert!(!segments_intersect(
+            o,
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(1.0, 1.0)
+        ));
+        // Touching at an endpoint counts
+        assert!(segments_intersect(
+            o,
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn test_circle() {
+        let c = Circle::new(Vec2::new(5.0, 5.0), 2.0);
+        assert!(c.contains(Vec2::new(6.0, 5.0)));
+        assert!(!c.contains(Vec2::new(8.0, 5.0)));
+        assert!(c.intersects_segment(Vec2::new(0.0, 5.5), Vec2::new(10.0, 5.5)));
+        assert!(!c.intersects_segment(Vec2::new(0.0, 8.0), Vec2::new(10.0, 8.0)));
+    }
+
+    #[test]
+    fn test_polygon_contains_and_segments() {
+        let square = Polygon::rect(Vec2::new(2.0, 2.0), Vec2::new(4.0, 4.0));
+        assert!(square.contains(Vec2::new(3.0, 3.0)));
+        assert!(!square.contains(Vec2::new(5.0, 3.0)));
+        assert!(square.intersects_segment(Vec2::new(0.0, 3.0), Vec2::new(6.0, 3.0)));
+        assert!(!square.intersects_segment(Vec2::new(0.0, 5.0), Vec2::new(6.0, 5.0)));
+        // Fully inside
+        assert!(square.intersects_segment(Vec2::new(2.5, 2.5), Vec2::new(3.5, 3.5)));
+    }
+
+    #[test]
+    fn test_polygon_concave() {
+        // L shape
+        let l = Polygon::new(alloc::vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        assert!(l.contains(Vec2::new(0.5, 3.0)));
+        assert!(l.contains(Vec2::new(3.0, 0.5)));
+        assert!(!l.contains(Vec2::new(2.0, 2.0)));
+    }
+
+    // ==================== OBSTACLE SET ====================
+
+    #[test]
+    fn test_obstacle_set() {
+        let mut world = ObstacleSet::new();
+        world.circles.push(Circle::new(Vec2::new(2.0, 2.0), 1.0));
+        world
+            .polygons
+            .push(Polygon::rect(Vec2::new(6.0, 0.0), Vec2::new(7.0, 5.0)));
+        assert!(!world.is_free(Vec2::new(2.0, 2.5)));
+        assert!(!world.is_free(Vec2::new(6.5, 2.0)));
+        assert!(world.is_free(Vec2::new(4.0, 4.0)));
+        assert!(!world.segment_free(Vec2::new(4.0, 2.0), Vec2::new(9.0, 2.0)));
+        assert!(world.segment_free(Vec2::new(4.0, 6.0), Vec2::new(9.0, 6.0)));
+    }
+
+    // ==================== POLYGON WORLD ====================
+
+    #[test]
+    fn test_polygon_world_boundaries_are_free() {
+        let square = Polygon::rect(Vec2::new(4.0, 4.0), Vec2::new(6.0, 6.0));
+        let world = PolygonWorld::with_obstacles(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            alloc::vec![square],
+        );
+        assert!(world.is_free(Vec2::new(4.0, 5.0)));
+        assert!(!world.is_free(Vec2::new(5.0, 5.0)));
+        assert!(!world.is_free(Vec2::new(11.0, 5.0)));
+        // Along an edge, touching a corner, and along the top edge
+        assert!(world.segment_free(Vec2::new(2.0, 4.0), Vec2::new(8.0, 4.0)));
+        assert!(world.segment_free(Vec2::new(3.0, 5.0), Vec2::new(5.0, 7.0)));
+        assert!(world.segment_free(Vec2::new(4.0, 6.0), Vec2::new(6.0, 6.0)));
+        // The diagonal runs through the interior
+        assert!(!world.segment_free(Vec2::new(4.0, 4.0), Vec2::new(6.0, 6.0)));
+        assert!(!world.segment_free(Vec2::new(2.0, 5.0), Vec2::new(8.0, 5.0)));
+    }
+
+    #[test]
+    fn test_polygon_world_concave_obstacle() {
+        let l = Polygon::new(alloc::vec![
+            Vec2::new(2.0, 2.0),
+            Vec2::new(6.0, 2.0),
+            Vec2::new(6.0, 3.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(3.0, 6.0),
+            Vec2::new(2.0, 6.0),
+        ]);
+        let world =
+            PolygonWorld::with_obstacles(Vec2::new(0.0, 0.0), Vec2::new(8.0, 8.0), alloc::vec![l]);
+        // Across the notch between the arms, and between the arm tips
+        assert!(world.segment_free(Vec2::new(4.0, 4.0), Vec2::new(5.0, 5.0)));
+        assert!(world.segment_free(Vec2::new(6.0, 3.0), Vec2::new(3.0, 6.0)));
+        // Between two vertices through the solid corner
+        assert!(!world.segment_free(Vec2::new(6.0, 2.0), Vec2::new(2.0, 6.0)));
+    }
+
+    #[test]
+    fn test_polygon_world_vertices_include_bound_crossings() {
+        let wall = Polygon::rect(Vec2::new(4.0, -1.0), Vec2::new(6.0, 5.0));
+        let world = PolygonWorld::with_obstacles(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            alloc::vec![wall],
+        );
+        let vertices = world.vertices();
+        assert!(vertices.contains(&Vec2::new(4.0, 0.0)));
+        assert!(vertices.contains(&Vec2::new(6.0, 0.0)));
+        assert_eq!(vertices.iter().filter(|&&v| world.is_free(v)).count(), 8);
+        // The wall closes the bottom edge
+        assert!(!world.segment_free(Vec2::new(1.0, 0.0), Vec2::new(9.0, 0.0)));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::dstar::{GridMap, FREE};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub const STATIC_LAYER: &str = "static";
+pub const INFLATION_LAYER: &str = "inflation";
+pub const DYNAMIC_LAYER: &str = "dynamic";
+
+/// One named source of cell costs, e.g. the static map or sensed obstacles.
+#[derive(Clone, Debug)]
+pub struct CostLayer {
+    pub name: String,
+    pub costs: Vec<u8>,
+    pub enabled: bool,
+}
+
+/// Stack of cost layers combined by taking the maximum per cell.
+///
+/// The combined result is kept in `master`, which planners consume directly.
+/// Single-cell edits only recombine that cell.
+#[derive(Clone, Debug)]
+pub struct LayeredCostmap {
+    layers: Vec<CostLayer>,
+    master: GridMap,
+}
+
+impl LayeredCostmap {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            layers: Vec::new(),
+            master: GridMap::new(width, height),
+        }
+    }
+
+    /// Costmap with empty static, inflation and dynamic layers.
+    pub fn standard(width: usize, height: usize) -> Self {
+        let mut costmap = Self::new(width, height);
+        costmap.add_layer(STATIC_LAYER);
+        costmap.add_layer(INFLATION_LAYER);
+        costmap.add_layer(DYNAMIC_LAYER);
+        costmap
+    }
+
+    pub fn width(&self) -> usize {
+        self.master.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.master.height
+    }
+
+    /// Add an empty layer on top of the stack. Returns false if the name is taken.
+    pub fn add_layer(&mut self, name: &str) -> bool {
+        if self.layer(name).is_some() {
+            return false;
+        }
+        self.layers.push(CostLayer {
+            name: String::from(name),
+            costs: alloc::vec![FREE; self.master.costs.len()],
+            enabled: true,
+        });
+        true
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<CostLayer> {
+        let pos = self.layers.iter().position(|l| l.name == name)?;
+        let layer = self.layers.remove(pos);
+        self.recombine();
+        Some(layer)
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&CostLayer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    pub fn layers(&self) -> &[CostLayer] {
+        &self.layers
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(layer) = self.layers.iter_mut().find(|l| l.name == name) {
+            if layer.enabled != enabled {
+                layer.enabled = enabled;
+                self.recombine();
+            }
+        }
+    }
+
+    /// Set one cell of a layer and update the combined map.
+    pub fn set_cost(&mut self, name: &str, x: usize, y: usize, cost: u8) {
+        if x >= self.width() || y >= self.height() {
+            return;
+        }
+        let i = self.master.index(x, y);
+        if let Some(layer) = self.layers.iter_mut().find(|l| l.name == name) {
+            layer.costs[i] = cost;
+            self.recombine_cell(i);
+        }
+    }
+
+    /// Replace a whole layer, e.g. after recomputing inflation.
+    pub fn set_layer(&mut self, name: &str, costs: &[u8]) {
+        if costs.len() != self.master.costs.len() {
+            return;
+        }
+        if let Some(layer) = self.layers.iter_mut().find(|l| l.name == name) {
+            layer.costs.copy_from_slice(costs);
+            self.recombine();
+        }
+    }
+
+    /// Cost of a cell in one layer; `None` for unknown layers or out of bounds.
+    pub fn layer_cost(&self, name: &str, x: usize, y: usize) -> Option<u8> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let i = self.master.index(x, y);
+        self.layer(name).map(|l| l.costs[i])
+    }
+
+    /// Combined cost of a cell.
+    pub fn cost(&self, x: usize, y: usize) -> u8 {
+        self.master.cost(x, y)
+    }
+
+    /// Combined map for planning.
+    pub fn master(&self) -> &GridMap {
+        &self.master
+    }
+
+    fn recombine_cell(&mut self, i: usize) {
+        self.master.costs[i] = self
+            .layers
+            .iter()
+            .filter(|l| l.enabled)
+            .map(|l| l.costs[i])
+            .max()
+            .unwrap_or(FREE);
+    }
+
+    fn recombine(&mut self) {
+        for i in 0..self.master.costs.len() {
+            self.recombine_cell(i);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "costmap_tests.rs"]
+mod tests;
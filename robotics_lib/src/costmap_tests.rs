@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use crate::costmap::{LayeredCostmap, DYNAMIC_LAYER, INFLATION_LAYER, STATIC_LAYER};
+    use crate::dstar::{FREE, LETHAL};
+
+    #[test]
+    fn test_standard_layers() {
+        let costmap = LayeredCostmap::standard(10, 8);
+        let names: alloc::vec::Vec<&str> =
+            costmap.layers().iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, [STATIC_LAYER, INFLATION_LAYER, DYNAMIC_LAYER]);
+        assert_eq!(costmap.width(), 10);
+        assert_eq!(costmap.height(), 8);
+        assert_eq!(costmap.cost(3, 3), FREE);
+    }
+
+    #[test]
+    fn test_add_duplicate_layer() {
+        let mut costmap = LayeredCostmap::new(5, 5);
+        assert!(costmap.add_layer("terrain"));
+        assert!(!costmap.add_layer("terrain"));
+        assert_eq!(costmap.layers().len(), 1);
+    }
+
+    #[test]
+    fn test_combined_cost_is_max() {
+        let mut costmap = LayeredCostmap::standard(5, 5);
+        costmap.set_cost(STATIC_LAYER, 2, 2, 40);
+        costmap.set_cost(INFLATION_LAYER, 2, 2, 120);
+        costmap.set_cost(DYNAMIC_LAYER, 2, 2, 10);
+        assert_eq!(costmap.cost(2, 2), 120);
+        assert_eq!(costmap.master().cost(2, 2), 120);
+        assert_eq!(costmap.layer_cost(STATIC_LAYER, 2, 2), Some(40));
+    }
+
+    #[test]
+    fn test_lowering_a_layer_restores_others() {
+        let mut costmap = LayeredCostmap::standard(5, 5);
+        costmap.set_cost(STATIC_LAYER, 1, 1, 30);
+        costmap.set_cost(DYNAMIC_LAYER, 1, 1, LETHAL);
+        assert!(costmap.master().is_obstacle(1, 1));
+        costmap.set_cost(DYNAMIC_LAYER, 1, 1, FREE);
+        assert_eq!(costmap.cost(1, 1), 30);
+    }
+
+    #[test]
+    fn test_disable_and_remove_layer() {
+        let mut costmap = LayeredCostmap::standard(5, 5);
+        costmap.set_cost(DYNAMIC_LAYER, 4, 0, LETHAL);
+        costmap.set_enabled(DYNAMIC_LAYER, false);
+        assert_eq!(costmap.cost(4, 0), FREE);
+        costmap.set_enabled(DYNAMIC_LAYER, true);
+        assert_eq!(costmap.cost(4, 0), LETHAL);
+
+        let removed = costmap.remove_layer(DYNAMIC_LAYER).unwrap();
+        assert_eq!(removed.costs[4], LETHAL);
+        assert_eq!(costmap.cost(4, 0), FREE);
+        assert!(costmap.remove_layer(DYNAMIC_LAYER).is_none());
+    }
+
+    #[test]
+    fn test_set_layer_bulk() {
+        let mut costmap = LayeredCostmap::standard(3, 2);
+        costmap.set_layer(STATIC_LAYER, &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(costmap.cost(2, 1), 6);
+        // Wrong size is ignored
+        costmap.set_layer(STATIC_LAYER, &[9; 4]);
+        assert_eq!(costmap.cost(0, 0), 1);
+    }
+
+    #[test]
+    fn test_out_of_bounds_and_unknown_layer() {
+        let mut costmap = LayeredCostmap::standard(5, 5);
+        costmap.set_cost(STATIC_LAYER, 10, 10, LETHAL);
+        costmap.set_cost("missing", 1, 1, LETHAL);
+        assert_eq!(costmap.cost(1, 1), FREE);
+        assert_eq!(costmap.cost(10, 10), LETHAL);
+        assert_eq!(costmap.layer_cost("missing", 1, 1), None);
+        assert_eq!(costmap.layer_cost(STATIC_LAYER, 10, 1), None);
+    }
+}
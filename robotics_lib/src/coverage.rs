@@ -0,0 +1,403 @@
+use crate::dstar::{octile_distance, GridMap, NEIGHBORS_8};
+use crate::grid_search::GridSearch;
+use alloc::vec::Vec;
+
+type Cell = (usize, usize);
+
+/// A cell of the boustrophedon decomposition: a region a vertical sweep
+/// line crosses without its free interval splitting or merging, so it can
+/// be covered by simple back-and-forth strokes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoustrophedonCell {
+    /// One free run per column as `(x, y_min, y_max)`, bounds inclusive,
+    /// for consecutive x from left to right.
+    pub columns: Vec<(usize, usize, usize)>,
+}
+
+impl BoustrophedonCell {
+    /// Number of grid cells in the region.
+    pub fn area(&self) -> usize {
+        self.columns.iter().map(|&(_, y0, y1)| y1 - y0 + 1).sum()
+    }
+}
+
+// Maximal free runs of a column as (y_min, y_max)
+fn runs(map: &GridMap, x: usize) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut y = 0;
+    while y < map.height {
+        if map.is_obstacle(x, y) {
+            y += 1;
+            continue;
+        }
+        let y0 = y;
+        while y + 1 < map.height && !map.is_obstacle(x, y + 1) {
+            y += 1;
+        }
+        out.push((y0, y));
+        y += 1;
+    }
+    out
+}
+
+fn overlaps(a: (usize, usize), b: (usize, usize)) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
+}
+
+/// Boustrophedon cellular decomposition of the free space (Choset).
+///
+/// A line sweeps left to right over the columns. A free run continues the
+/// cell of the previous column's run when the two overlap only each other;
+/// wherever runs split or merge around an obstacle (a critical point) a
+/// new cell starts.
+pub fn decompose(map: &GridMap) -> Vec<BoustrophedonCell> {
+    let mut cells: Vec<BoustrophedonCell> = Vec::new();
+    // Runs of the previous column with the cell each belongs to
+    let mut prev: Vec<(usize, usize, usize)> = Vec::new();
+    for x in 0..map.width {
+        let current = runs(map, x);
+        let mut next = Vec::with_capacity(current.len());
+        for &run in &current {
+            let mut touching = prev.iter().filter(|p| overlaps((p.0, p.1), run));
+            let cell = match (touching.next(), touching.next()) {
+                (Some(&(y0, y1, cell)), None)
+                    if current.iter().filter(|&&r| overlaps((y0, y1), r)).count() == 1 =>
+                {
+                    cell
+                }
+                _ => {
+                    cells.push(BoustrophedonCell {
+                        columns: Vec::new(),
+                    });
+                    cells.len() - 1
+                }
+            };
+            cells[cell].columns.push((x, run.0, run.1));
+            next.push((run.0, run.1, cell));
+        }
+        prev = next;
+    }
+    cells
+}
+
+/// A complete-coverage path and how well it covers the map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoveragePath {
+    /// Cells in driving order, each adjacent (8-connected) to the last.
+    pub cells: Vec<Cell>,
+    /// Travel distance in cells.
+    pub length: f32,
+    /// Fraction of the free cells reachable from the start that the tool
+    /// passes over, 1.0 for full coverage.
+    pub coverage: f32,
+    /// Area swept beyond the area covered, as a fraction of it: 0.0 when no
+    /// cell is passed over twice.
+    pub overlap: f32,
+}
+
+// Free cells under a square tool `width` cells wide centred on a cell
+fn footprint(map: &GridMap, cell: Cell, width: usize) -> impl Iterator<Item = Cell> + '_ {
+    let x0 = cell.0.saturating_sub((width - 1) / 2);
+    let y0 = cell.1.saturating_sub((width - 1) / 2);
+    let x1 = (cell.0 + width / 2).min(map.width - 1);
+    let y1 = (cell.1 + width / 2).min(map.height - 1);
+    (y0..=y1)
+        .flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+        .filter(|&(x, y)| !map.is_obstacle(x, y))
+}
+
+fn reachable(map: &GridMap, start: Cell) -> Vec<bool> {
+    let mut seen = alloc::vec![false; map.width * map.height];
+    if map.is_obstacle(start.0, start.1) {
+        return seen;
+    }
+    seen[map.index(start.0, start.1)] = true;
+    let mut stack = alloc::vec![start];
+    while let Some((x, y)) = stack.pop() {
+        for (next, _) in map.neighbors(x, y) {
+            let i = map.index(next.0, next.1);
+            if !seen[i] {
+                seen[i] = true;
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+impl CoveragePath {
+    /// Measure a cell path driven with a square tool `tool_width` cells
+    /// wide. Overlap compares the swept area, `tool_width` times the length
+    /// plus the tool itself, with the covered area.
+    pub fn evaluate(map: &GridMap, cells: Vec<Cell>, tool_width: usize) -> Self {
+        let w = tool_width.max(1);
+        let length = cells
+            .windows(2)
+            .map(|p| octile_distance(p[0], p[1]))
+            .sum::<f32>();
+        let mut covered = alloc::vec![false; map.width * map.height];
+        for &cell in &cells {
+            for (x, y) in footprint(map, cell, w) {
+                covered[map.index(x, y)] = true;
+            }
+        }
+        let area = covered.iter().filter(|&&c| c).count();
+        let (coverage, overlap) = match cells.first() {
+            Some(&start) => {
+                let reach = reachable(map, start);
+                let total = reach.iter().filter(|&&r| r).count();
+                let hit = reach
+                    .iter()
+                    .zip(&covered)
+                    .filter(|&(&r, &c)| r && c)
+                    .count();
+                let swept = w as f32 * (length + w as f32);
+                (
+                    hit as f32 / total.max(1) as f32,
+                    (swept / area.max(1) as f32 - 1.0).max(0.0),
+                )
+            }
+            None => (0.0, 0.0),
+        };
+        Self {
+            cells,
+            length,
+            coverage,
+            overlap,
+        }
+    }
+}
+
+// Stroke columns of a cell for a tool `width` wide: lanes `width` apart,
+// the last one pulled in so the tool still reaches the cell's right edge
+fn lanes(cell: &BoustrophedonCell, width: usize) -> Vec<(usize, usize, usize)> {
+    let x0 = cell.columns[0].0;
+    let x1 = cell.columns[cell.columns.len() - 1].0;
+    let mut out = Vec::new();
+    let mut x = (x0 + (width - 1) / 2).min(x1);
+    loop {
+        out.push(cell.columns[x - x0]);
+        if x + width / 2 >= x1 {
+            return out;
+        }
+        x = (x + width).min(x1 - width / 2);
+    }
+}
+
+// Path under construction with the cells its tool has passed over
+struct Route<'a> {
+    map: &'a GridMap,
+    search: GridSearch,
+    width: usize,
+    cells: Vec<Cell>,
+    covered: Vec<bool>,
+}
+
+impl Route<'_> {
+    fn here(&self) -> Cell {
+        self.cells[self.cells.len() - 1]
+    }
+
+    fn push(&mut self, cell: Cell) {
+        for (x, y) in footprint(self.map, cell, self.width) {
+            self.covered[self.map.index(x, y)] = true;
+        }
+        self.cells.push(cell);
+    }
+
+    // Drive the shortest path to `goal`; false if it cannot be reached
+    fn go_to(&mut self, goal: Cell) -> bool {
+        if goal == self.here() {
+            return true;
+        }
+        match self.search.plan(self.map, self.here(), goal) {
+            Some(path) => {
+                for &cell in &path.cells[1..] {
+                    self.push(cell);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Back-and-forth coverage over the boustrophedon decomposition with a
+/// square tool `tool_width` cells wide.
+///
+/// Each cell is swept with vertical strokes `tool_width` apart, alternating
+/// direction. Cells are visited greedily, entering the nearest unswept cell
+/// from whichever side and end is closest; transfers between strokes and
+/// cells follow A* paths. A wide tool can miss corners of irregular cells,
+/// so any reachable cell still uncovered after the sweeps is visited last,
+/// nearest first. Returns None if the start is blocked.
+pub fn boustrophedon(map: &GridMap, start: Cell, tool_width: usize) -> Option<CoveragePath> {
+    if map.is_obstacle(start.0, start.1) {
+        return None;
+    }
+    let width = tool_width.max(1);
+    let mut route = Route {
+        map,
+        search: GridSearch::default(),
+        width,
+        cells: Vec::new(),
+        covered: alloc::vec![false; map.width * map.height],
+    };
+    route.push(start);
+
+    let mut pending: Vec<Vec<(usize, usize, usize)>> =
+        decompose(map).iter().map(|c| lanes(c, width)).collect();
+    while !pending.is_empty() {
+        let here = route.here();
+        let mut best: Option<(f32, usize, bool)> = None;
+        for (i, cell_lanes) in pending.iter().enumerate() {
+            for reversed in [false, true] {
+                let lane = if reversed {
+                    cell_lanes[cell_lanes.len() - 1]
+                } else {
+                    cell_lanes[0]
+                };
+                let d = octile_distance(here, (lane.0, lane.1))
+                    .min(octile_distance(here, (lane.0, lane.2)));
+                if best.is_none_or(|b| d < b.0) {
+                    best = Some((d, i, reversed));
+                }
+            }
+        }
+        let (_, i, reversed) = best.unwrap();
+        let mut cell_lanes = pending.swap_remove(i);
+        if reversed {
+            cell_lanes.reverse();
+        }
+        for (x, y0, y1) in cell_lanes {
+            // Start each stroke at the end nearer the previous one's finish
+            let y = route.here().1;
+            let (from, to) = if y.abs_diff(y0) <= y.abs_diff(y1) {
+                (y0, y1)
+            } else {
+                (y1, y0)
+            };
+            if !route.go_to((x, from)) {
+                // Not connected to the start
+                break;
+            }
+            if from <= to {
+                (from + 1..=to).for_each(|y| route.push((x, y)));
+            } else {
+                (to..from).rev().for_each(|y| route.push((x, y)));
+            }
+        }
+    }
+
+    loop {
+        let costs = route.search.cost_field(map, route.here());
+        let target = (0..costs.len())
+            .filter(|&i| !route.covered[i] && costs[i].is_finite())
+            .min_by(|&a, &b| costs[a].total_cmp(&costs[b]));
+        let Some(i) = target else {
+            break;
+        };
+        if !route.go_to((i % map.width, i / map.width)) {
+            break;
+        }
+    }
+    Some(CoveragePath::evaluate(map, route.cells, width))
+}
+
+/// Spanning-tree coverage (Gabriely and Rimon) with a one-cell tool.
+///
+/// The map is grouped into 2x2 blocks, usable only when all four cells are
+/// free. A spanning tree is grown over the usable blocks from the start's
+/// block, and the path circumnavigates it: every block starts as a loop
+/// around its four cells, and each tree edge joins two loops into one, so
+/// the result visits every cell of every usable block exactly once with
+/// 4-connected moves. Cells of partly blocked blocks are left out, which
+/// shows up as coverage below one. Returns None if the start's block is not
+/// usable.
+pub fn spanning_tree_coverage(map: &GridMap, start: Cell) -> Option<CoveragePath> {
+    let (bw, bh) = (map.width / 2, map.height / 2);
+    let usable = |bx: usize, by: usize| {
+        bx < bw && by < bh && (0..4).all(|k| !map.is_obstacle(2 * bx + k % 2, 2 * by + k / 2))
+    };
+    let root = (start.0 / 2, start.1 / 2);
+    if !usable(root.0, root.1) {
+        return None;
+    }
+
+    // Block cells in loop order: bottom-left, bottom-right, top-right, top-left
+    let corner = |(bx, by): Cell, k: usize| {
+        let (dx, dy) = [(0, 0), (1, 0), (1, 1), (0, 1)][k];
+        map.index(2 * bx + dx, 2 * by + dy)
+    };
+    let mut links: Vec<Vec<usize>> = alloc::vec![Vec::new(); map.width * map.height];
+    let link = |links: &mut Vec<Vec<usize>>, a: usize, b: usize| {
+        links[a].push(b);
+        links[b].push(a);
+    };
+    let unlink = |links: &mut Vec<Vec<usize>>, a: usize, b: usize| {
+        links[a].retain(|&n| n != b);
+        links[b].retain(|&n| n != a);
+    };
+
+    let mut in_tree = alloc::vec![false; bw * bh];
+    in_tree[root.1 * bw + root.0] = true;
+    let mut stack = alloc::vec![root];
+    let mut blocks = Vec::new();
+    let mut tree = Vec::new();
+    while let Some(block) = stack.pop() {
+        blocks.push(block);
+        for &(dx, dy) in &NEIGHBORS_8[..4] {
+            let (nx, ny) = (block.0 as isize + dx, block.1 as isize + dy);
+            if nx < 0 || ny < 0 || !usable(nx as usize, ny as usize) {
+                continue;
+            }
+            let next = (nx as usize, ny as usize);
+            if !in_tree[next.1 * bw + next.0] {
+                in_tree[next.1 * bw + next.0] = true;
+                // Stored as (lower, upper) with the axis they differ along
+                tree.push(if (dx, dy) < (0, 0) {
+                    (next, block, dx != 0)
+                } else {
+                    (block, next, dx != 0)
+                });
+                stack.push(next);
+            }
+        }
+    }
+
+    for &block in &blocks {
+        for k in 0..4 {
+            link(&mut links, corner(block, k), corner(block, (k + 1) % 4));
+        }
+    }
+    for (a, b, horizontal) in tree {
+        // Replace the two facing sides with two edges across the border
+        let (a_side, b_side) = if horizontal {
+            ((1, 2), (0, 3))
+        } else {
+            ((3, 2), (0, 1))
+        };
+        unlink(&mut links, corner(a, a_side.0), corner(a, a_side.1));
+        unlink(&mut links, corner(b, b_side.0), corner(b, b_side.1));
+        link(&mut links, corner(a, a_side.0), corner(b, b_side.0));
+        link(&mut links, corner(a, a_side.1), corner(b, b_side.1));
+    }
+
+    // Walk the single loop once round from the start
+    let first = map.index(start.0, start.1);
+    let mut cells = Vec::with_capacity(blocks.len() * 4);
+    let (mut prev, mut current) = (usize::MAX, first);
+    loop {
+        cells.push((current % map.width, current / map.width));
+        let next = links[current].iter().copied().find(|&n| n != prev).unwrap();
+        if next == first {
+            break;
+        }
+        (prev, current) = (current, next);
+    }
+    Some(CoveragePath::evaluate(map, cells, 1))
+}
+
+#[cfg(test)]
+#[path = "coverage_tests.rs"]
+mod tests;
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod tests {
+    use crate::coverage::{boustrophedon, decompose, spanning_tree_coverage, CoveragePath};
+    use crate::dstar::GridMap;
+    use crate::mapgen::{random_rects, rooms_and_corridors};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn block(map: &mut GridMap, x0: usize, y0: usize, x1: usize, y1: usize) {
+        for y in y0..y1 {
+            for x in x0..x1 {
+                map.set_obstacle(x, y, true);
+            }
+        }
+    }
+
+    // Every step moves to a free neighbour without cutting a corner
+    fn assert_drivable(map: &GridMap, cells: &[(usize, usize)]) {
+        for &(x, y) in cells {
+            assert!(!map.is_obstacle(x, y), "({}, {}) blocked", x, y);
+        }
+        for w in cells.windows(2) {
+            assert!(
+                map.move_cost(w[0], w[1]).is_finite()
+                    && w[0].0.abs_diff(w[1].0) <= 1
+                    && w[0].1.abs_diff(w[1].1) <= 1,
+                "{:?} -> {:?}",
+                w[0],
+                w[1]
+            );
+        }
+    }
+
+    // ==================== DECOMPOSITION ====================
+
+    #[test]
+    fn test_open_map_is_one_cell() {
+        let map = GridMap::new(12, 8);
+        let cells = decompose(&map);
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].columns.len(), 12);
+        assert_eq!(cells[0].area(), 96);
+    }
+
+    #[test]
+    fn test_obstacle_splits_into_four_cells() {
+        let mut map = GridMap::new(10, 10);
+        block(&mut map, 4, 4, 6, 6);
+        let cells = decompose(&map);
+        // Left of the block, below it, above it and right of it
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells.iter().map(|c| c.area()).sum::<usize>(), 96);
+        assert_eq!(cells[1].columns, vec![(4, 0, 3), (5, 0, 3)]);
+        assert_eq!(cells[2].columns, vec![(4, 6, 9), (5, 6, 9)]);
+    }
+
+    #[test]
+    fn test_cells_partition_free_space() {
+        let generated = random_rects(30, 25, 0.2, 5, 3);
+        let map = &generated.map;
+        let mut seen = vec![0; map.width * map.height];
+        for cell in decompose(map) {
+            for w in cell.columns.windows(2) {
+                assert_eq!(w[0].0 + 1, w[1].0);
+            }
+            for &(x, y0, y1) in &cell.columns {
+                for y in y0..=y1 {
+                    assert!(!map.is_obstacle(x, y));
+                    seen[map.index(x, y)] += 1;
+                }
+            }
+        }
+        for y in 0..map.height {
+            for x in 0..map.width {
+                let expected = if map.is_obstacle(x, y) { 0 } else { 1 };
+                assert_eq!(seen[map.index(x, y)], expected, "({}, {})", x, y);
+            }
+        }
+    }
+
+    // ==================== BOUSTROPHEDON ====================
+
+    #[test]
+    fn test_open_map_sweep_has_no_overlap() {
+        let map = GridMap::new(10, 6);
+        let path = boustrophedon(&map, (0, 0), 1).unwrap();
+        assert_eq!(path.cells.len(), 60);
+        assert_eq!(path.coverage, 1.0);
+        assert_eq!(path.overlap, 0.0);
+        // Back and forth: up the first column, down the second
+        assert_eq!(path.cells[5], (0, 5));
+        assert_eq!(path.cells[6], (1, 5));
+        assert_eq!(path.cells[11], (1, 0));
+        assert_drivable(&map, &path.cells);
+    }
+
+    #[test]
+    fn test_cluttered_maps_fully_covered() {
+        for seed in 0..4 {
+            let generated = random_rects(30, 30, 0.2, 6, seed);
+            let path = boustrophedon(&generated.map, generated.start, 1).unwrap();
+            assert_eq!(path.cells[0], generated.start);
+            assert_eq!(path.coverage, 1.0, "seed {}", seed);
+            assert_drivable(&generated.map, &path.cells);
+        }
+        let generated = rooms_and_corridors(40, 30, 6, 8, 5);
+        let path = boustrophedon(&generated.map, generated.start, 1).unwrap();
+        assert_eq!(path.coverage, 1.0);
+        assert_drivable(&generated.map, &path.cells);
+    }
+
+    #[test]
+    fn test_wide_tool_shortens_path() {
+        let map = GridMap::new(20, 20);
+        let narrow = boustrophedon(&map, (0, 0), 1).unwrap();
+        let wide = boustrophedon(&map, (0, 0), 3).unwrap();
+        assert_eq!(wide.coverage, 1.0);
+        assert!(wide.length < narrow.length / 2.5);
+        assert!(wide.overlap < 0.25, "overlap {}", wide.overlap);
+        // Lanes sit one tool width apart
+        assert!(wide.cells.contains(&(1, 10)) && wide.cells.contains(&(4, 10)));
+        assert!(!wide.cells.contains(&(2, 10)));
+    }
+
+    #[test]
+    fn test_wide_tool_covers_cluttered_map() {
+        let generated = random_rects(30, 30, 0.15, 4, 7);
+        let path = boustrophedon(&generated.map, generated.start, 3).unwrap();
+        assert_eq!(path.coverage, 1.0);
+        assert_drivable(&generated.map, &path.cells);
+    }
+
+    #[test]
+    fn test_unreachable_region_is_not_counted() {
+        let mut map = GridMap::new(12, 12);
+        block(&mut map, 6, 0, 7, 12);
+        let path = boustrophedon(&map, (0, 0), 1).unwrap();
+        assert_eq!(path.coverage, 1.0);
+        assert!(path.cells.iter().all(|&(x, _)| x < 6));
+        assert!(boustrophedon(&map, (6, 3), 1).is_none());
+    }
+
+    // ==================== SPANNING TREE ====================
+
+    #[test]
+    fn test_spanning_tree_visits_every_cell_once() {
+        let mut map = GridMap::new(20, 20);
+        block(&mut map, 6, 6, 10, 12);
+        let path = spanning_tree_coverage(&map, (3, 2)).unwrap();
+        assert_eq!(path.cells.len(), 400 - 24);
+        assert_eq!(path.cells[0], (3, 2));
+        assert_eq!(path.coverage, 1.0);
+        assert_eq!(path.overlap, 0.0);
+        let mut seen = vec![false; 400];
+        for &(x, y) in &path.cells {
+            assert!(!seen[map.index(x, y)]);
+            seen[map.index(x, y)] = true;
+        }
+        // 4-connected moves only, ending next to the start
+        let mut closed: Vec<_> = path.cells.clone();
+        closed.push(path.cells[0]);
+        for w in closed.windows(2) {
+            assert_eq!(w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1), 1);
+        }
+    }
+
+    #[test]
+    fn test_spanning_tree_misses_partial_blocks() {
+        let mut map = GridMap::new(20, 20);
+        map.set_obstacle(5, 5, true);
+        let path = spanning_tree_coverage(&map, (0, 0)).unwrap();
+        // The three free cells sharing a block with the obstacle are skipped
+        assert_eq!(path.cells.len(), 396);
+        assert!(path.coverage < 1.0);
+        assert!(!path.cells.contains(&(4, 4)));
+        assert_drivable(&map, &path.cells);
+    }
+
+    #[test]
+    fn test_spanning_tree_blocked_start() {
+        let mut map = GridMap::new(10, 10);
+        map.set_obstacle(1, 1, true);
+        assert!(spanning_tree_coverage(&map, (0, 0)).is_none());
+        assert!(spanning_tree_coverage(&map, (2, 0)).is_some());
+    }
+
+    // ==================== EVALUATION ====================
+
+    #[test]
+    fn test_evaluate_counts_overlap() {
+        let map = GridMap::new(5, 1);
+        let there_and_back = vec![(0, 0), (1, 0), (2, 0), (1, 0), (0, 0)];
+        let path = CoveragePath::evaluate(&map, there_and_back, 1);
+        assert_eq!(path.length, 4.0);
+        assert!((path.coverage - 0.6).abs() < 1e-6);
+        assert!((path.overlap - 2.0 / 3.0).abs() < 1e-6);
+    }
+}
@@ -1,6 +1,8 @@
+use alloc::collections::{BinaryHeap, VecDeque};
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 
-// Simplified D* Lite node
+// D* Lite node: search state for one grid cell
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Node {
     pub x: i32,
@@ -9,11 +11,34 @@ pub struct Node {
     pub rhs: f32,
 }
 
+/// 8-connected neighbour offsets; the first four are the orthogonal moves.
+pub(crate) const NEIGHBORS_8: [(isize, isize); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Cell cost of free space.
+pub const FREE: u8 = 0;
+/// Cell cost at or above which a cell cannot be entered.
+pub const LETHAL: u8 = 254;
+/// Cell cost of unexplored space; blocks movement like `LETHAL`.
+pub const UNKNOWN: u8 = 255;
+
 // Grid map for D*
+#[derive(Clone, Debug)]
 pub struct GridMap {
     pub width: usize,
     pub height: usize,
-    pub obstacles: Vec<bool>, // flattened grid
+    pub costs: Vec<u8>, // flattened grid, FREE..=UNKNOWN
+    /// Extra traversal cost per unit of cell cost; a move is multiplied by
+    /// `1 + cost * cost_scale`, averaged over the two cells it joins.
+    pub cost_scale: f32,
 }
 
 impl GridMap {
@@ -21,63 +46,609 @@ impl GridMap {
         Self {
             width,
             height,
-            obstacles: alloc::vec![false; width * height], // Using vec! requires alloc, but this is a library file
+            costs: alloc::vec![FREE; width * height], // Using vec! requires alloc, but this is a library file
+            cost_scale: 0.05,
         }
     }
 
-    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) {
+    pub fn set_cost(&mut self, x: usize, y: usize, cost: u8) {
         if x < self.width && y < self.height {
-            self.obstacles[y * self.width + x] = is_obs;
+            self.costs[y * self.width + x] = cost;
         }
     }
 
-    pub fn is_obstacle(&self, x: usize, y: usize) -> bool {
+    pub fn cost(&self, x: usize, y: usize) -> u8 {
         if x < self.width && y < self.height {
-            self.obstacles[y * self.width + x]
+            self.costs[y * self.width + x]
+        } else {
+            LETHAL // Out of bounds is obstacle
+        }
+    }
+
+    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) {
+        self.set_cost(x, y, if is_obs { LETHAL } else { FREE });
+    }
+
+    pub fn is_obstacle(&self, x: usize, y: usize) -> bool {
+        self.cost(x, y) >= LETHAL
+    }
+
+    pub fn in_bounds(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// Cost of moving between two 8-adjacent cells, or `f32::INFINITY` if the
+    /// move is blocked. Diagonal moves may not cut the corner of an obstacle.
+    /// The cost is symmetric, so it serves for reverse searches as well.
+    pub fn move_cost(&self, from: (usize, usize), to: (usize, usize)) -> f32 {
+        let a = self.cost(from.0, from.1);
+        let b = self.cost(to.0, to.1);
+        if a >= LETHAL || b >= LETHAL {
+            return f32::INFINITY;
+        }
+        let diagonal = from.0 != to.0 && from.1 != to.1;
+        if diagonal && (self.is_obstacle(from.0, to.1) || self.is_obstacle(to.0, from.1)) {
+            return f32::INFINITY;
+        }
+        let step = if diagonal {
+            core::f32::consts::SQRT_2
         } else {
-            true // Out of bounds is obstacle
+            1.0
+        };
+        step * (1.0 + (a as f32 + b as f32) * 0.5 * self.cost_scale)
+    }
+
+    /// Traversable 8-connected neighbours of a cell with their move cost.
+    pub fn neighbors(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = ((usize, usize), f32)> + '_ {
+        NEIGHBORS_8.iter().filter_map(move |&(dx, dy)| {
+            let nx = x as isize + dx;
+            let ny = y as isize + dy;
+            if !self.in_bounds(nx, ny) {
+                return None;
+            }
+            let next = (nx as usize, ny as usize);
+            let cost = self.move_cost((x, y), next);
+            if cost.is_finite() {
+                Some((next, cost))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Octile distance, the exact obstacle-free cost on an 8-connected grid.
+pub fn octile_distance(a: (usize, usize), b: (usize, usize)) -> f32 {
+    let dx = (a.0 as f32 - b.0 as f32).abs();
+    let dy = (a.1 as f32 - b.1 as f32).abs();
+    let (lo, hi) = if dx < dy { (dx, dy) } else { (dy, dx) };
+    hi + (core::f32::consts::SQRT_2 - 1.0) * lo
+}
+
+// D* Lite priority: [min(g, rhs) + h + km; min(g, rhs)], compared lexicographically
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Key(f32, f32);
+
+impl Key {
+    fn cmp_key(&self, other: &Key) -> Ordering {
+        self.0
+            .total_cmp(&other.0)
+            .then_with(|| self.1.total_cmp(&other.1))
+    }
+
+    // Lexicographic `<` that ignores float rounding between equal path costs
+    fn less_than(&self, other: &Key) -> bool {
+        const EPS: f32 = 1e-4;
+        if self.0 < other.0 - EPS {
+            return true;
         }
+        self.0 <= other.0 + EPS && self.1 < other.1 - EPS
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct QueueEntry {
+    key: Key,
+    cell: usize,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-// Very simplified D* logic for portfolio visualization
-// Real D* Lite is complex with priority queues.
-// We will implement a basic "Dynamic Path Re-planning" visualizer.
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    // Reversed so BinaryHeap pops the smallest key first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .cmp_key(&self.key)
+            .then_with(|| other.cell.cmp(&self.cell))
+    }
+}
+
+fn obstacle_costs(cells: &[(usize, usize, bool)]) -> Vec<(usize, usize, u8)> {
+    cells
+        .iter()
+        .map(|&(x, y, is_obs)| (x, y, if is_obs { LETHAL } else { FREE }))
+        .collect()
+}
+
+/// One step of a D* Lite search, for animating or inspecting the planner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchEvent {
+    /// A search from scratch began.
+    SearchStarted {
+        start: (usize, usize),
+        goal: (usize, usize),
+    },
+    /// An incremental repair of the previous search began.
+    ReplanStarted {
+        start: (usize, usize),
+        goal: (usize, usize),
+    },
+    /// A cell was put on (or moved within) the open queue.
+    Opened {
+        cell: (usize, usize),
+        key: (f32, f32),
+    },
+    /// A cell was taken off the queue and expanded; values before expansion.
+    Expanded {
+        cell: (usize, usize),
+        g: f32,
+        rhs: f32,
+    },
+    /// A cell's g or rhs value changed.
+    Updated {
+        cell: (usize, usize),
+        g: f32,
+        rhs: f32,
+    },
+    /// The search finished with a path of `cells` cells costing `cost`.
+    PathFound { cells: usize, cost: f32 },
+    /// The search finished without reaching the goal.
+    NoPath,
+}
+
+// Receives search events. The unit observer does nothing and compiles away,
+// so uninstrumented planning pays nothing for the instrumentation.
+trait SearchObserver {
+    fn event(&mut self, event: SearchEvent);
+}
+
+impl SearchObserver for () {
+    #[inline(always)]
+    fn event(&mut self, _event: SearchEvent) {}
+}
+
+impl SearchObserver for VecDeque<SearchEvent> {
+    fn event(&mut self, event: SearchEvent) {
+        self.push_back(event);
+    }
+}
+
+/// Iterator over the events of one plan or replan, running the search one
+/// expansion at a time. `DStarLite::path` is filled in once the final
+/// `PathFound`/`NoPath` event is reached. Dropping the iterator early pauses
+/// the search; the next plan resumes where it stopped.
+pub struct PlanSteps<'a> {
+    planner: &'a mut DStarLite,
+    pending: VecDeque<SearchEvent>,
+    searching: bool,
+    finished: bool,
+}
+
+impl Iterator for PlanSteps<'_> {
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<SearchEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            if self.finished {
+                return None;
+            }
+            if !self.searching || !self.planner.expand_one(&mut self.pending) {
+                self.planner.finish(self.searching, &mut self.pending);
+                self.finished = true;
+            }
+        }
+    }
+}
+
+/// Incremental D* Lite planner (Koenig & Likhachev, optimized version).
+///
+/// The search runs backwards from `goal`, so moving `start` or changing cells
+/// through [`DStarLite::update_cells`] only re-expands the affected part of the
+/// previous search. Edit the map directly only before the first plan.
 pub struct DStarLite {
     pub start: (usize, usize),
     pub goal: (usize, usize),
     pub map: GridMap,
     pub path: Vec<(usize, usize)>,
+    /// Vertices expanded by the most recent `compute_shortest_path` call.
+    pub expansions: usize,
+    nodes: Vec<Node>,
+    // Lazy-deletion heap: an entry is live only if it matches `queued[cell]`
+    queue: BinaryHeap<QueueEntry>,
+    queued: Vec<Option<Key>>,
+    km: f32,
+    last_start: (usize, usize),
+    search_goal: Option<(usize, usize)>,
 }
 
 impl DStarLite {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
             start: (0, 0),
-            goal: (width - 1, height - 1),
+            goal: (width.saturating_sub(1), height.saturating_sub(1)),
             map: GridMap::new(width, height),
             path: Vec::new(),
+            expansions: 0,
+            nodes: Vec::new(),
+            queue: BinaryHeap::new(),
+            queued: Vec::new(),
+            km: 0.0,
+            last_start: (0, 0),
+            search_goal: None,
         }
     }
 
-    // Replan path using simple BFS/A* for now as "Dynamic" aspect
-    // is triggered when map changes.
+    /// Discard all search state; the next plan starts from scratch.
+    pub fn reset(&mut self) {
+        self.search_goal = None;
+    }
+
+    /// Search state (g, rhs) of a cell, if a search has been run.
+    pub fn node(&self, x: usize, y: usize) -> Option<&Node> {
+        if x < self.map.width && y < self.map.height {
+            self.nodes.get(self.map.index(x, y))
+        } else {
+            None
+        }
+    }
+
+    // Plan (or replan) from `start` to `goal` and rebuild `path`.
+    // `path` is left empty when the goal is unreachable.
     pub fn compute_shortest_path(&mut self) {
-        // Simplified BFS for "pathfinding" demo
-        // In production, use A* with heuristic
-        // let mut queue = Vec::new(); // alloc
-        // queue.push(self.start);
-
-        // let mut came_from = Vec::new(); // map equivalent
-        // ... Full implementation requires Map/HashMap which are in std or hashbrown
-        // For no_std pure logic, we'd need a custom Map.
-        // Skipping full pathfinding implementation for this specific tool step
-        // to focus on integrating the *structure* first.
-
-        // Just drawing a direct line if no obstacles for visual filler
+        self.plan(&[], &mut ());
+    }
+
+    /// Like [`DStarLite::compute_shortest_path`], but runs lazily as an
+    /// iterator of search events.
+    pub fn steps(&mut self) -> PlanSteps<'_> {
+        self.plan_steps(&[])
+    }
+
+    /// Apply obstacle changes `(x, y, is_obs)` and repair the existing search.
+    pub fn update_cells(&mut self, cells: &[(usize, usize, bool)]) {
+        self.update_costs(&obstacle_costs(cells));
+    }
+
+    /// Apply cell cost changes `(x, y, cost)` and repair the existing search.
+    pub fn update_costs(&mut self, cells: &[(usize, usize, u8)]) {
+        self.plan(cells, &mut ());
+    }
+
+    /// Like [`DStarLite::update_cells`], but runs the repair as an iterator
+    /// of search events.
+    pub fn update_cells_steps(&mut self, cells: &[(usize, usize, bool)]) -> PlanSteps<'_> {
+        self.plan_steps(&obstacle_costs(cells))
+    }
+
+    /// Like [`DStarLite::update_costs`], but runs the repair as an iterator
+    /// of search events.
+    pub fn update_costs_steps(&mut self, cells: &[(usize, usize, u8)]) -> PlanSteps<'_> {
+        self.plan_steps(cells)
+    }
+
+    fn plan<O: SearchObserver>(&mut self, changes: &[(usize, usize, u8)], obs: &mut O) {
+        let searching = self.begin(changes, obs);
+        if searching {
+            while self.expand_one(obs) {}
+        }
+        self.finish(searching, obs);
+    }
+
+    fn plan_steps(&mut self, changes: &[(usize, usize, u8)]) -> PlanSteps<'_> {
+        let mut pending = VecDeque::new();
+        let searching = self.begin(changes, &mut pending);
+        PlanSteps {
+            planner: self,
+            pending,
+            searching,
+            finished: false,
+        }
+    }
+
+    // Apply cost changes and get the queue ready for expansion. Returns false
+    // if start or goal is off the map and there is nothing to search.
+    fn begin<O: SearchObserver>(&mut self, changes: &[(usize, usize, u8)], obs: &mut O) -> bool {
+        self.expansions = 0;
         self.path.clear();
-        self.path.push(self.start);
-        self.path.push(self.goal);
+        let fresh = self.search_goal != Some(self.goal) || self.nodes.len() != self.cell_count();
+
+        if fresh {
+            for &(x, y, cost) in changes {
+                self.map.set_cost(x, y, cost);
+            }
+        } else {
+            obs.event(SearchEvent::ReplanStarted {
+                start: self.start,
+                goal: self.goal,
+            });
+            // Robot moved: keep old keys valid by raising the key modifier
+            self.km += octile_distance(self.last_start, self.start);
+            self.last_start = self.start;
+            for &(x, y, cost) in changes {
+                self.repair_cell(x, y, cost, obs);
+            }
+        }
+
+        let in_map = |(x, y): (usize, usize)| x < self.map.width && y < self.map.height;
+        if !in_map(self.start) || !in_map(self.goal) {
+            return false;
+        }
+        if fresh {
+            obs.event(SearchEvent::SearchStarted {
+                start: self.start,
+                goal: self.goal,
+            });
+            self.initialize(obs);
+        }
+        true
+    }
+
+    fn repair_cell<O: SearchObserver>(&mut self, x: usize, y: usize, cost: u8, obs: &mut O) {
+        if x >= self.map.width || y >= self.map.height || self.map.cost(x, y) == cost {
+            return;
+        }
+        self.map.set_cost(x, y, cost);
+
+        // Every edge touching (x, y) changed, and with the corner-cutting
+        // rule so did the diagonals between its neighbours.
+        for dy in -1..=1isize {
+            for dx in -1..=1isize {
+                let nx = x as isize + dx;
+                let ny = y as isize + dy;
+                if self.map.in_bounds(nx, ny) {
+                    let cell = self.map.index(nx as usize, ny as usize);
+                    self.recompute_rhs(cell, obs);
+                    self.update_vertex(cell, obs);
+                }
+            }
+        }
+    }
+
+    fn finish<O: SearchObserver>(&mut self, searched: bool, obs: &mut O) {
+        if searched {
+            self.extract_path();
+        }
+        if self.path.is_empty() {
+            obs.event(SearchEvent::NoPath);
+        } else {
+            let start = self.map.index(self.start.0, self.start.1);
+            obs.event(SearchEvent::PathFound {
+                cells: self.path.len(),
+                cost: self.nodes[start].g,
+            });
+        }
+    }
+
+    fn cell_count(&self) -> usize {
+        self.map.width * self.map.height
+    }
+
+    fn coords(&self, cell: usize) -> (usize, usize) {
+        (cell % self.map.width, cell / self.map.width)
+    }
+
+    fn initialize<O: SearchObserver>(&mut self, obs: &mut O) {
+        let width = self.map.width;
+        self.nodes = (0..self.cell_count())
+            .map(|i| Node {
+                x: (i % width) as i32,
+                y: (i / width) as i32,
+                g: f32::INFINITY,
+                rhs: f32::INFINITY,
+            })
+            .collect();
+        self.queue.clear();
+        self.queued = alloc::vec![None; self.cell_count()];
+        self.km = 0.0;
+        self.last_start = self.start;
+        self.search_goal = Some(self.goal);
+
+        let goal = self.map.index(self.goal.0, self.goal.1);
+        self.nodes[goal].rhs = 0.0;
+        self.updated(goal, obs);
+        let key = self.calculate_key(goal);
+        self.push(goal, key, obs);
+    }
+
+    fn calculate_key(&self, cell: usize) -> Key {
+        let node = &self.nodes[cell];
+        let k2 = node.g.min(node.rhs);
+        Key(
+            k2 + octile_distance(self.start, self.coords(cell)) + self.km,
+            k2,
+        )
+    }
+
+    fn push<O: SearchObserver>(&mut self, cell: usize, key: Key, obs: &mut O) {
+        self.queued[cell] = Some(key);
+        self.queue.push(QueueEntry { key, cell });
+        obs.event(SearchEvent::Opened {
+            cell: self.coords(cell),
+            key: (key.0, key.1),
+        });
+    }
+
+    fn updated<O: SearchObserver>(&self, cell: usize, obs: &mut O) {
+        let node = &self.nodes[cell];
+        obs.event(SearchEvent::Updated {
+            cell: self.coords(cell),
+            g: node.g,
+            rhs: node.rhs,
+        });
+    }
+
+    fn top(&mut self) -> Option<QueueEntry> {
+        while let Some(entry) = self.queue.peek() {
+            if self.queued[entry.cell] == Some(entry.key) {
+                return Some(*entry);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    fn update_vertex<O: SearchObserver>(&mut self, cell: usize, obs: &mut O) {
+        let node = self.nodes[cell];
+        if node.g != node.rhs {
+            let key = self.calculate_key(cell);
+            self.push(cell, key, obs);
+        } else {
+            self.queued[cell] = None;
+        }
+    }
+
+    // rhs(s) = min over successors s' of c(s, s') + g(s')
+    fn recompute_rhs<O: SearchObserver>(&mut self, cell: usize, obs: &mut O) {
+        let pos = self.coords(cell);
+        if pos == self.goal {
+            return;
+        }
+        let mut best = f32::INFINITY;
+        for (next, cost) in self.map.neighbors(pos.0, pos.1) {
+            let g = self.nodes[self.map.index(next.0, next.1)].g;
+            best = best.min(cost + g);
+        }
+        if self.nodes[cell].rhs != best {
+            self.nodes[cell].rhs = best;
+            self.updated(cell, obs);
+        }
+    }
+
+    // Pop queue entries until one vertex is expanded. Returns false once the
+    // start is consistent and no queued key is below its key.
+    fn expand_one<O: SearchObserver>(&mut self, obs: &mut O) -> bool {
+        let start = self.map.index(self.start.0, self.start.1);
+        let goal = self.map.index(self.goal.0, self.goal.1);
+
+        while let Some(top) = self.top() {
+            let start_key = self.calculate_key(start);
+            let start_node = self.nodes[start];
+            if !top.key.less_than(&start_key) && start_node.rhs == start_node.g {
+                return false;
+            }
+
+            let cell = top.cell;
+            let k_new = self.calculate_key(cell);
+            if top.key.cmp_key(&k_new) == Ordering::Less {
+                self.push(cell, k_new, obs);
+                continue;
+            }
+
+            self.expansions += 1;
+            let pos = self.coords(cell);
+            obs.event(SearchEvent::Expanded {
+                cell: pos,
+                g: self.nodes[cell].g,
+                rhs: self.nodes[cell].rhs,
+            });
+            // At most 8, copied to the stack so expansions do not allocate
+            let mut neighbors = [((0, 0), 0.0); 8];
+            let mut count = 0;
+            for n in self.map.neighbors(pos.0, pos.1) {
+                neighbors[count] = n;
+                count += 1;
+            }
+            let neighbors = &neighbors[..count];
+
+            if self.nodes[cell].g > self.nodes[cell].rhs {
+                // Overconsistent: settle g and relax predecessors
+                let g = self.nodes[cell].rhs;
+                self.nodes[cell].g = g;
+                self.queued[cell] = None;
+                self.updated(cell, obs);
+                for &(prev, cost) in neighbors {
+                    let p = self.map.index(prev.0, prev.1);
+                    if p != goal && cost + g < self.nodes[p].rhs {
+                        self.nodes[p].rhs = cost + g;
+                        self.updated(p, obs);
+                    }
+                    self.update_vertex(p, obs);
+                }
+            } else {
+                // Underconsistent: invalidate g and repair dependants
+                let g_old = self.nodes[cell].g;
+                self.nodes[cell].g = f32::INFINITY;
+                self.updated(cell, obs);
+                for &(prev, cost) in neighbors {
+                    let p = self.map.index(prev.0, prev.1);
+                    if self.nodes[p].rhs == cost + g_old {
+                        self.recompute_rhs(p, obs);
+                    }
+                    self.update_vertex(p, obs);
+                }
+                self.recompute_rhs(cell, obs);
+                self.update_vertex(cell, obs);
+            }
+            return true;
+        }
+        false
+    }
+
+    fn extract_path(&mut self) {
+        let start = self.map.index(self.start.0, self.start.1);
+        if !self.nodes[start].g.is_finite() && self.start != self.goal {
+            return;
+        }
+        // A blocked start has no path even to itself, matching GridSearch
+        if self.map.is_obstacle(self.start.0, self.start.1) {
+            return;
+        }
+
+        let mut current = self.start;
+        self.path.push(current);
+        while current != self.goal && self.path.len() <= self.cell_count() {
+            let mut best: Option<((usize, usize), f32)> = None;
+            for (next, cost) in self.map.neighbors(current.0, current.1) {
+                let total = cost + self.nodes[self.map.index(next.0, next.1)].g;
+                if total.is_finite() && best.is_none_or(|(_, b)| total < b) {
+                    best = Some((next, total));
+                }
+            }
+            match best {
+                Some((next, _)) => {
+                    current = next;
+                    self.path.push(current);
+                }
+                None => break,
+            }
+        }
+        if current != self.goal {
+            self.path.clear();
+        }
     }
 }
 
@@ -1,6 +1,45 @@
 #[cfg(test)]
 mod tests {
-    use crate::dstar::{DStarLite, GridMap, Node};
+    use crate::dstar::{octile_distance, DStarLite, GridMap, Node, SearchEvent, FREE, LETHAL};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn path_cost(map: &GridMap, path: &[(usize, usize)]) -> f32 {
+        path.windows(2).map(|w| map.move_cost(w[0], w[1])).sum()
+    }
+
+    fn assert_valid_path(ds: &DStarLite) {
+        assert_eq!(ds.path[0], ds.start);
+        assert_eq!(*ds.path.last().unwrap(), ds.goal);
+        for w in ds.path.windows(2) {
+            assert!(ds.map.move_cost(w[0], w[1]).is_finite());
+        }
+    }
+
+    // Reference Dijkstra over the same 8-connected graph
+    fn reference_cost(map: &GridMap, start: (usize, usize), goal: (usize, usize)) -> f32 {
+        let mut dist = vec![f32::INFINITY; map.width * map.height];
+        let mut done = vec![false; map.width * map.height];
+        dist[map.index(start.0, start.1)] = 0.0;
+        loop {
+            let mut best: Option<usize> = None;
+            for i in 0..dist.len() {
+                if !done[i] && dist[i].is_finite() && best.is_none_or(|b| dist[i] < dist[b]) {
+                    best = Some(i);
+                }
+            }
+            let Some(i) = best else { break };
+            done[i] = true;
+            let (x, y) = (i % map.width, i / map.width);
+            for (n, c) in map.neighbors(x, y) {
+                let j = map.index(n.0, n.1);
+                if dist[i] + c < dist[j] {
+                    dist[j] = dist[i] + c;
+                }
+            }
+        }
+        dist[map.index(goal.0, goal.1)]
+    }
 
     // ==================== NODE ====================
 
@@ -71,7 +110,7 @@ mod tests {
         let map = GridMap::new(10, 20);
         assert_eq!(map.width, 10);
         assert_eq!(map.height, 20);
-        assert_eq!(map.obstacles.len(), 200);
+        assert_eq!(map.costs.len(), 200);
     }
 
     #[test]
@@ -89,7 +128,7 @@ mod tests {
         let map = GridMap::new(0, 0);
         assert_eq!(map.width, 0);
         assert_eq!(map.height, 0);
-        assert_eq!(map.obstacles.len(), 0);
+        assert_eq!(map.costs.len(), 0);
     }
 
     // ==================== GRIDMAP OBSTACLES ====================
@@ -152,7 +191,7 @@ mod tests {
         // Should not crash, just ignore
         map.set_obstacle(100, 100, true);
         // Map should be unchanged
-        assert_eq!(map.obstacles.iter().filter(|&&x| x).count(), 0);
+        assert_eq!(map.costs.iter().filter(|&&c| c == LETHAL).count(), 0);
     }
 
     #[test]
@@ -229,9 +268,8 @@ mod tests {
         ds.map.set_obstacle(5, 5, true);
         ds.compute_shortest_path();
 
-        // Current simple implementation just does start -> goal
-        // So this tests that it doesn't crash with obstacles
         assert!(!ds.path.is_empty());
+        assert!(!ds.path.contains(&(5, 5)));
     }
 
     #[test]
@@ -245,9 +283,10 @@ mod tests {
 
         ds.compute_shortest_path();
 
-        // Simple implementation will still produce a path
-        // (doesn't actually pathfind around obstacles yet)
+        // Path must go around the bottom end of the wall
         assert!(!ds.path.is_empty());
+        assert!(ds.path.iter().all(|&(x, y)| !ds.map.is_obstacle(x, y)));
+        assert!(ds.path.iter().any(|&(_, y)| y >= 8));
     }
 
     // ==================== INTEGRATION ====================
@@ -283,6 +322,23 @@ mod tests {
         assert_eq!(ds.goal, (0, 0)); // Same as start
     }
 
+    #[test]
+    fn test_dstarlite_blocked_start_at_goal() {
+        let mut ds = DStarLite::new(5, 5);
+        ds.map.set_obstacle(2, 2, true);
+        ds.start = (2, 2);
+        ds.goal = (2, 2);
+        ds.compute_shortest_path();
+        assert!(ds.path.is_empty());
+        assert!(crate::grid_search::GridSearch::default()
+            .plan(&ds.map, (2, 2), (2, 2))
+            .is_none());
+        // A free start at the goal is a one-cell path
+        ds.map.set_obstacle(2, 2, false);
+        ds.compute_shortest_path();
+        assert_eq!(ds.path, [(2, 2)]);
+    }
+
     #[test]
     fn test_dstarlite_rectangular_grid() {
         let ds = DStarLite::new(50, 10);
@@ -305,12 +361,418 @@ mod tests {
         }
 
         // Verify pattern
-        let obstacle_count = map.obstacles.iter().filter(|&&x| x).count();
+        let obstacle_count = map.costs.iter().filter(|&&c| c == LETHAL).count();
         assert!(obstacle_count > 0);
 
         // Verify we can still query
         assert!(map.is_obstacle(0, 0)); // 0+0 = 0, 0%3 = 0 -> obstacle
         assert!(!map.is_obstacle(1, 0)); // 1+0 = 1, 1%3 = 1 -> not obstacle
     }
-}
 
+    // ==================== GRIDMAP MOVES ====================
+
+    #[test]
+    fn test_move_cost_orthogonal_and_diagonal() {
+        let map = GridMap::new(5, 5);
+        assert_eq!(map.move_cost((1, 1), (2, 1)), 1.0);
+        assert!((map.move_cost((1, 1), (2, 2)) - core::f32::consts::SQRT_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_move_cost_no_corner_cutting() {
+        let mut map = GridMap::new(5, 5);
+        map.set_obstacle(2, 1, true);
+        assert!(map.move_cost((1, 1), (2, 2)).is_infinite());
+        assert!(map.move_cost((1, 1), (1, 2)).is_finite());
+    }
+
+    #[test]
+    fn test_neighbors_at_corner() {
+        let map = GridMap::new(5, 5);
+        assert_eq!(map.neighbors(0, 0).count(), 3);
+        assert_eq!(map.neighbors(2, 2).count(), 8);
+    }
+
+    #[test]
+    fn test_octile_distance() {
+        assert_eq!(octile_distance((0, 0), (3, 0)), 3.0);
+        let d = octile_distance((0, 0), (3, 5));
+        assert!((d - (5.0 + 3.0 * (core::f32::consts::SQRT_2 - 1.0))).abs() < 1e-5);
+    }
+
+    // ==================== DSTARLITE OPTIMALITY ====================
+
+    #[test]
+    fn test_dstarlite_open_grid_is_octile() {
+        let mut ds = DStarLite::new(12, 8);
+        ds.compute_shortest_path();
+        assert_valid_path(&ds);
+        let cost = path_cost(&ds.map, &ds.path);
+        assert!((cost - octile_distance(ds.start, ds.goal)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_dstarlite_matches_reference_dijkstra() {
+        let mut ds = DStarLite::new(20, 15);
+        for x in 0..16 {
+            ds.map.set_obstacle(x, 5, true);
+        }
+        for x in 4..20 {
+            ds.map.set_obstacle(x, 10, true);
+        }
+        ds.compute_shortest_path();
+        assert_valid_path(&ds);
+        let expected = reference_cost(&ds.map, ds.start, ds.goal);
+        assert!((path_cost(&ds.map, &ds.path) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_dstarlite_unreachable_goal() {
+        let mut ds = DStarLite::new(10, 10);
+        for y in 0..10 {
+            ds.map.set_obstacle(5, y, true);
+        }
+        ds.compute_shortest_path();
+        assert!(ds.path.is_empty());
+    }
+
+    #[test]
+    fn test_dstarlite_blocked_goal() {
+        let mut ds = DStarLite::new(10, 10);
+        ds.map.set_obstacle(9, 9, true);
+        ds.compute_shortest_path();
+        assert!(ds.path.is_empty());
+    }
+
+    #[test]
+    fn test_dstarlite_out_of_bounds_start() {
+        let mut ds = DStarLite::new(10, 10);
+        ds.start = (20, 20);
+        ds.compute_shortest_path();
+        assert!(ds.path.is_empty());
+    }
+
+    #[test]
+    fn test_dstarlite_node_state() {
+        let mut ds = DStarLite::new(10, 10);
+        assert!(ds.node(0, 0).is_none());
+        ds.compute_shortest_path();
+        let goal = ds.node(9, 9).unwrap();
+        assert_eq!(goal.rhs, 0.0);
+        assert_eq!(goal.g, 0.0);
+        let start = ds.node(0, 0).unwrap();
+        assert_eq!(start.g, start.rhs);
+    }
+
+    // ==================== DSTARLITE INCREMENTAL ====================
+
+    #[test]
+    fn test_update_cells_blocks_path() {
+        let mut ds = DStarLite::new(15, 15);
+        ds.compute_shortest_path();
+        let blocked = ds.path[ds.path.len() / 2];
+
+        ds.update_cells(&[(blocked.0, blocked.1, true)]);
+        assert_valid_path(&ds);
+        assert!(!ds.path.contains(&blocked));
+        let expected = reference_cost(&ds.map, ds.start, ds.goal);
+        assert!((path_cost(&ds.map, &ds.path) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_update_cells_opens_shortcut() {
+        let mut ds = DStarLite::new(20, 20);
+        for y in 0..19 {
+            ds.map.set_obstacle(10, y, true);
+        }
+        ds.compute_shortest_path();
+        let detour = path_cost(&ds.map, &ds.path);
+
+        ds.update_cells(&[(10, 10, false)]);
+        assert_valid_path(&ds);
+        let shortcut = path_cost(&ds.map, &ds.path);
+        assert!(shortcut < detour);
+        let expected = reference_cost(&ds.map, ds.start, ds.goal);
+        assert!((shortcut - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_update_cells_disconnects_and_reconnects() {
+        let mut ds = DStarLite::new(10, 10);
+        for y in 0..9 {
+            ds.map.set_obstacle(5, y, true);
+        }
+        ds.compute_shortest_path();
+        assert!(!ds.path.is_empty());
+
+        ds.update_cells(&[(5, 9, true)]);
+        assert!(ds.path.is_empty());
+
+        ds.update_cells(&[(5, 9, false)]);
+        assert_valid_path(&ds);
+    }
+
+    #[test]
+    fn test_update_cells_far_change_is_cheap() {
+        let mut ds = DStarLite::new(40, 40);
+        ds.start = (0, 20);
+        ds.goal = (39, 20);
+        for y in 5..35 {
+            ds.map.set_obstacle(20, y, true);
+        }
+        ds.compute_shortest_path();
+        let full = ds.expansions;
+
+        // A change behind the goal, far from the current path
+        ds.update_cells(&[(38, 38, true)]);
+        assert_valid_path(&ds);
+        assert!(
+            ds.expansions * 10 < full,
+            "replan expanded {} of {}",
+            ds.expansions,
+            full
+        );
+    }
+
+    #[test]
+    fn test_update_cells_matches_fresh_plan() {
+        let changes: Vec<(usize, usize, bool)> = (3..17).map(|y| (8, y, true)).collect();
+
+        let mut incremental = DStarLite::new(20, 20);
+        incremental.compute_shortest_path();
+        incremental.update_cells(&changes);
+
+        let mut fresh = DStarLite::new(20, 20);
+        for &(x, y, obs) in &changes {
+            fresh.map.set_obstacle(x, y, obs);
+        }
+        fresh.compute_shortest_path();
+
+        let a = path_cost(&incremental.map, &incremental.path);
+        let b = path_cost(&fresh.map, &fresh.path);
+        assert!((a - b).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_update_cells_before_first_plan() {
+        let mut ds = DStarLite::new(10, 10);
+        ds.update_cells(&[(4, 4, true)]);
+        assert!(ds.map.is_obstacle(4, 4));
+        assert_valid_path(&ds);
+    }
+
+    #[test]
+    fn test_dstarlite_moving_start() {
+        let mut ds = DStarLite::new(20, 20);
+        for x in 2..18 {
+            ds.map.set_obstacle(x, 10, true);
+        }
+        ds.compute_shortest_path();
+
+        // Walk along the path, replanning after each step and a new obstacle
+        for step in 0..5 {
+            ds.start = ds.path[1];
+            ds.update_cells(&[(step + 3, 12, true)]);
+            assert_valid_path(&ds);
+            let expected = reference_cost(&ds.map, ds.start, ds.goal);
+            assert!((path_cost(&ds.map, &ds.path) - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_dstarlite_goal_change_replans() {
+        let mut ds = DStarLite::new(10, 10);
+        ds.compute_shortest_path();
+        ds.goal = (0, 9);
+        ds.compute_shortest_path();
+        assert_valid_path(&ds);
+        assert_eq!(ds.path.len(), 10);
+    }
+
+    #[test]
+    fn test_dstarlite_reset() {
+        let mut ds = DStarLite::new(10, 10);
+        ds.compute_shortest_path();
+        ds.map.set_obstacle(5, 5, true);
+        ds.reset();
+        ds.compute_shortest_path();
+        assert_valid_path(&ds);
+        assert!(!ds.path.contains(&(5, 5)));
+    }
+
+    // ==================== COSTS ====================
+
+    #[test]
+    fn test_gridmap_cost_roundtrip() {
+        let mut map = GridMap::new(5, 5);
+        map.set_cost(2, 3, 100);
+        assert_eq!(map.cost(2, 3), 100);
+        assert!(!map.is_obstacle(2, 3));
+        map.set_cost(2, 3, LETHAL);
+        assert!(map.is_obstacle(2, 3));
+        assert_eq!(map.cost(9, 9), LETHAL);
+    }
+
+    #[test]
+    fn test_set_obstacle_wraps_costs() {
+        let mut map = GridMap::new(5, 5);
+        map.set_obstacle(1, 1, true);
+        assert_eq!(map.cost(1, 1), LETHAL);
+        map.set_obstacle(1, 1, false);
+        assert_eq!(map.cost(1, 1), FREE);
+    }
+
+    #[test]
+    fn test_move_cost_scales_with_cell_cost() {
+        let mut map = GridMap::new(5, 5);
+        let free = map.move_cost((0, 0), (1, 0));
+        map.set_cost(1, 0, 100);
+        let rough = map.move_cost((0, 0), (1, 0));
+        assert!(rough > free);
+        assert_eq!(rough, map.move_cost((1, 0), (0, 0)));
+    }
+
+    #[test]
+    fn test_dstarlite_prefers_cheap_cells() {
+        let mut ds = DStarLite::new(11, 5);
+        ds.start = (0, 2);
+        ds.goal = (10, 2);
+        // Rough strip across the straight line, open detour along the top
+        for x in 3..8 {
+            for y in 1..5 {
+                ds.map.set_cost(x, y, 200);
+            }
+        }
+        ds.compute_shortest_path();
+        assert_valid_path(&ds);
+        assert!(ds.path.iter().any(|&(_, y)| y == 0));
+        let expected = reference_cost(&ds.map, ds.start, ds.goal);
+        assert!((path_cost(&ds.map, &ds.path) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_update_costs_repairs_path() {
+        let mut ds = DStarLite::new(15, 15);
+        ds.compute_shortest_path();
+        let changes: Vec<(usize, usize, u8)> = (4..11).map(|i| (i, i, 220)).collect();
+        ds.update_costs(&changes);
+        assert_valid_path(&ds);
+        let expected = reference_cost(&ds.map, ds.start, ds.goal);
+        assert!((path_cost(&ds.map, &ds.path) - expected).abs() < 1e-3);
+        assert!(!ds.path.contains(&(7, 7)));
+    }
+
+    // ==================== STEPPING ====================
+
+    fn expanded(events: &[SearchEvent]) -> Vec<(usize, usize)> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SearchEvent::Expanded { cell, .. } => Some(*cell),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn wall_planner() -> DStarLite {
+        let mut ds = DStarLite::new(15, 15);
+        for y in 2..15 {
+            ds.map.set_obstacle(7, y, true);
+        }
+        ds
+    }
+
+    #[test]
+    fn test_steps_match_normal_run() {
+        let mut normal = wall_planner();
+        normal.compute_shortest_path();
+
+        let mut stepped = wall_planner();
+        let events: Vec<SearchEvent> = stepped.steps().collect();
+        assert_eq!(stepped.path, normal.path);
+        assert_eq!(stepped.expansions, normal.expansions);
+        assert_eq!(expanded(&events).len(), normal.expansions);
+
+        assert_eq!(
+            events[0],
+            SearchEvent::SearchStarted {
+                start: (0, 0),
+                goal: (14, 14)
+            }
+        );
+        match events.last() {
+            Some(&SearchEvent::PathFound { cells, cost }) => {
+                assert_eq!(cells, normal.path.len());
+                assert!((cost - path_cost(&normal.map, &normal.path)).abs() < 1e-3);
+            }
+            other => panic!("unexpected final event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_steps_expansion_order() {
+        // A corridor is searched backwards from the goal, one cell at a time
+        let mut ds = DStarLite::new(5, 1);
+        let events: Vec<SearchEvent> = ds.steps().collect();
+        assert_eq!(
+            expanded(&events),
+            vec![(4, 0), (3, 0), (2, 0), (1, 0), (0, 0)]
+        );
+        assert!(events.contains(&SearchEvent::Updated {
+            cell: (4, 0),
+            g: f32::INFINITY,
+            rhs: 0.0
+        }));
+        assert!(events.contains(&SearchEvent::Opened {
+            cell: (3, 0),
+            key: (4.0, 1.0)
+        }));
+    }
+
+    #[test]
+    fn test_steps_are_lazy() {
+        let mut ds = wall_planner();
+        let mut steps = ds.steps();
+        let first: Vec<SearchEvent> = steps.by_ref().take(20).collect();
+        assert_eq!(first.len(), 20);
+        drop(steps);
+        assert!(ds.path.is_empty());
+        assert!(ds.expansions > 0 && ds.expansions < 20);
+
+        // The next plan resumes the paused search
+        ds.compute_shortest_path();
+        assert_valid_path(&ds);
+        let expected = reference_cost(&ds.map, ds.start, ds.goal);
+        assert!((path_cost(&ds.map, &ds.path) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_update_steps_report_replan() {
+        let mut normal = wall_planner();
+        normal.compute_shortest_path();
+        normal.update_cells(&[(7, 1, true)]);
+
+        let mut stepped = wall_planner();
+        stepped.compute_shortest_path();
+        let events: Vec<SearchEvent> = stepped.update_cells_steps(&[(7, 1, true)]).collect();
+        assert!(matches!(events[0], SearchEvent::ReplanStarted { .. }));
+        assert_eq!(stepped.path, normal.path);
+        assert_eq!(expanded(&events).len(), normal.expansions);
+        assert!(matches!(events.last(), Some(SearchEvent::PathFound { .. })));
+    }
+
+    #[test]
+    fn test_steps_report_no_path() {
+        let mut ds = wall_planner();
+        ds.compute_shortest_path();
+        let events: Vec<SearchEvent> = ds
+            .update_costs_steps(&[(7, 0, LETHAL), (7, 1, LETHAL)])
+            .collect();
+        assert_eq!(events.last(), Some(&SearchEvent::NoPath));
+        assert!(ds.path.is_empty());
+
+        ds.start = (20, 20);
+        let events: Vec<SearchEvent> = ds.steps().collect();
+        assert_eq!(events.last(), Some(&SearchEvent::NoPath));
+    }
+}
@@ -0,0 +1,248 @@
+use crate::math::{wrap_angle, Pose};
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+
+/// Steering of one curve segment: a full-lock arc or a straight line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Steer {
+    Left,
+    Straight,
+    Right,
+}
+
+/// Move `length` along a segment of turning radius `radius`. A negative
+/// length drives the segment in reverse.
+pub fn advance(pose: Pose, steer: Steer, length: f32, radius: f32) -> Pose {
+    let (x, y, theta) = (pose.x, pose.y, pose.theta);
+    match steer {
+        Steer::Straight => Pose::new(
+            x + length * libm::cosf(theta),
+            y + length * libm::sinf(theta),
+            theta,
+        ),
+        Steer::Left => {
+            let phi = length / radius;
+            Pose::new(
+                x + radius * (libm::sinf(theta + phi) - libm::sinf(theta)),
+                y - radius * (libm::cosf(theta + phi) - libm::cosf(theta)),
+                wrap_angle(theta + phi),
+            )
+        }
+        Steer::Right => {
+            let phi = length / radius;
+            Pose::new(
+                x - radius * (libm::sinf(theta - phi) - libm::sinf(theta)),
+                y + radius * (libm::cosf(theta - phi) - libm::cosf(theta)),
+                wrap_angle(theta - phi),
+            )
+        }
+    }
+}
+
+// Pose at arc length `s` along signed segments (negative = reverse)
+pub(crate) fn sample_segments(start: Pose, segments: &[(Steer, f32)], radius: f32, s: f32) -> Pose {
+    let mut pose = start;
+    let mut remaining = s.max(0.0);
+    for &(steer, length) in segments {
+        let step = remaining.min(length.abs());
+        pose = advance(pose, steer, step * length.signum(), radius);
+        remaining -= step;
+        if remaining <= 0.0 {
+            break;
+        }
+    }
+    pose
+}
+
+// Poses every `step` along the segments, always ending on the final pose
+pub(crate) fn sample_many(
+    start: Pose,
+    segments: &[(Steer, f32)],
+    radius: f32,
+    step: f32,
+) -> Vec<Pose> {
+    let step = step.max(1e-3);
+    let total: f32 = segments.iter().map(|s| s.1.abs()).sum();
+    let n = libm::ceilf(total / step) as usize;
+    let mut poses = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        poses.push(sample_segments(start, segments, radius, i as f32 * step));
+    }
+    poses.push(sample_segments(start, segments, radius, total));
+    poses
+}
+
+// Angle in [0, 2PI)
+pub(crate) fn mod2pi(angle: f32) -> f32 {
+    let a = libm::fmodf(angle, 2.0 * PI);
+    if a < 0.0 {
+        a + 2.0 * PI
+    } else {
+        a
+    }
+}
+
+/// The six Dubins path words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DubinsWord {
+    Lsl,
+    Lsr,
+    Rsl,
+    Rsr,
+    Rlr,
+    Lrl,
+}
+
+impl DubinsWord {
+    pub const ALL: [DubinsWord; 6] = [
+        DubinsWord::Lsl,
+        DubinsWord::Lsr,
+        DubinsWord::Rsl,
+        DubinsWord::Rsr,
+        DubinsWord::Rlr,
+        DubinsWord::Lrl,
+    ];
+
+    pub fn steering(&self) -> [Steer; 3] {
+        use Steer::*;
+        match self {
+            DubinsWord::Lsl => [Left, Straight, Left],
+            DubinsWord::Lsr => [Left, Straight, Right],
+            DubinsWord::Rsl => [Right, Straight, Left],
+            DubinsWord::Rsr => [Right, Straight, Right],
+            DubinsWord::Rlr => [Right, Left, Right],
+            DubinsWord::Lrl => [Left, Right, Left],
+        }
+    }
+}
+
+/// Shortest forward-only path between two poses for a car with a minimum
+/// turning radius.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DubinsPath {
+    pub start: Pose,
+    pub radius: f32,
+    pub word: DubinsWord,
+    /// Length of each segment in world units.
+    pub lengths: [f32; 3],
+}
+
+impl DubinsPath {
+    /// Shortest Dubins path, or `None` if `radius` is not positive.
+    pub fn shortest(start: Pose, goal: Pose, radius: f32) -> Option<Self> {
+        DubinsWord::ALL
+            .iter()
+            .filter_map(|&word| Self::with_word(start, goal, radius, word))
+            .min_by(|a, b| a.length().total_cmp(&b.length()))
+    }
+
+    /// Path of a given word, if that word can connect the two poses.
+    pub fn with_word(start: Pose, goal: Pose, radius: f32, word: DubinsWord) -> Option<Self> {
+        if radius <= 0.0 {
+            return None;
+        }
+        let dx = goal.x - start.x;
+        let dy = goal.y - start.y;
+        let d = libm::sqrtf(dx * dx + dy * dy) / radius;
+        let angle = libm::atan2f(dy, dx);
+        let a = mod2pi(start.theta - angle);
+        let b = mod2pi(goal.theta - angle);
+        let (sa, ca, sb, cb) = (libm::sinf(a), libm::cosf(a), libm::sinf(b), libm::cosf(b));
+        let c_ab = libm::cosf(a - b);
+
+        // Normalised segment lengths (Shkel & Lumelsky)
+        let [t, p, q] = match word {
+            DubinsWord::Lsl => {
+                let p_sq = 2.0 + d * d - 2.0 * c_ab + 2.0 * d * (sa - sb);
+                if p_sq < 0.0 {
+                    return None;
+                }
+                let tmp = libm::atan2f(cb - ca, d + sa - sb);
+                [mod2pi(tmp - a), libm::sqrtf(p_sq), mod2pi(b - tmp)]
+            }
+            DubinsWord::Rsr => {
+                let p_sq = 2.0 + d * d - 2.0 * c_ab + 2.0 * d * (sb - sa);
+                if p_sq < 0.0 {
+                    return None;
+                }
+                let tmp = libm::atan2f(ca - cb, d - sa + sb);
+                [mod2pi(a - tmp), libm::sqrtf(p_sq), mod2pi(tmp - b)]
+            }
+            DubinsWord::Lsr => {
+                let p_sq = -2.0 + d * d + 2.0 * c_ab + 2.0 * d * (sa + sb);
+                if p_sq < 0.0 {
+                    return None;
+                }
+                let p = libm::sqrtf(p_sq);
+                let tmp = libm::atan2f(-ca - cb, d + sa + sb) - libm::atan2f(-2.0, p);
+                [mod2pi(tmp - a), p, mod2pi(tmp - b)]
+            }
+            DubinsWord::Rsl => {
+                let p_sq = -2.0 + d * d + 2.0 * c_ab - 2.0 * d * (sa + sb);
+                if p_sq < 0.0 {
+                    return None;
+                }
+                let p = libm::sqrtf(p_sq);
+                let tmp = libm::atan2f(ca + cb, d - sa - sb) - libm::atan2f(2.0, p);
+                [mod2pi(a - tmp), p, mod2pi(b - tmp)]
+            }
+            DubinsWord::Rlr => {
+                let tmp = (6.0 - d * d + 2.0 * c_ab + 2.0 * d * (sa - sb)) / 8.0;
+                if tmp.abs() > 1.0 {
+                    return None;
+                }
+                let p = mod2pi(2.0 * PI - libm::acosf(tmp));
+                let t = mod2pi(a - libm::atan2f(ca - cb, d - sa + sb) + p / 2.0);
+                [t, p, mod2pi(a - b - t + p)]
+            }
+            DubinsWord::Lrl => {
+                let tmp = (6.0 - d * d + 2.0 * c_ab + 2.0 * d * (sb - sa)) / 8.0;
+                if tmp.abs() > 1.0 {
+                    return None;
+                }
+                let p = mod2pi(2.0 * PI - libm::acosf(tmp));
+                let t = mod2pi(-a - libm::atan2f(ca - cb, d + sa - sb) + p / 2.0);
+                [t, p, mod2pi(b - a - t + p)]
+            }
+        };
+
+        Some(Self {
+            start,
+            radius,
+            word,
+            lengths: [t * radius, p * radius, q * radius],
+        })
+    }
+
+    pub fn length(&self) -> f32 {
+        self.lengths.iter().sum()
+    }
+
+    pub fn segments(&self) -> [(Steer, f32); 3] {
+        let steer = self.word.steering();
+        [
+            (steer[0], self.lengths[0]),
+            (steer[1], self.lengths[1]),
+            (steer[2], self.lengths[2]),
+        ]
+    }
+
+    /// Pose after driving `s` along the path (clamped to the path).
+    pub fn sample(&self, s: f32) -> Pose {
+        sample_segments(self.start, &self.segments(), self.radius, s)
+    }
+
+    /// Poses every `step` along the path (at least 1e-3), including both
+    /// ends.
+    pub fn sample_many(&self, step: f32) -> Vec<Pose> {
+        sample_many(self.start, &self.segments(), self.radius, step)
+    }
+
+    pub fn end(&self) -> Pose {
+        self.sample(self.length())
+    }
+}
+
+#[cfg(test)]
+#[path = "dubins_tests.rs"]
+mod tests;
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::dubins::{advance, DubinsPath, DubinsWord, Steer};
+    use crate::math::{wrap_angle, Pose};
+    use crate::rng::Rng;
+    use core::f32::consts::PI;
+
+    fn assert_pose_near(a: Pose, b: Pose, tol: f32) {
+        assert!((a.x - b.x).abs() < tol, "{:?} vs {:?}", a, b);
+        assert!((a.y - b.y).abs() < tol, "{:?} vs {:?}", a, b);
+        assert!(
+            wrap_angle(a.theta - b.theta).abs() < tol,
+            "{:?} vs {:?}",
+            a,
+            b
+        );
+    }
+
+    // ==================== SEGMENTS ====================
+
+    #[test]
+    fn test_advance_straight_and_arcs() {
+        let p = Pose::new(0.0, 0.0, 0.0);
+        assert_pose_near(
+            advance(p, Steer::Straight, 2.0, 1.0),
+            Pose::new(2.0, 0.0, 0.0),
+            1e-5,
+        );
+        // Quarter turn left with radius 2 ends at (2, 2) facing +y
+        let left = advance(p, Steer::Left, PI, 2.0);
+        assert_pose_near(left, Pose::new(2.0, 2.0, PI / 2.0), 1e-5);
+        let right = advance(p, Steer::Right, PI, 2.0);
+        assert_pose_near(right, Pose::new(2.0, -2.0, -PI / 2.0), 1e-5);
+        // Reversing retraces the arc
+        assert_pose_near(advance(left, Steer::Left, -PI, 2.0), p, 1e-5);
+    }
+
+    // ==================== DUBINS ====================
+
+    #[test]
+    fn test_straight_line() {
+        let path =
+            DubinsPath::shortest(Pose::new(0.0, 0.0, 0.0), Pose::new(10.0, 0.0, 0.0), 1.0).unwrap();
+        assert!((path.length() - 10.0).abs() < 1e-4);
+        assert_pose_near(path.end(), Pose::new(10.0, 0.0, 0.0), 1e-4);
+    }
+
+    #[test]
+    fn test_u_turn_length() {
+        // Turning around onto the parallel lane two radii away is a half circle
+        let path =
+            DubinsPath::shortest(Pose::new(0.0, 0.0, 0.0), Pose::new(0.0, 2.0, PI), 1.0).unwrap();
+        assert!((path.length() - PI).abs() < 1e-3);
+        assert_eq!(path.word, DubinsWord::Lsl);
+    }
+
+    #[test]
+    fn test_every_word_reaches_goal() {
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let start = Pose::new(
+                rng.range(-5.0, 5.0),
+                rng.range(-5.0, 5.0),
+                rng.range(-PI, PI),
+            );
+            let goal = Pose::new(
+                rng.range(-5.0, 5.0),
+                rng.range(-5.0, 5.0),
+                rng.range(-PI, PI),
+            );
+            let radius = rng.range(0.5, 2.0);
+            for word in DubinsWord::ALL {
+                if let Some(path) = DubinsPath::with_word(start, goal, radius, word) {
+                    assert_pose_near(path.end(), goal, 2e-3);
+                }
+            }
+            let best = DubinsPath::shortest(start, goal, radius).unwrap();
+            assert!(best.length() >= start.position().distance(&goal.position()) - 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_close_poses_use_ccc() {
+        // Same position, opposite heading: CSC words are longer than RLR/LRL
+        let start = Pose::new(0.0, 0.0, 0.0);
+        let goal = Pose::new(0.5, 0.0, PI);
+        let path = DubinsPath::shortest(start, goal, 1.0).unwrap();
+        assert!(matches!(path.word, DubinsWord::Rlr | DubinsWord::Lrl));
+        assert_pose_near(path.end(), goal, 1e-3);
+    }
+
+    #[test]
+    fn test_sample_many_spacing() {
+        let path =
+            DubinsPath::shortest(Pose::new(0.0, 0.0, 0.0), Pose::new(4.0, 4.0, PI / 2.0), 1.0)
+                .unwrap();
+        let poses = path.sample_many(0.1);
+        assert_pose_near(poses[0], path.start, 1e-6);
+        assert_pose_near(*poses.last().unwrap(), path.end(), 1e-6);
+        for w in poses.windows(2) {
+            assert!(w[0].position().distance(&w[1].position()) <= 0.1 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_zero_step_samples_whole_path() {
+        let path =
+            DubinsPath::shortest(Pose::new(0.0, 0.0, 0.0), Pose::new(4.0, 0.0, 0.0), 1.0).unwrap();
+        for step in [0.0, -1.0] {
+            let poses = path.sample_many(step);
+            assert_eq!(poses.len(), 4001);
+            assert_pose_near(poses[2000], Pose::new(2.0, 0.0, 0.0), 1e-3);
+            assert_pose_near(*poses.last().unwrap(), path.end(), 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_invalid_radius() {
+        let p = Pose::new(0.0, 0.0, 0.0);
+        assert!(DubinsPath::shortest(p, p, 0.0).is_none());
+    }
+}
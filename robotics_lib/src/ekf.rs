@@ -1,4 +1,4 @@
-use crate::math::Vec2;
+use crate::math::{wrap_angle, Matrix, Pose, Vec2, Vector};
 
 // ... Mat2 struct and impls ...
 
@@ -93,11 +93,178 @@ impl Mat2 {
     }
 }
 
+impl From<Mat2> for Matrix<2, 2> {
+    fn from(m: Mat2) -> Self {
+        Matrix::new([[m.m11, m.m12], [m.m21, m.m22]])
+    }
+}
+
+impl From<Matrix<2, 2>> for Mat2 {
+    fn from(m: Matrix<2, 2>) -> Self {
+        Mat2::new(m[(0, 0)], m[(0, 1)], m[(1, 0)], m[(1, 1)])
+    }
+}
+
 // ... EKF struct and impls ...
 
-/// Extended Kalman Filter for 2D Unicycle Model
-/// State: [x, y, theta] (approximated here as [x, y] + velocity for simplicity in boid context,
-/// but we'll do a proper discrete step estimation)
+// Kalman correction with innovation `y` and measurement Jacobian `h`, in
+// Joseph form to keep P symmetric and positive. Leaves the estimate
+// unchanged and returns false if S is singular.
+fn kalman_correct<const N: usize, const M: usize>(
+    state: &mut Vector<N>,
+    covariance: &mut Matrix<N, N>,
+    y: &Vector<M>,
+    h: &Matrix<M, N>,
+    r: &Matrix<M, M>,
+) -> bool {
+    let pht = *covariance * h.transpose();
+    let s = *h * pht + *r;
+    // K = P H' S^-1, solved as S K' = H P since S and P are symmetric
+    let Some(kt) = s.solve(&pht.transpose()) else {
+        return false;
+    };
+    let k = kt.transpose();
+    *state = *state + k * *y;
+    let a = Matrix::identity() - k * *h;
+    *covariance = a * *covariance * a.transpose() + k * *r * k.transpose();
+    true
+}
+
+/// Linear Kalman filter with `N` states and `M` measurements:
+/// x' = F x (+ B u) and z = H x, with process noise Q and measurement
+/// noise R. Sizes are const generics, so the filter lives on the stack.
+#[derive(Clone, Copy, Debug)]
+pub struct KalmanFilter<const N: usize, const M: usize> {
+    pub state: Vector<N>,
+    pub covariance: Matrix<N, N>,
+    pub transition: Matrix<N, N>,        // F
+    pub observation: Matrix<M, N>,       // H
+    pub process_noise: Matrix<N, N>,     // Q
+    pub measurement_noise: Matrix<M, M>, // R
+}
+
+impl<const N: usize, const M: usize> KalmanFilter<N, M> {
+    pub fn new(
+        state: Vector<N>,
+        covariance: Matrix<N, N>,
+        transition: Matrix<N, N>,
+        observation: Matrix<M, N>,
+        process_noise: Matrix<N, N>,
+        measurement_noise: Matrix<M, M>,
+    ) -> Self {
+        Self {
+            state,
+            covariance,
+            transition,
+            observation,
+            process_noise,
+            measurement_noise,
+        }
+    }
+
+    /// x = F x, P = F P F' + Q.
+    pub fn predict(&mut self) {
+        let f = self.transition;
+        self.state = f * self.state;
+        self.covariance = f * self.covariance * f.transpose() + self.process_noise;
+    }
+
+    /// Prediction with a control input: x = F x + B u.
+    pub fn predict_control<const U: usize>(&mut self, b: &Matrix<N, U>, u: &Vector<U>) {
+        self.predict();
+        self.state = self.state + *b * *u;
+    }
+
+    /// Correct with measurement `z`. Returns false, leaving the estimate
+    /// unchanged, if the innovation covariance is singular.
+    pub fn update(&mut self, z: &Vector<M>) -> bool {
+        let y = *z - self.observation * self.state;
+        kalman_correct(
+            &mut self.state,
+            &mut self.covariance,
+            &y,
+            &self.observation,
+            &self.measurement_noise,
+        )
+    }
+}
+
+/// Extended Kalman filter with `N` states and `M` measurements.
+///
+/// The motion and measurement models and their Jacobians are closures
+/// passed to each `predict` and `update`, so one filter type serves any
+/// model without boxing or allocation.
+#[derive(Clone, Copy, Debug)]
+pub struct ExtendedKalmanFilter<const N: usize, const M: usize> {
+    pub state: Vector<N>,
+    pub covariance: Matrix<N, N>,
+    pub process_noise: Matrix<N, N>,     // Q
+    pub measurement_noise: Matrix<M, M>, // R
+}
+
+impl<const N: usize, const M: usize> ExtendedKalmanFilter<N, M> {
+    pub fn new(
+        state: Vector<N>,
+        covariance: Matrix<N, N>,
+        process_noise: Matrix<N, N>,
+        measurement_noise: Matrix<M, M>,
+    ) -> Self {
+        Self {
+            state,
+            covariance,
+            process_noise,
+            measurement_noise,
+        }
+    }
+
+    /// x = f(x), P = F P F' + Q with F = `jacobian` at the prior state.
+    pub fn predict(
+        &mut self,
+        motion: impl Fn(&Vector<N>) -> Vector<N>,
+        jacobian: impl Fn(&Vector<N>) -> Matrix<N, N>,
+    ) {
+        let f = jacobian(&self.state);
+        self.state = motion(&self.state);
+        self.covariance = f * self.covariance * f.transpose() + self.process_noise;
+    }
+
+    /// Correct with measurement `z` of the model `measure`, linearised by
+    /// `jacobian` at the predicted state. Returns false, leaving the
+    /// estimate unchanged, if the innovation covariance is singular.
+    pub fn update(
+        &mut self,
+        z: &Vector<M>,
+        measure: impl Fn(&Vector<N>) -> Vector<M>,
+        jacobian: impl Fn(&Vector<N>) -> Matrix<M, N>,
+    ) -> bool {
+        self.update_with(z, measure, jacobian, |z, expected| *z - *expected)
+    }
+
+    /// [`ExtendedKalmanFilter::update`] with a custom innovation
+    /// `residual(z, expected)`, e.g. to wrap bearing differences.
+    pub fn update_with(
+        &mut self,
+        z: &Vector<M>,
+        measure: impl Fn(&Vector<N>) -> Vector<M>,
+        jacobian: impl Fn(&Vector<N>) -> Matrix<M, N>,
+        residual: impl Fn(&Vector<M>, &Vector<M>) -> Vector<M>,
+    ) -> bool {
+        let h = jacobian(&self.state);
+        let y = residual(z, &measure(&self.state));
+        kalman_correct(
+            &mut self.state,
+            &mut self.covariance,
+            &y,
+            &h,
+            &self.measurement_noise,
+        )
+    }
+}
+
+/// Position-only Kalman filter used by the boids: state [x, y], driven by a
+/// known velocity and corrected by position fixes. A thin wrapper over a
+/// [`KalmanFilter<2, 2>`] with F = H = I; see [`UnicycleEkf`] for a
+/// nonlinear filter.
 #[derive(Clone, Debug)]
 pub struct EKF {
     pub state: Vec2,             // Estimated Position
@@ -116,43 +283,205 @@ impl EKF {
         }
     }
 
-    pub fn predict(&mut self, velocity: Vec2, dt: f32) {
-        // x_pred = F * x + B * u
-        // Linear model for position update: pos = pos + vel * dt
-        // Jacobian F is Identity for simple linear motion
-        let f = Mat2::identity();
+    fn filter(&self) -> KalmanFilter<2, 2> {
+        KalmanFilter::new(
+            self.state.into(),
+            self.covariance.into(),
+            Matrix::identity(),
+            Matrix::identity(),
+            self.process_noise.into(),
+            self.measurement_noise.into(),
+        )
+    }
 
-        self.state = self.state + velocity * dt;
+    fn store(&mut self, filter: &KalmanFilter<2, 2>) {
+        self.state = filter.state.into();
+        self.covariance = filter.covariance.into();
+    }
 
-        // P_pred = F * P * F' + Q
-        let p_ft = self.covariance.mul(f.transpose());
-        let f_p_ft = f.mul(p_ft);
-        self.covariance = f_p_ft.add(self.process_noise);
+    pub fn predict(&mut self, velocity: Vec2, dt: f32) {
+        // pos = pos + vel * dt, so B = I dt with the velocity as input
+        let mut filter = self.filter();
+        filter.predict_control(&(Matrix::identity() * dt), &velocity.into());
+        self.store(&filter);
     }
 
     pub fn update(&mut self, measurement: Vec2) {
-        // y = z - H * x_pred
-        // H is Identity if we measure position directly
-        let h = Mat2::identity();
-        let y = measurement - self.state;
-
-        // S = H * P_pred * H' + R
-        let p_ht = self.covariance.mul(h.transpose());
-        let h_p_ht = h.mul(p_ht);
-        let s = h_p_ht.add(self.measurement_noise);
-
-        // K = P_pred * H' * S^-1
-        if let Some(s_inv) = s.inverse() {
-            let k = p_ht.mul(s_inv); // p_ht is P * H'
-
-            // x_new = x_pred + K * y
-            self.state = self.state + k.mul_vec(y);
-
-            // P_new = (I - K * H) * P_pred
-            let kh = k.mul(h);
-            let i = Mat2::identity();
-            let i_minus_kh = i.sub(kh);
-            self.covariance = i_minus_kh.mul(self.covariance);
+        let mut filter = self.filter();
+        if filter.update(&measurement.into()) {
+            self.store(&filter);
+        }
+    }
+}
+
+/// Number of states of [`UnicycleEkf`].
+pub const UNICYCLE_STATES: usize = 5;
+
+type State = [f32; UNICYCLE_STATES];
+type Covariance = [[f32; UNICYCLE_STATES]; UNICYCLE_STATES];
+
+// Below this turn rate the straight-line motion model is used
+const MIN_TURN_RATE: f32 = 1e-4;
+
+/// Noise settings of [`UnicycleEkf`], all variances.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnicycleNoise {
+    /// Growth per second of each state's variance, [x, y, theta, v, omega].
+    pub process: State,
+    /// Position fix, per axis (m^2).
+    pub position: f32,
+    /// Heading measurement (rad^2).
+    pub heading: f32,
+    /// Odometry speed and turn rate.
+    pub odometry: [f32; 2],
+}
+
+impl Default for UnicycleNoise {
+    fn default() -> Self {
+        Self {
+            process: [0.01, 0.01, 0.01, 0.5, 0.1],
+            position: 1.0,
+            heading: 0.05,
+            odometry: [0.05, 0.01],
+        }
+    }
+}
+
+/// Extended Kalman filter for a unicycle with constant speed and turn rate
+/// between steps.
+///
+/// State [x, y, theta, v, omega]. Over a step the robot drives an arc of
+/// radius v / omega (a straight line when omega is near zero); `predict`
+/// propagates the covariance with the analytic Jacobian of that motion.
+/// Headings are kept in (-PI, PI] and heading innovations are wrapped, so
+/// crossing the +-PI seam does not look like a full-turn error.
+#[derive(Clone, Debug)]
+pub struct UnicycleEkf {
+    pub state: State,
+    pub covariance: Covariance,
+    pub noise: UnicycleNoise,
+}
+
+impl UnicycleEkf {
+    pub fn new(initial: Pose, speed: f32, turn_rate: f32) -> Self {
+        Self {
+            state: [
+                initial.x,
+                initial.y,
+                wrap_angle(initial.theta),
+                speed,
+                turn_rate,
+            ],
+            covariance: Matrix::identity().data,
+            noise: UnicycleNoise::default(),
+        }
+    }
+
+    pub fn pose(&self) -> Pose {
+        Pose::new(self.state[0], self.state[1], self.state[2])
+    }
+
+    pub fn position(&self) -> Vec2 {
+        Vec2::new(self.state[0], self.state[1])
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.state[3]
+    }
+
+    pub fn turn_rate(&self) -> f32 {
+        self.state[4]
+    }
+
+    /// State after driving for `dt` seconds.
+    pub fn motion(state: &State, dt: f32) -> State {
+        let [x, y, theta, v, w] = *state;
+        let (s0, c0) = (libm::sinf(theta), libm::cosf(theta));
+        let (dx, dy) = if w.abs() > MIN_TURN_RATE {
+            let (s1, c1) = (libm::sinf(theta + w * dt), libm::cosf(theta + w * dt));
+            (v / w * (s1 - s0), v / w * (c0 - c1))
+        } else {
+            (v * c0 * dt, v * s0 * dt)
+        };
+        [x + dx, y + dy, wrap_angle(theta + w * dt), v, w]
+    }
+
+    /// Jacobian of [`UnicycleEkf::motion`] with respect to the state.
+    pub fn motion_jacobian(state: &State, dt: f32) -> Covariance {
+        let [_, _, theta, v, w] = *state;
+        let (s0, c0) = (libm::sinf(theta), libm::cosf(theta));
+        let mut f = Matrix::<UNICYCLE_STATES, UNICYCLE_STATES>::identity().data;
+        if w.abs() > MIN_TURN_RATE {
+            let (s1, c1) = (libm::sinf(theta + w * dt), libm::cosf(theta + w * dt));
+            f[0][2] = v / w * (c1 - c0);
+            f[0][3] = (s1 - s0) / w;
+            f[0][4] = v * c1 * dt / w - v * (s1 - s0) / (w * w);
+            f[1][2] = v / w * (s1 - s0);
+            f[1][3] = (c0 - c1) / w;
+            f[1][4] = v * s1 * dt / w - v * (c0 - c1) / (w * w);
+        } else {
+            // Limits of the arc terms as omega goes to zero
+            f[0][2] = -v * s0 * dt;
+            f[0][3] = c0 * dt;
+            f[0][4] = -0.5 * v * s0 * dt * dt;
+            f[1][2] = v * c0 * dt;
+            f[1][3] = s0 * dt;
+            f[1][4] = 0.5 * v * c0 * dt * dt;
+        }
+        f[2][4] = dt;
+        f
+    }
+
+    /// Propagate the state and covariance over `dt` seconds:
+    /// P = F P F' + Q dt.
+    pub fn predict(&mut self, dt: f32) {
+        let f = Matrix::new(Self::motion_jacobian(&self.state, dt));
+        self.state = Self::motion(&self.state, dt);
+        let q = Matrix::diagonal(self.noise.process) * dt;
+        self.covariance = (f * Matrix::new(self.covariance) * f.transpose() + q).data;
+    }
+
+    /// Correct with a position fix.
+    pub fn update_position(&mut self, z: Vec2) {
+        let r = self.noise.position;
+        let innovation = [z.x - self.state[0], z.y - self.state[1]];
+        self.correct([0, 1], innovation, [[r, 0.0], [0.0, r]]);
+    }
+
+    /// Correct with an absolute heading, e.g. from a compass.
+    pub fn update_heading(&mut self, theta: f32) {
+        let innovation = [wrap_angle(theta - self.state[2])];
+        self.correct([2], innovation, [[self.noise.heading]]);
+    }
+
+    /// Correct with wheel odometry: measured speed and turn rate.
+    pub fn update_odometry(&mut self, speed: f32, turn_rate: f32) {
+        let [rv, rw] = self.noise.odometry;
+        let innovation = [speed - self.state[3], turn_rate - self.state[4]];
+        self.correct([3, 4], innovation, [[rv, 0.0], [0.0, rw]]);
+    }
+
+    // Kalman update for a measurement of the states in `observed`, so H
+    // selects them
+    fn correct<const M: usize>(
+        &mut self,
+        observed: [usize; M],
+        innovation: [f32; M],
+        r: [[f32; M]; M],
+    ) {
+        let h = Matrix::from_fn(|i, j| if observed[i] == j { 1.0 } else { 0.0 });
+        let mut state = Vector::from_array(self.state);
+        let mut covariance = Matrix::new(self.covariance);
+        if kalman_correct(
+            &mut state,
+            &mut covariance,
+            &innovation.into(),
+            &h,
+            &Matrix::new(r),
+        ) {
+            self.state = state.to_array();
+            self.state[2] = wrap_angle(self.state[2]);
+            self.covariance = covariance.data;
         }
     }
 }
@@ -0,0 +1,282 @@
+#[cfg(test)]
+mod tests {
+    use crate::ekf_slam::{Association, EkfSlam, Landmark, RangeBearing};
+    use crate::math::{wrap_angle, Matrix, Pose, Vec2};
+    use crate::rng::Rng;
+    use alloc::vec::Vec;
+    use core::f32::consts::{FRAC_PI_2, PI};
+
+    fn certain(pose: Pose) -> EkfSlam {
+        EkfSlam::new(pose, Matrix::zeros())
+    }
+
+    fn assert_symmetric(slam: &EkfSlam) {
+        let n = slam.state().len();
+        for i in 0..n {
+            assert!(slam.covariance(i, i) >= 0.0);
+            for j in 0..n {
+                let (a, b) = (slam.covariance(i, j), slam.covariance(j, i));
+                assert!((a - b).abs() < 1e-5, "P[{}][{}] {} vs {}", i, j, a, b);
+            }
+        }
+    }
+
+    // ==================== MEASUREMENTS ====================
+
+    #[test]
+    fn test_range_bearing_round_trip() {
+        let pose = Pose::new(1.0, 2.0, FRAC_PI_2);
+        let z = RangeBearing::observe(pose, Vec2::new(1.0, 5.0));
+        assert!((z.range - 3.0).abs() < 1e-5 && z.bearing.abs() < 1e-5);
+        let z = RangeBearing::observe(pose, Vec2::new(-1.0, 2.0));
+        assert!((z.bearing - FRAC_PI_2).abs() < 1e-5);
+        let p = RangeBearing::new(2.0, -0.7).endpoint(pose);
+        let back = RangeBearing::observe(pose, p);
+        assert!((back.range - 2.0).abs() < 1e-5 && (back.bearing + 0.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_ellipse_axes() {
+        let landmark = Landmark {
+            position: Vec2::zero(),
+            covariance: Matrix::diagonal([0.04, 0.01]),
+        };
+        let (major, minor, angle) = landmark.ellipse(2.0);
+        assert!((major - 0.4).abs() < 1e-5 && (minor - 0.2).abs() < 1e-5);
+        assert!(angle.abs() < 1e-5);
+        // Correlated x and y tilt the ellipse onto the diagonal
+        let tilted = Landmark {
+            position: Vec2::zero(),
+            covariance: Matrix::new([[0.02, 0.01], [0.01, 0.02]]),
+        };
+        let (major, minor, angle) = tilted.ellipse(1.0);
+        assert!((major * major - 0.03).abs() < 1e-5 && (minor * minor - 0.01).abs() < 1e-5);
+        assert!((angle - PI / 4.0).abs() < 1e-4);
+    }
+
+    // ==================== LANDMARKS ====================
+
+    #[test]
+    fn test_new_landmark_from_observation() {
+        let mut slam = certain(Pose::new(2.0, 1.0, 0.0));
+        let index = slam.add_landmark(RangeBearing::new(3.0, FRAC_PI_2));
+        assert_eq!(index, 0);
+        assert_eq!(slam.landmark_count(), 1);
+        let landmark = slam.landmark(0).unwrap();
+        assert!(landmark.position.distance(&Vec2::new(2.0, 4.0)) < 1e-5);
+        // With a certain pose the spread is the measurement noise alone:
+        // range along y, bearing times range across it
+        let c = landmark.covariance;
+        assert!((c[(1, 1)] - slam.noise.range).abs() < 1e-5);
+        assert!((c[(0, 0)] - 9.0 * slam.noise.bearing).abs() < 1e-5);
+        assert!(c[(0, 1)].abs() < 1e-5);
+        assert_symmetric(&slam);
+    }
+
+    #[test]
+    fn test_pose_uncertainty_carries_into_landmark() {
+        let p0 = Matrix::diagonal([0.5, 0.5, 0.0]);
+        let mut slam = EkfSlam::new(Pose::new(0.0, 0.0, 0.0), p0);
+        slam.add_landmark(RangeBearing::new(2.0, 0.0));
+        let c = slam.landmark(0).unwrap().covariance;
+        assert!((c[(0, 0)] - 0.5 - slam.noise.range).abs() < 1e-5);
+        // Fully correlated with the robot: learning where the robot is
+        // moves the landmark with it
+        assert!((slam.covariance(0, 3) - 0.5).abs() < 1e-5);
+        assert!((slam.covariance(1, 4) - 0.5).abs() < 1e-5);
+        assert_symmetric(&slam);
+    }
+
+    #[test]
+    fn test_predict_grows_pose_only() {
+        let mut slam = certain(Pose::new(0.0, 0.0, 0.0));
+        slam.add_landmark(RangeBearing::new(2.0, 0.5));
+        let before = slam.landmark(0).unwrap();
+        for _ in 0..10 {
+            slam.predict(1.0, 0.2, 0.1);
+        }
+        let pose = slam.pose();
+        assert!(pose.x > 0.9 && pose.x < 1.0 && pose.y > 0.0);
+        assert!((pose.theta - 0.2).abs() < 1e-5);
+        let p = slam.pose_covariance();
+        assert!(p[(0, 0)] > 0.0 && p[(1, 1)] > 0.0 && p[(2, 2)] > 0.0);
+        assert_eq!(slam.landmark(0).unwrap(), before);
+        assert_symmetric(&slam);
+    }
+
+    #[test]
+    fn test_reobserving_shrinks_covariance() {
+        let mut slam = EkfSlam::new(
+            Pose::new(0.0, 0.0, 0.0),
+            Matrix::diagonal([0.01, 0.01, 0.001]),
+        );
+        let landmark = Vec2::new(3.0, 1.0);
+        slam.add_landmark(RangeBearing::observe(slam.pose(), landmark));
+        let first = slam.landmark(0).unwrap().covariance.trace();
+        for _ in 0..5 {
+            slam.predict(0.5, 0.0, 0.2);
+            assert!(slam.update(
+                0,
+                RangeBearing::observe(Pose::new(slam.pose().x, 0.0, 0.0), landmark)
+            ));
+        }
+        assert!(slam.landmark(0).unwrap().covariance.trace() < first);
+        assert!(slam.landmark(0).unwrap().position.distance(&landmark) < 0.05);
+        assert_symmetric(&slam);
+    }
+
+    #[test]
+    fn test_unknown_landmark_index() {
+        let mut slam = certain(Pose::new(0.0, 0.0, 0.0));
+        let z = RangeBearing::new(2.0, 0.3);
+        assert!(slam.landmark(0).is_none());
+        assert!(slam.mahalanobis(0, z).is_none());
+        assert!(!slam.update(0, z));
+        slam.add_landmark(z);
+        let before = slam.state().to_vec();
+        assert!(slam.landmark(1).is_none());
+        assert!(slam.mahalanobis(1, z).is_none());
+        assert!(!slam.update(1, z));
+        assert_eq!(slam.state(), &before[..]);
+    }
+
+    // ==================== DATA ASSOCIATION ====================
+
+    #[test]
+    fn test_gating_matches_or_creates() {
+        let mut slam = certain(Pose::new(0.0, 0.0, 0.0));
+        let a = Vec2::new(4.0, 0.0);
+        let b = Vec2::new(0.0, 4.0);
+        assert_eq!(
+            slam.observe(RangeBearing::observe(slam.pose(), a)),
+            Association::New(0)
+        );
+        // A small error on the same landmark is a match
+        let near = RangeBearing::observe(slam.pose(), a + Vec2::new(0.05, 0.05));
+        assert!(slam.mahalanobis(0, near).unwrap() < slam.gate);
+        assert_eq!(slam.observe(near), Association::Matched(0));
+        // A landmark well away from the first is new
+        assert_eq!(
+            slam.observe(RangeBearing::observe(slam.pose(), b)),
+            Association::New(1)
+        );
+        // Somewhere between the two gates is neither
+        let z = RangeBearing::observe(slam.pose(), a);
+        let ambiguous = RangeBearing::new(z.range + 0.4, z.bearing);
+        let d = slam.mahalanobis(0, ambiguous).unwrap();
+        assert!(d > slam.gate && d < slam.new_landmark_gate, "{}", d);
+        assert_eq!(slam.observe(ambiguous), Association::Rejected);
+        assert_eq!(slam.landmark_count(), 2);
+    }
+
+    // ==================== SIMULATION ====================
+
+    // Landmarks on two rings around a circular drive, observed within
+    // `SENSOR_RANGE` with noisy range and bearing, and driven with noisy
+    // odometry. Association is left to the gates.
+    const SENSOR_RANGE: f32 = 4.0;
+
+    fn landmarks() -> Vec<Vec2> {
+        (0..12)
+            .map(|i| {
+                let angle = i as f32 * PI / 6.0;
+                let radius = if i % 2 == 0 { 3.0 } else { 10.0 };
+                Vec2::new(radius * libm::cosf(angle), radius * libm::sinf(angle))
+            })
+            .collect()
+    }
+
+    // Returns the filter, the true pose and the dead-reckoned pose
+    fn circle(seed: u64, laps: f32) -> (EkfSlam, Pose, Pose) {
+        let (v, w, dt) = (1.0, 0.2, 0.1);
+        let mut rng = Rng::new(seed);
+        let start = Pose::new(0.0, -5.0, 0.0);
+        let mut slam = EkfSlam::new(start, Matrix::zeros());
+        let (speed_sd, turn_sd) = (
+            libm::sqrtf(slam.noise.speed),
+            libm::sqrtf(slam.noise.turn_rate),
+        );
+        let (range_sd, bearing_sd) = (
+            libm::sqrtf(slam.noise.range),
+            libm::sqrtf(slam.noise.bearing),
+        );
+        let world = landmarks();
+        let (mut truth, mut dead) = (start, start);
+        let steps = (laps * 2.0 * PI / (w * dt)) as usize;
+        for _ in 0..steps {
+            let (tv, tw) = (v + speed_sd * rng.gaussian(), w + turn_sd * rng.gaussian());
+            truth = Pose::new(
+                truth.x + tv * libm::cosf(truth.theta) * dt,
+                truth.y + tv * libm::sinf(truth.theta) * dt,
+                wrap_angle(truth.theta + tw * dt),
+            );
+            dead = Pose::new(
+                dead.x + v * libm::cosf(dead.theta) * dt,
+                dead.y + v * libm::sinf(dead.theta) * dt,
+                wrap_angle(dead.theta + w * dt),
+            );
+            slam.predict(v, w, dt);
+            let scan: Vec<RangeBearing> = world
+                .iter()
+                .map(|&l| RangeBearing::observe(truth, l))
+                .filter(|z| z.range < SENSOR_RANGE)
+                .map(|z| {
+                    RangeBearing::new(
+                        z.range + range_sd * rng.gaussian(),
+                        wrap_angle(z.bearing + bearing_sd * rng.gaussian()),
+                    )
+                })
+                .collect();
+            slam.observe_all(&scan);
+        }
+        (slam, truth, dead)
+    }
+
+    #[test]
+    fn test_maps_landmarks_around_loop() {
+        let (slam, truth, dead) = circle(3, 2.0);
+        let world = landmarks();
+        // The outer ring is beyond the sensor from the whole loop
+        let inner: Vec<Vec2> = world.iter().step_by(2).copied().collect();
+        assert_eq!(slam.landmark_count(), inner.len());
+        for (i, l) in slam.landmarks().iter().enumerate() {
+            let nearest = inner
+                .iter()
+                .map(|t| t.distance(&l.position))
+                .fold(f32::INFINITY, f32::min);
+            assert!(nearest < 0.3, "landmark {} off by {}", i, nearest);
+            assert!(l.covariance.trace() < 0.1);
+        }
+        let error = slam.pose().position().distance(&truth.position());
+        assert!(error < 0.3, "{}", error);
+        assert!(error < dead.position().distance(&truth.position()));
+        assert!(wrap_angle(slam.pose().theta - truth.theta).abs() < 0.1);
+        assert_symmetric(&slam);
+    }
+
+    #[test]
+    fn test_estimates_are_consistent() {
+        // Normalised estimation error squared of the landmarks, averaged
+        // over runs, should stay near its 2 degrees of freedom; an
+        // overconfident filter drifts far above it
+        let mut total = 0.0;
+        let mut count = 0;
+        let world = landmarks();
+        for seed in 0..10 {
+            let (slam, _, _) = circle(seed, 1.0);
+            for l in slam.landmarks() {
+                let truth = world
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| a.distance(&l.position).total_cmp(&b.distance(&l.position)))
+                    .unwrap();
+                let e = crate::math::Vector::from(l.position - truth);
+                let weighted = l.covariance.solve(&e).unwrap();
+                total += e.dot(&weighted);
+                count += 1;
+            }
+        }
+        let nees = total / count as f32;
+        assert!(nees < 6.0, "NEES {}", nees);
+    }
+}
@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::ekf::{Mat2, EKF};
-    use crate::math::Vec2;
+    use crate::ekf::{ExtendedKalmanFilter, KalmanFilter, Mat2, UnicycleEkf, EKF, UNICYCLE_STATES};
+    use crate::math::{wrap_angle, Matrix, Pose, Vec2, Vector};
+    use crate::rng::Rng;
 
     // ==================== MAT2 CONSTRUCTION ====================
 
@@ -369,4 +370,297 @@ mod tests {
         assert!(ekf.state.x.is_finite());
         assert!(ekf.state.y.is_finite());
     }
+
+    // ==================== UNICYCLE MOTION ====================
+
+    fn assert_symmetric_positive(ekf: &UnicycleEkf) {
+        let p = &ekf.covariance;
+        for (i, row) in p.iter().enumerate() {
+            assert!(row[i] > 0.0);
+            for (j, &value) in row.iter().enumerate() {
+                assert!((value - p[j][i]).abs() < 1e-4, "P[{}][{}]", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unicycle_straight_motion() {
+        let next = UnicycleEkf::motion(&[1.0, 2.0, core::f32::consts::FRAC_PI_2, 3.0, 0.0], 2.0);
+        assert!((next[0] - 1.0).abs() < 1e-5);
+        assert!((next[1] - 8.0).abs() < 1e-5);
+        assert_eq!(next[3], 3.0);
+    }
+
+    #[test]
+    fn test_unicycle_arc_motion() {
+        // A quarter circle of radius 2 from the origin heading +x, turning left
+        let w = core::f32::consts::FRAC_PI_2;
+        let next = UnicycleEkf::motion(&[0.0, 0.0, 0.0, 2.0 * w, w], 1.0);
+        assert!((next[0] - 2.0).abs() < 1e-4);
+        assert!((next[1] - 2.0).abs() < 1e-4);
+        assert!((next[2] - w).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_unicycle_heading_wraps() {
+        let next = UnicycleEkf::motion(&[0.0, 0.0, 3.0, 1.0, 1.0], 0.5);
+        assert!((next[2] - wrap_angle(3.5)).abs() < 1e-5);
+        assert!(next[2] < 0.0);
+    }
+
+    #[test]
+    fn test_unicycle_jacobian_matches_finite_differences() {
+        for state in [
+            [1.0, -2.0, 0.7, 1.5, 0.8],
+            [0.0, 0.0, -2.5, 2.0, -1.2],
+            // Near-straight branch
+            [3.0, 1.0, 1.0, 1.0, 0.0],
+        ] {
+            let dt = 0.2;
+            let f = UnicycleEkf::motion_jacobian(&state, dt);
+            let h = 1e-2;
+            for j in 0..UNICYCLE_STATES {
+                let (mut plus, mut minus) = (state, state);
+                plus[j] += h;
+                minus[j] -= h;
+                let (a, b) = (
+                    UnicycleEkf::motion(&plus, dt),
+                    UnicycleEkf::motion(&minus, dt),
+                );
+                for i in 0..UNICYCLE_STATES {
+                    // Heading differences are taken across the seam
+                    let diff = if i == 2 {
+                        wrap_angle(a[i] - b[i])
+                    } else {
+                        a[i] - b[i]
+                    };
+                    let numeric = diff / (2.0 * h);
+                    assert!(
+                        (f[i][j] - numeric).abs() < 2e-3,
+                        "F[{}][{}] = {} vs {}",
+                        i,
+                        j,
+                        f[i][j],
+                        numeric
+                    );
+                }
+            }
+        }
+    }
+
+    // ==================== UNICYCLE UPDATES ====================
+
+    #[test]
+    fn test_unicycle_predict_grows_and_update_shrinks_uncertainty() {
+        let mut ekf = UnicycleEkf::new(Pose::new(0.0, 0.0, 0.0), 1.0, 0.0);
+        ekf.predict(0.5);
+        let before = ekf.covariance[0][0];
+        assert!(before > 1.0);
+        ekf.update_position(Vec2::new(0.6, 0.1));
+        assert!(ekf.covariance[0][0] < before);
+        assert!(ekf.state[0] > 0.5 && ekf.state[0] < 0.6);
+        assert_symmetric_positive(&ekf);
+
+        let (v, w) = (ekf.covariance[3][3], ekf.covariance[4][4]);
+        ekf.update_odometry(1.2, 0.1);
+        assert!(ekf.covariance[3][3] < v && ekf.covariance[4][4] < w);
+        assert!(ekf.speed() > 1.0 && ekf.turn_rate() > 0.0);
+    }
+
+    #[test]
+    fn test_unicycle_heading_update_across_seam() {
+        let mut ekf = UnicycleEkf::new(Pose::new(0.0, 0.0, 3.1), 0.0, 0.0);
+        // -3.1 is 0.083 rad away across the seam, not 6.2
+        ekf.update_heading(-3.1);
+        let error = wrap_angle(ekf.state[2] - core::f32::consts::PI);
+        assert!(error.abs() < 0.05, "heading {}", ekf.state[2]);
+    }
+
+    #[test]
+    fn test_unicycle_tracks_circle_from_position_fixes() {
+        // Truth drives a circle; the filter starts with the wrong heading,
+        // speed and turn rate and only sees noisy positions
+        let (v, w, dt) = (2.0, 0.4, 0.1);
+        let mut truth = [0.0, 0.0, 0.0, v, w];
+        let mut ekf = UnicycleEkf::new(Pose::new(0.5, -0.5, 1.0), 0.5, 0.0);
+        let mut rng = Rng::new(3);
+        for _ in 0..400 {
+            truth = UnicycleEkf::motion(&truth, dt);
+            ekf.predict(dt);
+            let z = Vec2::new(
+                truth[0] + 0.3 * rng.gaussian(),
+                truth[1] + 0.3 * rng.gaussian(),
+            );
+            ekf.update_position(z);
+            assert_symmetric_positive(&ekf);
+        }
+        assert!(ekf.position().distance(&Vec2::new(truth[0], truth[1])) < 0.4);
+        assert!(wrap_angle(ekf.state[2] - truth[2]).abs() < 0.15);
+        assert!((ekf.speed() - v).abs() < 0.2);
+        assert!((ekf.turn_rate() - w).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_unicycle_fuses_all_measurements() {
+        let (v, w, dt) = (1.0, -0.3, 0.1);
+        let mut truth = [2.0, 1.0, 3.0, v, w];
+        let mut ekf = UnicycleEkf::new(Pose::new(2.0, 1.0, 3.0), 0.0, 0.0);
+        // The truth holds speed and turn rate constant
+        ekf.noise.process[3] = 0.01;
+        ekf.noise.process[4] = 0.001;
+        let mut rng = Rng::new(8);
+        for step in 0..300usize {
+            truth = UnicycleEkf::motion(&truth, dt);
+            ekf.predict(dt);
+            ekf.update_odometry(v + 0.2 * rng.gaussian(), w + 0.1 * rng.gaussian());
+            ekf.update_heading(wrap_angle(truth[2] + 0.2 * rng.gaussian()));
+            if step.is_multiple_of(10) {
+                ekf.update_position(
+                    Vec2::new(truth[0], truth[1]) + Vec2::new(rng.gaussian(), rng.gaussian()),
+                );
+            }
+        }
+        assert!(ekf.position().distance(&Vec2::new(truth[0], truth[1])) < 0.5);
+        assert!(wrap_angle(ekf.pose().theta - truth[2]).abs() < 0.1);
+        assert!((ekf.turn_rate() - w).abs() < 0.05);
+    }
+
+    // ==================== GENERIC KALMAN FILTER ====================
+
+    #[test]
+    fn test_kalman_filter_estimates_velocity() {
+        // State [x, y, vx, vy], position measurements only
+        let dt = 0.1;
+        let mut f = Matrix::<4, 4>::identity();
+        f[(0, 2)] = dt;
+        f[(1, 3)] = dt;
+        let h = Matrix::new([[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]]);
+        let mut kf = KalmanFilter::new(
+            Vector::zeros(),
+            Matrix::diagonal([1.0, 1.0, 10.0, 10.0]),
+            f,
+            h,
+            Matrix::diagonal([1e-4, 1e-4, 1e-3, 1e-3]),
+            Matrix::diagonal([0.25, 0.25]),
+        );
+        let mut rng = Rng::new(4);
+        let velocity = Vec2::new(1.0, -0.5);
+        let mut truth = Vec2::new(2.0, 3.0);
+        for _ in 0..300 {
+            truth = truth + velocity * dt;
+            kf.predict();
+            let z = truth + Vec2::new(rng.gaussian(), rng.gaussian()) * 0.5;
+            assert!(kf.update(&z.into()));
+        }
+        assert!((kf.state[2] - velocity.x).abs() < 0.1, "vx {}", kf.state[2]);
+        assert!((kf.state[3] - velocity.y).abs() < 0.1, "vy {}", kf.state[3]);
+        assert!(Vec2::new(kf.state[0], kf.state[1]).distance(&truth) < 0.3);
+        assert!((kf.covariance - kf.covariance.transpose()).max_abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ekf_is_generic_filter_special_case() {
+        let mut ekf = EKF::new(Vec2::new(1.0, 2.0));
+        let mut kf = KalmanFilter::<2, 2>::new(
+            Vector::from_array([1.0, 2.0]),
+            Matrix::identity(),
+            Matrix::identity(),
+            Matrix::identity(),
+            Matrix::identity() * 0.1,
+            Matrix::identity(),
+        );
+        let velocity = Vec2::new(0.5, -1.0);
+        for i in 0..20 {
+            ekf.predict(velocity, 0.1);
+            kf.predict_control(&(Matrix::identity() * 0.1), &velocity.into());
+            let z = Vec2::new(i as f32 * 0.1, 2.0);
+            ekf.update(z);
+            kf.update(&z.into());
+        }
+        assert!(Vec2::from(kf.state).distance(&ekf.state) < 1e-5);
+        assert!((Matrix::from(ekf.covariance) - kf.covariance).max_abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_singular_update_leaves_estimate() {
+        let mut ekf = ExtendedKalmanFilter::<2, 1>::new(
+            Vector::from_array([1.0, 1.0]),
+            Matrix::zeros(),
+            Matrix::zeros(),
+            Matrix::zeros(),
+        );
+        let updated = ekf.update(
+            &Vector::from_array([5.0]),
+            |x| Vector::from_array([x[0]]),
+            |_| Matrix::new([[1.0, 0.0]]),
+        );
+        assert!(!updated);
+        assert_eq!(ekf.state.to_array(), [1.0, 1.0]);
+    }
+
+    // ==================== GENERIC EXTENDED KALMAN FILTER ====================
+
+    // Range and bearing of a point seen from the origin
+    fn range_bearing(x: &Vector<2>) -> Vector<2> {
+        Vector::from_array([libm::hypotf(x[0], x[1]), libm::atan2f(x[1], x[0])])
+    }
+
+    fn range_bearing_jacobian(x: &Vector<2>) -> Matrix<2, 2> {
+        let r_sq = x[0] * x[0] + x[1] * x[1];
+        let r = libm::sqrtf(r_sq);
+        Matrix::new([[x[0] / r, x[1] / r], [-x[1] / r_sq, x[0] / r_sq]])
+    }
+
+    #[test]
+    fn test_extended_filter_range_bearing_across_seam() {
+        // Target just either side of the +-PI bearing seam
+        let truth = Vec2::new(-5.0, 0.05);
+        let mut ekf = ExtendedKalmanFilter::<2, 2>::new(
+            Vector::from_array([-4.0, -0.5]),
+            Matrix::identity() * 4.0,
+            Matrix::identity() * 1e-4,
+            Matrix::diagonal([0.04, 0.01]),
+        );
+        let mut rng = Rng::new(12);
+        for _ in 0..100 {
+            ekf.predict(|x| *x, |_| Matrix::identity());
+            let mut z = range_bearing(&truth.into());
+            z[0] += 0.2 * rng.gaussian();
+            z[1] = wrap_angle(z[1] + 0.1 * rng.gaussian());
+            let updated = ekf.update_with(&z, range_bearing, range_bearing_jacobian, |z, h| {
+                Vector::from_array([z[0] - h[0], wrap_angle(z[1] - h[1])])
+            });
+            assert!(updated);
+        }
+        let estimate = Vec2::from(ekf.state);
+        assert!(estimate.distance(&truth) < 0.2, "{:?}", estimate);
+    }
+
+    #[test]
+    fn test_extended_filter_matches_unicycle() {
+        let dt = 0.1;
+        let mut unicycle = UnicycleEkf::new(Pose::new(0.0, 0.0, 0.5), 1.0, 0.2);
+        let mut ekf = ExtendedKalmanFilter::<UNICYCLE_STATES, 2>::new(
+            Vector::from_array(unicycle.state),
+            Matrix::new(unicycle.covariance),
+            Matrix::diagonal(unicycle.noise.process) * dt,
+            Matrix::identity() * unicycle.noise.position,
+        );
+        let h = Matrix::new([[1.0, 0.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0, 0.0]]);
+        let mut truth = [0.5, -0.5, 0.3, 1.5, 0.4];
+        for _ in 0..50 {
+            truth = UnicycleEkf::motion(&truth, dt);
+            unicycle.predict(dt);
+            ekf.predict(
+                |x| UnicycleEkf::motion(&x.to_array(), dt).into(),
+                |x| Matrix::new(UnicycleEkf::motion_jacobian(&x.to_array(), dt)),
+            );
+            let z = Vec2::new(truth[0], truth[1]);
+            unicycle.update_position(z);
+            ekf.update(&z.into(), |x| h * *x, |_| h);
+        }
+        for i in 0..UNICYCLE_STATES {
+            assert!((ekf.state[i] - unicycle.state[i]).abs() < 1e-3);
+        }
+    }
 }
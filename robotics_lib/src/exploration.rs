@@ -0,0 +1,281 @@
+use crate::dstar::{octile_distance, GridMap, FREE, LETHAL, NEIGHBORS_8, UNKNOWN};
+use crate::grid_search::{GridSearch, Path};
+use alloc::vec::Vec;
+
+type Cell = (usize, usize);
+
+/// What is known about a cell of a partially explored map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellState {
+    Unknown,
+    Free,
+    Occupied,
+}
+
+/// State of a cell stored as `FREE..LETHAL` (free, possibly costed),
+/// `LETHAL` (occupied) or `UNKNOWN`. Out of bounds reads as occupied.
+pub fn cell_state(map: &GridMap, x: usize, y: usize) -> CellState {
+    match map.cost(x, y) {
+        UNKNOWN => CellState::Unknown,
+        c if c >= LETHAL => CellState::Occupied,
+        _ => CellState::Free,
+    }
+}
+
+/// A map with every cell unknown.
+pub fn unknown_map(width: usize, height: usize) -> GridMap {
+    let mut map = GridMap::new(width, height);
+    map.costs.fill(UNKNOWN);
+    map
+}
+
+/// A free cell with an unknown 4-neighbour.
+pub fn is_frontier(map: &GridMap, x: usize, y: usize) -> bool {
+    cell_state(map, x, y) == CellState::Free
+        && NEIGHBORS_8[..4].iter().any(|&(dx, dy)| {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            map.in_bounds(nx, ny) && cell_state(map, nx as usize, ny as usize) == CellState::Unknown
+        })
+}
+
+/// A connected group of frontier cells.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frontier {
+    pub cells: Vec<Cell>,
+    /// Mean position of the cells, in cell coordinates.
+    pub centroid: (f32, f32),
+    /// The member cell closest to the centroid, used as the goal.
+    pub target: Cell,
+}
+
+/// Frontier cells grouped into 8-connected clusters, dropping clusters
+/// smaller than `min_size`.
+pub fn find_frontiers(map: &GridMap, min_size: usize) -> Vec<Frontier> {
+    let mut seen = alloc::vec![false; map.width * map.height];
+    let mut frontiers = Vec::new();
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if seen[map.index(x, y)] || !is_frontier(map, x, y) {
+                continue;
+            }
+            seen[map.index(x, y)] = true;
+            let mut cells = Vec::new();
+            let mut stack = alloc::vec![(x, y)];
+            while let Some(cell) = stack.pop() {
+                cells.push(cell);
+                for &(dx, dy) in &NEIGHBORS_8 {
+                    let (nx, ny) = (cell.0 as isize + dx, cell.1 as isize + dy);
+                    if !map.in_bounds(nx, ny) {
+                        continue;
+                    }
+                    let (nx, ny) = (nx as usize, ny as usize);
+                    if !seen[map.index(nx, ny)] && is_frontier(map, nx, ny) {
+                        seen[map.index(nx, ny)] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            if cells.len() >= min_size.max(1) {
+                frontiers.push(Frontier::new(cells));
+            }
+        }
+    }
+    frontiers
+}
+
+impl Frontier {
+    fn new(cells: Vec<Cell>) -> Self {
+        let n = cells.len() as f32;
+        let cx = cells.iter().map(|c| c.0 as f32).sum::<f32>() / n;
+        let cy = cells.iter().map(|c| c.1 as f32).sum::<f32>() / n;
+        let d = |c: &Cell| {
+            let (dx, dy) = (c.0 as f32 - cx, c.1 as f32 - cy);
+            dx * dx + dy * dy
+        };
+        let target = *cells.iter().min_by(|a, b| d(a).total_cmp(&d(b))).unwrap();
+        Self {
+            cells,
+            centroid: (cx, cy),
+            target,
+        }
+    }
+}
+
+/// Unknown cells within `range` of a cell: what a sensor there could reveal
+/// at best.
+pub fn information_gain(map: &GridMap, cell: Cell, range: f32) -> usize {
+    let r = libm::ceilf(range) as isize;
+    let mut gain = 0;
+    for dy in -r..=r {
+        for dx in -r..=r {
+            let (x, y) = (cell.0 as isize + dx, cell.1 as isize + dy);
+            if (dx * dx + dy * dy) as f32 <= range * range
+                && map.in_bounds(x, y)
+                && cell_state(map, x as usize, y as usize) == CellState::Unknown
+            {
+                gain += 1;
+            }
+        }
+    }
+    gain
+}
+
+/// A candidate exploration goal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub frontier: Frontier,
+    pub gain: usize,
+    /// Travel cost from the robot over known free space.
+    pub cost: f32,
+    pub utility: f32,
+}
+
+/// Frontier-based exploration (Yamauchi) of a hidden map.
+///
+/// The robot keeps its own map, initially unknown, and fills it in from a
+/// range sensor. Each round it clusters the frontiers, scores each cluster
+/// by `gain - cost_weight * cost` where gain is the unknown area around the
+/// cluster's target and cost the travel cost over known free space, and
+/// drives the best one's A* path, sensing as it goes. Unknown cells block
+/// movement, so every path stays in explored space.
+#[derive(Clone, Debug)]
+pub struct Explorer {
+    /// What the robot knows so far.
+    pub map: GridMap,
+    pub position: Cell,
+    /// Sensor range in cells.
+    pub sensor_range: f32,
+    /// Gain traded per unit of travel cost.
+    pub cost_weight: f32,
+    /// Smaller frontier clusters are ignored. Keep at 1 for maps with
+    /// one-cell corridors, whose openings are single frontier cells.
+    pub min_frontier_size: usize,
+    /// Total travel cost so far.
+    pub distance: f32,
+}
+
+impl Explorer {
+    pub fn new(width: usize, height: usize, start: Cell, sensor_range: f32) -> Self {
+        let mut map = unknown_map(width, height);
+        map.set_cost(start.0, start.1, FREE);
+        Self {
+            map,
+            position: start,
+            sensor_range,
+            cost_weight: 1.0,
+            min_frontier_size: 1,
+            distance: 0.0,
+        }
+    }
+
+    /// Reveal what the sensor sees from the current position. Rays stop at
+    /// the first occupied cell, so space behind walls stays unknown.
+    pub fn sense(&mut self, truth: &GridMap) {
+        let (ox, oy) = (self.position.0 as f32 + 0.5, self.position.1 as f32 + 0.5);
+        let range = self.sensor_range.max(1.0);
+        let rays = libm::ceilf(2.0 * core::f32::consts::TAU * range) as usize;
+        let steps = libm::ceilf(range * 2.0) as usize;
+        for i in 0..rays {
+            let angle = i as f32 / rays as f32 * core::f32::consts::TAU;
+            let (dx, dy) = (libm::cosf(angle), libm::sinf(angle));
+            for k in 0..=steps {
+                let r = k as f32 * range / steps as f32;
+                let (px, py) = (ox + dx * r, oy + dy * r);
+                if px < 0.0 || py < 0.0 {
+                    break;
+                }
+                let (x, y) = (px as usize, py as usize);
+                if x >= truth.width || y >= truth.height {
+                    break;
+                }
+                if truth.is_obstacle(x, y) {
+                    self.map.set_cost(x, y, LETHAL);
+                    break;
+                }
+                self.map.set_cost(x, y, truth.cost(x, y));
+            }
+        }
+    }
+
+    /// Frontier clusters scored from the current position, best first.
+    /// Unreachable clusters are left out.
+    pub fn candidates(&self) -> Vec<Candidate> {
+        let costs = GridSearch::default().cost_field(&self.map, self.position);
+        let mut out: Vec<Candidate> = find_frontiers(&self.map, self.min_frontier_size)
+            .into_iter()
+            .filter_map(|frontier| {
+                let cost = costs[self.map.index(frontier.target.0, frontier.target.1)];
+                if !cost.is_finite() {
+                    return None;
+                }
+                let gain = information_gain(&self.map, frontier.target, self.sensor_range);
+                let utility = gain as f32 - self.cost_weight * cost;
+                Some(Candidate {
+                    frontier,
+                    gain,
+                    cost,
+                    utility,
+                })
+            })
+            .collect();
+        out.sort_by(|a, b| b.utility.total_cmp(&a.utility));
+        out
+    }
+
+    /// Path to the best frontier, if any is reachable.
+    pub fn next_path(&self) -> Option<Path> {
+        let best = self.candidates().into_iter().next()?;
+        GridSearch::default().plan(&self.map, self.position, best.frontier.target)
+    }
+
+    /// Sense, pick a frontier and drive towards it, sensing after every
+    /// cell and stopping early once the goal is no longer a frontier.
+    /// Returns false when exploration is complete.
+    pub fn step(&mut self, truth: &GridMap) -> bool {
+        self.sense(truth);
+        let Some(path) = self.next_path() else {
+            return false;
+        };
+        let goal = *path.cells.last().unwrap();
+        for &cell in &path.cells[1..] {
+            self.distance += octile_distance(self.position, cell);
+            self.position = cell;
+            self.sense(truth);
+            if !is_frontier(&self.map, goal.0, goal.1) {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Explore until no frontier is left or `max_rounds` rounds have run.
+    /// Returns the number of rounds.
+    pub fn explore(&mut self, truth: &GridMap, max_rounds: usize) -> usize {
+        let mut rounds = 0;
+        while rounds < max_rounds && self.step(truth) {
+            rounds += 1;
+        }
+        rounds
+    }
+
+    /// Fraction of the truth's free cells that are known to be free.
+    pub fn coverage(&self, truth: &GridMap) -> f32 {
+        let mut free = 0;
+        let mut known = 0;
+        for y in 0..truth.height {
+            for x in 0..truth.width {
+                if !truth.is_obstacle(x, y) {
+                    free += 1;
+                    if cell_state(&self.map, x, y) == CellState::Free {
+                        known += 1;
+                    }
+                }
+            }
+        }
+        known as f32 / free.max(1) as f32
+    }
+}
+
+#[cfg(test)]
+#[path = "exploration_tests.rs"]
+mod tests;
@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::{GridMap, FREE, LETHAL, UNKNOWN};
+    use crate::exploration::{
+        cell_state, find_frontiers, information_gain, is_frontier, unknown_map, CellState, Explorer,
+    };
+    use crate::mapgen::{caves, rooms_and_corridors};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    // Known cells must agree with the truth
+    fn assert_consistent(explorer: &Explorer, truth: &GridMap) {
+        for y in 0..truth.height {
+            for x in 0..truth.width {
+                match cell_state(&explorer.map, x, y) {
+                    CellState::Free => assert!(!truth.is_obstacle(x, y), "({}, {})", x, y),
+                    CellState::Occupied => assert!(truth.is_obstacle(x, y), "({}, {})", x, y),
+                    CellState::Unknown => {}
+                }
+            }
+        }
+    }
+
+    // ==================== CELL STATES ====================
+
+    #[test]
+    fn test_cell_states() {
+        let mut map = unknown_map(4, 4);
+        assert_eq!(cell_state(&map, 0, 0), CellState::Unknown);
+        map.set_cost(1, 1, FREE);
+        map.set_cost(2, 1, 100);
+        map.set_cost(3, 1, LETHAL);
+        assert_eq!(cell_state(&map, 1, 1), CellState::Free);
+        assert_eq!(cell_state(&map, 2, 1), CellState::Free);
+        assert_eq!(cell_state(&map, 3, 1), CellState::Occupied);
+        assert_eq!(cell_state(&map, 9, 9), CellState::Occupied);
+        assert!(map.is_obstacle(0, 0));
+    }
+
+    // ==================== FRONTIERS ====================
+
+    #[test]
+    fn test_frontier_cells() {
+        let mut map = unknown_map(5, 5);
+        for x in 0..3 {
+            map.set_cost(x, 2, FREE);
+        }
+        map.set_cost(1, 1, FREE);
+        map.set_cost(1, 0, LETHAL);
+        assert!(is_frontier(&map, 0, 2));
+        assert!(is_frontier(&map, 2, 2));
+        // (1, 1) sees free and occupied cells plus unknown (0, 1)
+        assert!(is_frontier(&map, 1, 1));
+        assert!(!is_frontier(&map, 1, 0));
+        assert!(!is_frontier(&map, 4, 4));
+    }
+
+    #[test]
+    fn test_frontier_clustering() {
+        // Known corridor along y = 5 with two separate open ends
+        let mut map = unknown_map(20, 11);
+        for x in 0..20 {
+            map.set_cost(x, 4, LETHAL);
+            map.set_cost(x, 6, LETHAL);
+        }
+        for x in 3..17 {
+            map.set_cost(x, 5, FREE);
+        }
+        // Unknown beyond both ends, plus a hole in the wall at x = 10
+        map.set_cost(10, 6, FREE);
+        let frontiers = find_frontiers(&map, 1);
+        assert_eq!(frontiers.len(), 3);
+        let mut targets: Vec<_> = frontiers.iter().map(|f| f.target).collect();
+        targets.sort();
+        assert_eq!(targets, vec![(3, 5), (10, 6), (16, 5)]);
+
+        // A longer frontier survives a size filter
+        for x in 10..13 {
+            map.set_cost(x, 6, FREE);
+        }
+        let big = find_frontiers(&map, 3);
+        assert_eq!(big.len(), 1);
+        assert_eq!(big[0].cells.len(), 3);
+        assert_eq!(big[0].target, (11, 6));
+        assert_eq!(big[0].centroid, (11.0, 6.0));
+    }
+
+    #[test]
+    fn test_information_gain() {
+        let mut map = unknown_map(21, 21);
+        assert_eq!(information_gain(&map, (10, 10), 1.0), 5);
+        assert_eq!(information_gain(&map, (0, 0), 1.0), 3);
+        map.set_cost(10, 10, FREE);
+        assert_eq!(information_gain(&map, (10, 10), 1.0), 4);
+        let all = GridMap::new(5, 5);
+        assert_eq!(information_gain(&all, (2, 2), 3.0), 0);
+    }
+
+    // ==================== SENSING ====================
+
+    #[test]
+    fn test_sense_stops_at_walls() {
+        let mut truth = GridMap::new(20, 20);
+        for y in 0..20 {
+            truth.set_obstacle(8, y, true);
+        }
+        let mut explorer = Explorer::new(20, 20, (5, 10), 6.0);
+        explorer.sense(&truth);
+        assert_eq!(cell_state(&explorer.map, 7, 10), CellState::Free);
+        assert_eq!(cell_state(&explorer.map, 8, 10), CellState::Occupied);
+        assert_eq!(cell_state(&explorer.map, 9, 10), CellState::Unknown);
+        assert_eq!(cell_state(&explorer.map, 5, 4), CellState::Free);
+        assert_eq!(cell_state(&explorer.map, 5, 2), CellState::Unknown);
+        assert_consistent(&explorer, &truth);
+    }
+
+    #[test]
+    fn test_candidates_trade_gain_for_cost() {
+        // Known corridor from x = 2 to x = 30 along y = 5; unknown everywhere
+        // else, with a wide opening near the robot and a far one
+        let mut explorer = Explorer::new(40, 11, (4, 5), 3.0);
+        explorer.map = unknown_map(40, 11);
+        for x in 2..=30 {
+            explorer.map.set_cost(x, 5, FREE);
+            explorer.map.set_cost(x, 4, LETHAL);
+            explorer.map.set_cost(x, 6, LETHAL);
+        }
+        explorer.map.set_cost(2, 4, UNKNOWN);
+        explorer.map.set_cost(30, 4, UNKNOWN);
+
+        let best = &explorer.candidates()[0];
+        assert_eq!(best.frontier.target, (2, 5));
+        assert!(best.cost < 3.0);
+
+        // Ignoring travel, the far end is just as good; cost must decide
+        explorer.cost_weight = 0.0;
+        let candidates = explorer.candidates();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].utility, candidates[0].gain as f32);
+    }
+
+    // ==================== EXPLORATION ====================
+
+    #[test]
+    fn test_explores_rooms_to_coverage() {
+        let generated = rooms_and_corridors(60, 40, 8, 9, 3);
+        let truth = generated.map;
+        let mut explorer = Explorer::new(60, 40, generated.start, 6.0);
+        let rounds = explorer.explore(&truth, 500);
+        assert!(rounds < 500, "did not finish");
+        let coverage = explorer.coverage(&truth);
+        assert!(coverage > 0.99, "coverage {}", coverage);
+        assert!(!truth.is_obstacle(explorer.position.0, explorer.position.1));
+        assert!(explorer.distance > 0.0);
+        assert_consistent(&explorer, &truth);
+    }
+
+    #[test]
+    fn test_explores_cave_past_threshold() {
+        let generated = caves(50, 50, 0.42, 4, 8);
+        let truth = generated.map;
+        let mut explorer = Explorer::new(50, 50, generated.start, 5.0);
+        let mut rounds = 0;
+        let mut last = 0.0;
+        while explorer.coverage(&truth) < 0.8 {
+            assert!(explorer.step(&truth), "ran out of frontiers at {}", last);
+            let coverage = explorer.coverage(&truth);
+            assert!(coverage >= last);
+            last = coverage;
+            rounds += 1;
+            assert!(rounds < 300);
+        }
+        assert_consistent(&explorer, &truth);
+    }
+}
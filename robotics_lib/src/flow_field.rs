@@ -0,0 +1,294 @@
+use crate::dstar::{GridMap, FREE, LETHAL, NEIGHBORS_8};
+use crate::grid_search::MinScored;
+use crate::map_io::MapInfo;
+use crate::math::Vec2;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+const NO_PARENT: usize = usize::MAX;
+
+/// Shared navigation field for steering many agents to one goal.
+///
+/// The integration field holds every cell's cost-to-go to the goal (a
+/// Dijkstra map, 8-connected with the `GridMap` move costs); the direction
+/// field points each cell at its cheapest neighbour. Agents look up a
+/// smoothed direction with [`FlowField::steering`] instead of planning
+/// individually. Obstacle changes made through [`FlowField::set_cost`]
+/// repair only the part of the field whose distances changed.
+#[derive(Clone, Debug)]
+pub struct FlowField {
+    /// Placement of the grid in the world that `steering` works in.
+    pub info: MapInfo,
+    /// Cells settled by the most recent build or update.
+    pub updated_cells: usize,
+    map: GridMap,
+    goal: (usize, usize),
+    integration: Vec<f32>,
+    // Next cell towards the goal, or NO_PARENT
+    parent: Vec<usize>,
+}
+
+impl FlowField {
+    pub fn new(map: GridMap, goal: (usize, usize), info: MapInfo) -> Self {
+        let n = map.width * map.height;
+        let mut field = Self {
+            info,
+            updated_cells: 0,
+            map,
+            goal,
+            integration: alloc::vec![f32::INFINITY; n],
+            parent: alloc::vec![NO_PARENT; n],
+        };
+        field.rebuild();
+        field
+    }
+
+    pub fn map(&self) -> &GridMap {
+        &self.map
+    }
+
+    pub fn goal(&self) -> (usize, usize) {
+        self.goal
+    }
+
+    /// Move the goal; the whole field is recomputed.
+    pub fn set_goal(&mut self, goal: (usize, usize)) {
+        self.goal = goal;
+        self.rebuild();
+    }
+
+    /// Cost-to-go of every cell, `f32::INFINITY` where the goal is
+    /// unreachable. Index with `GridMap::index`.
+    pub fn integration(&self) -> &[f32] {
+        &self.integration
+    }
+
+    pub fn cost_to_go(&self, x: usize, y: usize) -> f32 {
+        if x < self.map.width && y < self.map.height {
+            self.integration[self.map.index(x, y)]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    /// Unit direction (in grid axes) from a cell to its next cell towards
+    /// the goal; zero at the goal and where the goal is unreachable.
+    pub fn direction(&self, x: usize, y: usize) -> Vec2 {
+        if x >= self.map.width || y >= self.map.height {
+            return Vec2::zero();
+        }
+        let parent = self.parent[self.map.index(x, y)];
+        if parent == NO_PARENT {
+            return Vec2::zero();
+        }
+        let (px, py) = self.coords(parent);
+        Vec2::new(px as f32 - x as f32, py as f32 - y as f32).normalize()
+    }
+
+    /// Steering direction at a world position: the direction field
+    /// bilinearly interpolated between the four nearest cell centres, in
+    /// world axes. Blocked and unreachable cells are left out of the blend,
+    /// so the result has length at most one and fades out at the goal.
+    pub fn steering(&self, p: Vec2) -> Vec2 {
+        let g = self.info.world_to_grid(p);
+        let fx = libm::floorf(g.x - 0.5);
+        let fy = libm::floorf(g.y - 0.5);
+        let (tx, ty) = (g.x - 0.5 - fx, g.y - 0.5 - fy);
+
+        let mut sum = Vec2::zero();
+        let mut weight = 0.0;
+        for (dx, dy, w) in [
+            (0, 0, (1.0 - tx) * (1.0 - ty)),
+            (1, 0, tx * (1.0 - ty)),
+            (0, 1, (1.0 - tx) * ty),
+            (1, 1, tx * ty),
+        ] {
+            let (cx, cy) = (fx as isize + dx, fy as isize + dy);
+            if !self.map.in_bounds(cx, cy) {
+                continue;
+            }
+            let (cx, cy) = (cx as usize, cy as usize);
+            if !self.cost_to_go(cx, cy).is_finite() {
+                continue;
+            }
+            sum = sum + self.direction(cx, cy) * w;
+            weight += w;
+        }
+        if weight <= 0.0 {
+            return Vec2::zero();
+        }
+        let local = sum / weight;
+        // Rotate from grid axes into the world frame
+        let (s, c) = (
+            libm::sinf(self.info.origin.theta),
+            libm::cosf(self.info.origin.theta),
+        );
+        Vec2::new(c * local.x - s * local.y, s * local.x + c * local.y)
+    }
+
+    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) {
+        self.set_cost(x, y, if is_obs { LETHAL } else { FREE });
+    }
+
+    /// Change a cell's cost and repair the field.
+    ///
+    /// Cells whose route to the goal used an edge touching the cell lose
+    /// their distance along with everything routed through them; those and
+    /// the cell's neighbours are then re-seeded from their intact
+    /// neighbours and Dijkstra runs outwards until distances stop changing.
+    pub fn set_cost(&mut self, x: usize, y: usize, cost: u8) {
+        if x >= self.map.width || y >= self.map.height || self.map.cost(x, y) == cost {
+            return;
+        }
+        if (x, y) == self.goal {
+            self.map.set_cost(x, y, cost);
+            self.rebuild();
+            return;
+        }
+        let around: Vec<usize> = self
+            .block(x, y)
+            .filter(|&c| self.parent[c] != NO_PARENT)
+            .collect();
+        let before: Vec<f32> = around
+            .iter()
+            .map(|&c| {
+                self.map
+                    .move_cost(self.coords(c), self.coords(self.parent[c]))
+            })
+            .collect();
+        self.map.set_cost(x, y, cost);
+
+        // Distances routed over a changed edge may now be too low
+        let mut invalid = Vec::new();
+        for (&c, &old) in around.iter().zip(&before) {
+            let new = self
+                .map
+                .move_cost(self.coords(c), self.coords(self.parent[c]));
+            if new != old {
+                self.invalidate_subtree(c, &mut invalid);
+            }
+        }
+
+        let mut open = BinaryHeap::new();
+        let seeds: Vec<usize> = self.block(x, y).chain(invalid).collect();
+        let goal = self.map.index(self.goal.0, self.goal.1);
+        for c in seeds {
+            if c == goal {
+                continue;
+            }
+            let (best, parent) = self.best_neighbor(c);
+            if best < self.integration[c] {
+                self.integration[c] = best;
+                self.parent[c] = parent;
+                open.push(MinScored::new(best, c));
+            }
+        }
+        self.updated_cells = 0;
+        self.propagate(open);
+    }
+
+    fn rebuild(&mut self) {
+        self.integration.fill(f32::INFINITY);
+        self.parent.fill(NO_PARENT);
+        self.updated_cells = 0;
+        let mut open = BinaryHeap::new();
+        if let Some(goal) = self.goal_index() {
+            self.integration[goal] = 0.0;
+            open.push(MinScored::new(0.0, goal));
+        }
+        self.propagate(open);
+    }
+
+    fn goal_index(&self) -> Option<usize> {
+        let (x, y) = self.goal;
+        if x < self.map.width && y < self.map.height && !self.map.is_obstacle(x, y) {
+            Some(self.map.index(x, y))
+        } else {
+            None
+        }
+    }
+
+    fn coords(&self, cell: usize) -> (usize, usize) {
+        (cell % self.map.width, cell / self.map.width)
+    }
+
+    // The cell and its in-bounds 8-neighbours
+    fn block(&self, x: usize, y: usize) -> impl Iterator<Item = usize> + '_ {
+        core::iter::once((0, 0))
+            .chain(NEIGHBORS_8.iter().copied())
+            .filter_map(move |(dx, dy)| {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                self.map
+                    .in_bounds(nx, ny)
+                    .then(|| self.map.index(nx as usize, ny as usize))
+            })
+    }
+
+    // Clear the distances of `root` and every cell routed through it
+    fn invalidate_subtree(&mut self, root: usize, invalid: &mut Vec<usize>) {
+        if self.parent[root] == NO_PARENT {
+            return;
+        }
+        let mut stack = alloc::vec![root];
+        self.parent[root] = NO_PARENT;
+        self.integration[root] = f32::INFINITY;
+        while let Some(c) = stack.pop() {
+            invalid.push(c);
+            let (x, y) = self.coords(c);
+            for &(dx, dy) in &NEIGHBORS_8 {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if !self.map.in_bounds(nx, ny) {
+                    continue;
+                }
+                let n = self.map.index(nx as usize, ny as usize);
+                if self.parent[n] == c {
+                    self.parent[n] = NO_PARENT;
+                    self.integration[n] = f32::INFINITY;
+                    stack.push(n);
+                }
+            }
+        }
+    }
+
+    // Cheapest (cost-to-go, next cell) through a neighbour
+    fn best_neighbor(&self, cell: usize) -> (f32, usize) {
+        let (x, y) = self.coords(cell);
+        let mut best = (f32::INFINITY, NO_PARENT);
+        for (next, cost) in self.map.neighbors(x, y) {
+            let n = self.map.index(next.0, next.1);
+            let total = self.integration[n] + cost;
+            if total < best.0 {
+                best = (total, n);
+            }
+        }
+        best
+    }
+
+    fn propagate(&mut self, mut open: BinaryHeap<MinScored<usize>>) {
+        while let Some(MinScored {
+            score,
+            item: current,
+            ..
+        }) = open.pop()
+        {
+            if score > self.integration[current] {
+                continue;
+            }
+            self.updated_cells += 1;
+            let (x, y) = self.coords(current);
+            // Move costs are symmetric, so forward edges serve as reverse edges
+            for (next, cost) in self.map.neighbors(x, y) {
+                let n = self.map.index(next.0, next.1);
+                if score + cost < self.integration[n] {
+                    self.integration[n] = score + cost;
+                    self.parent[n] = current;
+                    open.push(MinScored::new(score + cost, n));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "flow_field_tests.rs"]
+mod tests;
@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::flow_field::FlowField;
+    use crate::grid_search::GridSearch;
+    use crate::map_io::MapInfo;
+    use crate::math::{Pose, Vec2};
+    use crate::rng::Rng;
+    use core::f32::consts::FRAC_PI_2;
+
+    fn wall_map() -> GridMap {
+        // Wall at x = 10 with a gap at the top
+        let mut map = GridMap::new(20, 20);
+        for y in 0..16 {
+            map.set_obstacle(10, y, true);
+        }
+        map
+    }
+
+    fn assert_matches_fresh(field: &FlowField) {
+        let fresh = FlowField::new(field.map().clone(), field.goal(), field.info);
+        for (i, (&a, &b)) in field
+            .integration()
+            .iter()
+            .zip(fresh.integration())
+            .enumerate()
+        {
+            assert!(
+                a == b || (a - b).abs() < 1e-3,
+                "cell {} incremental {} fresh {}",
+                i,
+                a,
+                b
+            );
+        }
+    }
+
+    // ==================== INTEGRATION FIELD ====================
+
+    #[test]
+    fn test_integration_matches_dijkstra() {
+        let map = wall_map();
+        let field = FlowField::new(map.clone(), (15, 2), MapInfo::default());
+        let expected = GridSearch::default().cost_field(&map, (15, 2));
+        assert_eq!(field.integration(), &expected[..]);
+        assert_eq!(field.cost_to_go(15, 2), 0.0);
+        assert!(field.cost_to_go(10, 5).is_infinite());
+        assert!(field.cost_to_go(50, 5).is_infinite());
+    }
+
+    #[test]
+    fn test_blocked_goal_is_unreachable() {
+        let mut map = GridMap::new(5, 5);
+        map.set_obstacle(2, 2, true);
+        let field = FlowField::new(map, (2, 2), MapInfo::default());
+        assert!(field.integration().iter().all(|c| c.is_infinite()));
+        assert_eq!(field.direction(0, 0), Vec2::zero());
+    }
+
+    // ==================== DIRECTION FIELD ====================
+
+    #[test]
+    fn test_directions_lead_to_goal() {
+        let field = FlowField::new(wall_map(), (15, 2), MapInfo::default());
+        let (mut x, mut y) = (2usize, 2usize);
+        for _ in 0..400 {
+            if (x, y) == (15, 2) {
+                break;
+            }
+            let d = field.direction(x, y);
+            let (nx, ny) = (
+                (x as f32 + libm::roundf(d.x)) as usize,
+                (y as f32 + libm::roundf(d.y)) as usize,
+            );
+            assert!(field.cost_to_go(nx, ny) < field.cost_to_go(x, y));
+            (x, y) = (nx, ny);
+        }
+        assert_eq!((x, y), (15, 2));
+        assert_eq!(field.direction(15, 2), Vec2::zero());
+    }
+
+    // ==================== STEERING ====================
+
+    #[test]
+    fn test_steering_at_cell_centre_is_direction() {
+        let field = FlowField::new(wall_map(), (15, 2), MapInfo::default());
+        let s = field.steering(Vec2::new(3.5, 7.5));
+        let d = field.direction(3, 7);
+        assert!((s.x - d.x).abs() < 1e-5 && (s.y - d.y).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_steering_blends_between_cells() {
+        // Goal straight up from one cell, diagonal from its neighbour
+        let field = FlowField::new(GridMap::new(3, 10), (1, 9), MapInfo::default());
+        let a = field.direction(1, 1);
+        let b = field.direction(2, 1);
+        assert_ne!(a, b);
+        let s = field.steering(Vec2::new(2.0, 1.5));
+        let mid = (a + b) * 0.5;
+        assert!((s.x - mid.x).abs() < 1e-5 && (s.y - mid.y).abs() < 1e-5);
+        assert!(s.mag() <= 1.0);
+    }
+
+    #[test]
+    fn test_steering_skips_blocked_cells() {
+        let field = FlowField::new(wall_map(), (15, 2), MapInfo::default());
+        // Next to the wall the blocked cells must not drag the vector to zero
+        let s = field.steering(Vec2::new(10.0, 5.5));
+        assert!(s.mag() > 0.9, "{:?}", s);
+        assert_eq!(field.steering(Vec2::new(-5.0, -5.0)), Vec2::zero());
+    }
+
+    #[test]
+    fn test_steering_in_world_frame() {
+        // 20 px cells, grid rotated a quarter turn and shifted
+        let info = MapInfo::new(20.0, Pose::new(100.0, 50.0, FRAC_PI_2));
+        let field = FlowField::new(GridMap::new(10, 3), (9, 1), info);
+        // Grid +x is world +y
+        let p = info.cell_to_world((2, 1));
+        let s = field.steering(p);
+        assert!(s.x.abs() < 1e-5 && (s.y - 1.0).abs() < 1e-5, "{:?}", s);
+    }
+
+    // ==================== INCREMENTAL UPDATES ====================
+
+    #[test]
+    fn test_set_obstacle_blocks_gap() {
+        let mut field = FlowField::new(wall_map(), (15, 2), MapInfo::default());
+        for y in 16..20 {
+            field.set_obstacle(10, y, true);
+        }
+        assert!(field.cost_to_go(2, 2).is_infinite());
+        assert_eq!(field.direction(2, 2), Vec2::zero());
+        assert!(field.cost_to_go(12, 2).is_finite());
+        assert_matches_fresh(&field);
+
+        field.set_obstacle(10, 8, false);
+        assert!(field.cost_to_go(2, 2).is_finite());
+        assert_matches_fresh(&field);
+    }
+
+    #[test]
+    fn test_random_updates_match_rebuild() {
+        let mut rng = Rng::new(3);
+        let mut field = FlowField::new(wall_map(), (15, 2), MapInfo::default());
+        for _ in 0..200 {
+            let (x, y) = (rng.index(20), rng.index(20));
+            let cost = match rng.index(3) {
+                0 => 0,
+                1 => 120,
+                _ => 254,
+            };
+            field.set_cost(x, y, cost);
+            assert_matches_fresh(&field);
+        }
+    }
+
+    #[test]
+    fn test_update_is_local() {
+        let mut field = FlowField::new(GridMap::new(60, 60), (5, 5), MapInfo::default());
+        let full = field.updated_cells;
+        assert_eq!(full, 3600);
+        // A cell far from the goal only disturbs what lies behind it
+        field.set_obstacle(55, 55, true);
+        assert!(field.updated_cells < full / 50, "{}", field.updated_cells);
+        assert_matches_fresh(&field);
+    }
+
+    #[test]
+    fn test_moving_goal_rebuilds() {
+        let mut field = FlowField::new(wall_map(), (15, 2), MapInfo::default());
+        field.set_goal((2, 2));
+        assert_eq!(field.cost_to_go(2, 2), 0.0);
+        assert_matches_fresh(&field);
+        field.set_obstacle(2, 2, true);
+        assert!(field.integration().iter().all(|c| c.is_infinite()));
+        field.set_obstacle(2, 2, false);
+        assert_matches_fresh(&field);
+        assert!(field.cost_to_go(19, 19).is_finite());
+    }
+}
@@ -0,0 +1,275 @@
+use crate::dstar::{octile_distance, GridMap, NEIGHBORS_8};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Which neighbours a cell can move to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    pub(crate) fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &NEIGHBORS_8[..4],
+            Connectivity::Eight => &NEIGHBORS_8,
+        }
+    }
+}
+
+/// Distance estimate to the goal. `Zero` turns A* into Dijkstra.
+///
+/// Manhattan is only admissible with 4-connectivity; with 8-connectivity it
+/// overestimates diagonal moves and trades optimality for speed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heuristic {
+    Zero,
+    Manhattan,
+    Octile,
+    Euclidean,
+}
+
+impl Heuristic {
+    pub fn estimate(&self, a: (usize, usize), b: (usize, usize)) -> f32 {
+        let dx = (a.0 as f32 - b.0 as f32).abs();
+        let dy = (a.1 as f32 - b.1 as f32).abs();
+        match self {
+            Heuristic::Zero => 0.0,
+            Heuristic::Manhattan => dx + dy,
+            Heuristic::Octile => octile_distance(a, b),
+            Heuristic::Euclidean => libm::sqrtf(dx * dx + dy * dy),
+        }
+    }
+}
+
+/// A planned cell path and its total traversal cost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    pub cells: Vec<(usize, usize)>,
+    pub cost: f32,
+    /// Nodes expanded while searching, for comparing planners.
+    pub expansions: usize,
+}
+
+// Min-heap entry keyed on `score`, with `tie` as a secondary key
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MinScored<T> {
+    pub score: f32,
+    pub tie: f32,
+    pub item: T,
+}
+
+impl<T> MinScored<T> {
+    pub(crate) fn new(score: f32, item: T) -> Self {
+        Self {
+            score,
+            tie: 0.0,
+            item,
+        }
+    }
+}
+
+impl<T> PartialEq for MinScored<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for MinScored<T> {}
+
+impl<T> PartialOrd for MinScored<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for MinScored<T> {
+    // Reversed so BinaryHeap pops the smallest score first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then_with(|| other.tie.total_cmp(&self.tie))
+    }
+}
+
+/// One-shot A* / Dijkstra search over a `GridMap`.
+#[derive(Clone, Copy, Debug)]
+pub struct GridSearch {
+    pub connectivity: Connectivity,
+    pub heuristic: Heuristic,
+    /// Among equal f-values expand the node closest to the goal first.
+    /// Never changes the path cost, but cuts expansions on open maps.
+    pub tie_breaking: bool,
+}
+
+impl Default for GridSearch {
+    fn default() -> Self {
+        Self::new(Connectivity::Eight, Heuristic::Octile)
+    }
+}
+
+impl GridSearch {
+    pub fn new(connectivity: Connectivity, heuristic: Heuristic) -> Self {
+        Self {
+            connectivity,
+            heuristic,
+            tie_breaking: true,
+        }
+    }
+
+    pub fn dijkstra(connectivity: Connectivity) -> Self {
+        Self {
+            connectivity,
+            heuristic: Heuristic::Zero,
+            tie_breaking: false,
+        }
+    }
+
+    /// Traversable neighbours of a cell with their move cost.
+    pub fn neighbors<'a>(
+        &self,
+        map: &'a GridMap,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = ((usize, usize), f32)> + 'a {
+        self.connectivity
+            .offsets()
+            .iter()
+            .filter_map(move |&(dx, dy)| {
+                let nx = x as isize + dx;
+                let ny = y as isize + dy;
+                if !map.in_bounds(nx, ny) {
+                    return None;
+                }
+                let next = (nx as usize, ny as usize);
+                let cost = map.move_cost((x, y), next);
+                if cost.is_finite() {
+                    Some((next, cost))
+                } else {
+                    None
+                }
+            })
+    }
+
+    pub fn plan(&self, map: &GridMap, start: (usize, usize), goal: (usize, usize)) -> Option<Path> {
+        if map.is_obstacle(start.0, start.1) || map.is_obstacle(goal.0, goal.1) {
+            return None;
+        }
+
+        let n = map.width * map.height;
+        let mut g = alloc::vec![f32::INFINITY; n];
+        let mut parent = alloc::vec![usize::MAX; n];
+        let mut closed = alloc::vec![false; n];
+        let mut open = BinaryHeap::new();
+        let mut expansions = 0;
+
+        let start_idx = map.index(start.0, start.1);
+        let goal_idx = map.index(goal.0, goal.1);
+        g[start_idx] = 0.0;
+        open.push(self.entry(0.0, start, goal, start_idx));
+
+        while let Some(MinScored { item: current, .. }) = open.pop() {
+            if closed[current] {
+                continue;
+            }
+            closed[current] = true;
+            if current == goal_idx {
+                return Some(Path {
+                    cells: reconstruct(map, &parent, current),
+                    cost: g[current],
+                    expansions,
+                });
+            }
+            expansions += 1;
+
+            let pos = (current % map.width, current / map.width);
+            for (next, cost) in self.neighbors(map, pos.0, pos.1) {
+                let idx = map.index(next.0, next.1);
+                let tentative = g[current] + cost;
+                if !closed[idx] && tentative < g[idx] {
+                    g[idx] = tentative;
+                    parent[idx] = current;
+                    open.push(self.entry(tentative, next, goal, idx));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Cost-to-go from every cell to `goal` (Dijkstra over the whole map).
+    /// Unreachable cells are `f32::INFINITY`; index with `GridMap::index`.
+    pub fn cost_field(&self, map: &GridMap, goal: (usize, usize)) -> Vec<f32> {
+        let mut dist = alloc::vec![f32::INFINITY; map.width * map.height];
+        if map.is_obstacle(goal.0, goal.1) {
+            return dist;
+        }
+
+        let mut open = BinaryHeap::new();
+        let goal_idx = map.index(goal.0, goal.1);
+        dist[goal_idx] = 0.0;
+        open.push(MinScored::new(0.0, goal_idx));
+
+        while let Some(MinScored {
+            score,
+            item: current,
+            ..
+        }) = open.pop()
+        {
+            if score > dist[current] {
+                continue;
+            }
+            let pos = (current % map.width, current / map.width);
+            // Move costs are symmetric, so forward edges serve as reverse edges
+            for (next, cost) in self.neighbors(map, pos.0, pos.1) {
+                let idx = map.index(next.0, next.1);
+                if score + cost < dist[idx] {
+                    dist[idx] = score + cost;
+                    open.push(MinScored::new(dist[idx], idx));
+                }
+            }
+        }
+
+        dist
+    }
+
+    fn entry(
+        &self,
+        g: f32,
+        pos: (usize, usize),
+        goal: (usize, usize),
+        idx: usize,
+    ) -> MinScored<usize> {
+        let h = self.heuristic.estimate(pos, goal);
+        MinScored {
+            score: g + h,
+            // Prefer the larger g (smaller h) among equal f
+            tie: if self.tie_breaking { h } else { 0.0 },
+            item: idx,
+        }
+    }
+}
+
+pub(crate) fn reconstruct(
+    map: &GridMap,
+    parent: &[usize],
+    mut current: usize,
+) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    loop {
+        cells.push((current % map.width, current / map.width));
+        if parent[current] == usize::MAX {
+            break;
+        }
+        current = parent[current];
+    }
+    cells.reverse();
+    cells
+}
+
+#[cfg(test)]
+#[path = "grid_search_tests.rs"]
+mod tests;
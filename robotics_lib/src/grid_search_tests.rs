@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::{DStarLite, GridMap};
+    use crate::grid_search::{Connectivity, GridSearch, Heuristic};
+
+    fn walled_map() -> GridMap {
+        let mut map = GridMap::new(20, 20);
+        for y in 0..15 {
+            map.set_obstacle(6, y, true);
+        }
+        for y in 5..20 {
+            map.set_obstacle(13, y, true);
+        }
+        map
+    }
+
+    // ==================== HEURISTICS ====================
+
+    #[test]
+    fn test_heuristic_values() {
+        let a = (0, 0);
+        let b = (3, 4);
+        assert_eq!(Heuristic::Zero.estimate(a, b), 0.0);
+        assert_eq!(Heuristic::Manhattan.estimate(a, b), 7.0);
+        assert!((Heuristic::Euclidean.estimate(a, b) - 5.0).abs() < 1e-6);
+        let octile = 4.0 + 3.0 * (core::f32::consts::SQRT_2 - 1.0);
+        assert!((Heuristic::Octile.estimate(a, b) - octile).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_heuristic_symmetric() {
+        for h in [
+            Heuristic::Manhattan,
+            Heuristic::Octile,
+            Heuristic::Euclidean,
+        ] {
+            assert_eq!(h.estimate((2, 7), (9, 1)), h.estimate((9, 1), (2, 7)));
+        }
+    }
+
+    // ==================== OPEN GRID ====================
+
+    #[test]
+    fn test_astar_open_grid_eight() {
+        let map = GridMap::new(10, 10);
+        let path = GridSearch::default().plan(&map, (0, 0), (9, 9)).unwrap();
+        assert_eq!(path.cells.len(), 10);
+        assert!((path.cost - 9.0 * core::f32::consts::SQRT_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_astar_open_grid_four() {
+        let map = GridMap::new(10, 10);
+        let search = GridSearch::new(Connectivity::Four, Heuristic::Manhattan);
+        let path = search.plan(&map, (0, 0), (9, 9)).unwrap();
+        assert_eq!(path.cells.len(), 19);
+        assert_eq!(path.cost, 18.0);
+        for w in path.cells.windows(2) {
+            let step = w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1);
+            assert_eq!(step, 1);
+        }
+    }
+
+    #[test]
+    fn test_start_equals_goal() {
+        let map = GridMap::new(5, 5);
+        let path = GridSearch::default().plan(&map, (2, 2), (2, 2)).unwrap();
+        assert_eq!(path.cells, [(2, 2)]);
+        assert_eq!(path.cost, 0.0);
+    }
+
+    // ==================== OBSTACLES ====================
+
+    #[test]
+    fn test_blocked_endpoints() {
+        let mut map = GridMap::new(5, 5);
+        map.set_obstacle(4, 4, true);
+        assert!(GridSearch::default().plan(&map, (0, 0), (4, 4)).is_none());
+        assert!(GridSearch::default().plan(&map, (4, 4), (0, 0)).is_none());
+        assert!(GridSearch::default().plan(&map, (0, 0), (9, 9)).is_none());
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let mut map = GridMap::new(10, 10);
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        assert!(GridSearch::default().plan(&map, (0, 0), (9, 9)).is_none());
+    }
+
+    #[test]
+    fn test_path_avoids_obstacles() {
+        let map = walled_map();
+        let path = GridSearch::default().plan(&map, (0, 0), (19, 19)).unwrap();
+        assert_eq!(path.cells[0], (0, 0));
+        assert_eq!(*path.cells.last().unwrap(), (19, 19));
+        for w in path.cells.windows(2) {
+            assert!(map.move_cost(w[0], w[1]).is_finite());
+        }
+    }
+
+    #[test]
+    fn test_path_cost_matches_cells() {
+        let map = walled_map();
+        let path = GridSearch::default().plan(&map, (0, 0), (19, 19)).unwrap();
+        let summed: f32 = path
+            .cells
+            .windows(2)
+            .map(|w| map.move_cost(w[0], w[1]))
+            .sum();
+        assert!((summed - path.cost).abs() < 1e-4);
+    }
+
+    // ==================== OPTIMALITY ====================
+
+    #[test]
+    fn test_admissible_heuristics_agree_with_dijkstra() {
+        let map = walled_map();
+        let reference = GridSearch::dijkstra(Connectivity::Eight)
+            .plan(&map, (0, 0), (19, 19))
+            .unwrap();
+        for h in [Heuristic::Octile, Heuristic::Euclidean] {
+            for tie_breaking in [false, true] {
+                let mut search = GridSearch::new(Connectivity::Eight, h);
+                search.tie_breaking = tie_breaking;
+                let path = search.plan(&map, (0, 0), (19, 19)).unwrap();
+                assert!((path.cost - reference.cost).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_dstar_lite() {
+        let mut ds = DStarLite::new(20, 20);
+        ds.map = walled_map();
+        ds.compute_shortest_path();
+        let dstar_cost: f32 = ds
+            .path
+            .windows(2)
+            .map(|w| ds.map.move_cost(w[0], w[1]))
+            .sum();
+
+        let path = GridSearch::default()
+            .plan(&ds.map, ds.start, ds.goal)
+            .unwrap();
+        assert!((path.cost - dstar_cost).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_heuristic_reduces_expansions() {
+        let map = GridMap::new(40, 40);
+        let dijkstra = GridSearch::dijkstra(Connectivity::Eight)
+            .plan(&map, (0, 20), (39, 20))
+            .unwrap();
+        let astar = GridSearch::default().plan(&map, (0, 20), (39, 20)).unwrap();
+        assert!((dijkstra.cost - astar.cost).abs() < 1e-4);
+        assert!(astar.expansions * 4 < dijkstra.expansions);
+    }
+
+    #[test]
+    fn test_tie_breaking_reduces_expansions() {
+        let map = GridMap::new(30, 30);
+        let mut plain = GridSearch::new(Connectivity::Four, Heuristic::Manhattan);
+        plain.tie_breaking = false;
+        let tied = GridSearch::new(Connectivity::Four, Heuristic::Manhattan);
+        let a = plain.plan(&map, (0, 0), (29, 29)).unwrap();
+        let b = tied.plan(&map, (0, 0), (29, 29)).unwrap();
+        assert_eq!(a.cost, b.cost);
+        assert!(b.expansions < a.expansions);
+    }
+
+    // ==================== COST FIELD ====================
+
+    #[test]
+    fn test_cost_field_matches_plans() {
+        let map = walled_map();
+        let goal = (19, 19);
+        let search = GridSearch::dijkstra(Connectivity::Eight);
+        let field = search.cost_field(&map, goal);
+        for start in [(0, 0), (10, 3), (16, 0)] {
+            let path = search.plan(&map, start, goal).unwrap();
+            assert!((field[map.index(start.0, start.1)] - path.cost).abs() < 1e-4);
+        }
+        assert_eq!(field[map.index(goal.0, goal.1)], 0.0);
+        assert!(field[map.index(6, 0)].is_infinite());
+    }
+
+    #[test]
+    fn test_cost_field_blocked_goal() {
+        let mut map = GridMap::new(5, 5);
+        map.set_obstacle(2, 2, true);
+        let field = GridSearch::default().cost_field(&map, (2, 2));
+        assert!(field.iter().all(|d| d.is_infinite()));
+    }
+}
@@ -0,0 +1,509 @@
+use crate::dstar::{octile_distance, GridMap, FREE, LETHAL};
+use crate::grid_search::{MinScored, Path};
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use alloc::vec::Vec;
+
+type Cell = (usize, usize);
+
+// Cell bounds [x0, x1) x [y0, y1)
+type Rect = (usize, usize, usize, usize);
+
+// Dijkstra result over a rectangle of cells, indexed by local cell
+struct LocalSearch {
+    dist: Vec<f32>,
+    parent: Vec<usize>,
+    expansions: usize,
+}
+
+/// Hierarchical path-finding A* (Botea, Müller & Schaeffer) over a `GridMap`.
+///
+/// The map is cut into square clusters. Wherever two neighbouring clusters
+/// share a run of free cells, one or two transitions cross the border; those
+/// cells form an abstract graph whose intra-cluster edges carry precomputed
+/// costs. A query links start and goal into their clusters, searches the
+/// small abstract graph and refines each hop with a search confined to one
+/// cluster. When start and goal lie in neighbouring clusters, a search over
+/// those clusters alone is tried as well and the cheaper path kept. Paths
+/// are near-optimal: moves that cut diagonally through a cluster corner are
+/// never used at the abstract level.
+///
+/// Change cells through [`HpaStar::set_cost`] so that only the clusters
+/// around the cell are rebuilt.
+#[derive(Clone, Debug)]
+pub struct HpaStar {
+    pub cluster_size: usize,
+    /// Clusters whose abstract edges were rebuilt by the last map change.
+    pub repaired_clusters: usize,
+    map: GridMap,
+    clusters_x: usize,
+    clusters_y: usize,
+    // Transition cell pairs (this cluster, next cluster) across the border to
+    // the cluster at x + 1 and at y + 1
+    x_transitions: Vec<Vec<(usize, usize)>>,
+    y_transitions: Vec<Vec<(usize, usize)>>,
+    // Per cluster: transition cell -> (other transition cell, cost)
+    intra: Vec<BTreeMap<usize, Vec<(usize, f32)>>>,
+}
+
+impl HpaStar {
+    pub fn new(map: GridMap, cluster_size: usize) -> Self {
+        let cluster_size = cluster_size.max(2);
+        let clusters_x = map.width.div_ceil(cluster_size);
+        let clusters_y = map.height.div_ceil(cluster_size);
+        let n = clusters_x * clusters_y;
+        let mut hpa = Self {
+            cluster_size,
+            repaired_clusters: n,
+            map,
+            clusters_x,
+            clusters_y,
+            x_transitions: alloc::vec![Vec::new(); n],
+            y_transitions: alloc::vec![Vec::new(); n],
+            intra: alloc::vec![BTreeMap::new(); n],
+        };
+        for c in 0..n {
+            hpa.build_border(c, true);
+            hpa.build_border(c, false);
+        }
+        for c in 0..n {
+            hpa.build_intra(c);
+        }
+        hpa
+    }
+
+    pub fn map(&self) -> &GridMap {
+        &self.map
+    }
+
+    /// Number of clusters along x and y.
+    pub fn clusters(&self) -> (usize, usize) {
+        (self.clusters_x, self.clusters_y)
+    }
+
+    /// All border crossings as pairs of adjacent cells.
+    pub fn entrances(&self) -> Vec<(Cell, Cell)> {
+        self.x_transitions
+            .iter()
+            .chain(&self.y_transitions)
+            .flatten()
+            .map(|&(a, b)| (self.coords(a), self.coords(b)))
+            .collect()
+    }
+
+    /// Number of intra-cluster edges in the abstract graph, each direction
+    /// counted once.
+    pub fn abstract_edges(&self) -> usize {
+        self.intra
+            .iter()
+            .flat_map(|m| m.values())
+            .map(|v| v.len())
+            .sum::<usize>()
+            / 2
+    }
+
+    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) {
+        self.set_cost(x, y, if is_obs { LETHAL } else { FREE });
+    }
+
+    /// Change a cell's cost and repair the abstract graph. The cell's own
+    /// cluster is rebuilt, plus the cluster across any border the cell lies
+    /// on, since the entrances along that border may have changed.
+    pub fn set_cost(&mut self, x: usize, y: usize, cost: u8) {
+        self.repaired_clusters = 0;
+        if x >= self.map.width || y >= self.map.height || self.map.cost(x, y) == cost {
+            return;
+        }
+        self.map.set_cost(x, y, cost);
+
+        let cs = self.cluster_size;
+        let (cx, cy) = (x / cs, y / cs);
+        let mut dirty = BTreeSet::new();
+        dirty.insert(self.cluster_id(cx, cy));
+        // (cluster owning the border, along x)
+        let mut borders = Vec::new();
+        if x.is_multiple_of(cs) && cx > 0 {
+            borders.push((self.cluster_id(cx - 1, cy), true));
+        }
+        if x % cs == cs - 1 && cx + 1 < self.clusters_x {
+            borders.push((self.cluster_id(cx, cy), true));
+        }
+        if y.is_multiple_of(cs) && cy > 0 {
+            borders.push((self.cluster_id(cx, cy - 1), false));
+        }
+        if y % cs == cs - 1 && cy + 1 < self.clusters_y {
+            borders.push((self.cluster_id(cx, cy), false));
+        }
+        for (c, along_x) in borders {
+            self.build_border(c, along_x);
+            dirty.insert(c);
+            dirty.insert(if along_x { c + 1 } else { c + self.clusters_x });
+        }
+        for &c in &dirty {
+            self.build_intra(c);
+        }
+        self.repaired_clusters = dirty.len();
+    }
+
+    /// Plan from `start` to `goal`. `Path::expansions` counts abstract and
+    /// refinement expansions together.
+    pub fn plan(&self, start: Cell, goal: Cell) -> Option<Path> {
+        let in_map = |(x, y): Cell| x < self.map.width && y < self.map.height;
+        if !in_map(start) || !in_map(goal) {
+            return None;
+        }
+        if self.map.is_obstacle(start.0, start.1) || self.map.is_obstacle(goal.0, goal.1) {
+            return None;
+        }
+        let s = self.map.index(start.0, start.1);
+        let g = self.map.index(goal.0, goal.1);
+        if s == g {
+            return Some(Path {
+                cells: alloc::vec![start],
+                cost: 0.0,
+                expansions: 0,
+            });
+        }
+
+        // Link start and goal into the abstract graph
+        let start_cluster = self.cluster_of(s);
+        let goal_cluster = self.cluster_of(g);
+        let (start_rect, goal_rect) = (self.bounds(start_cluster), self.bounds(goal_cluster));
+        let from_start = self.cluster_search(start_cluster, s, None);
+        let to_goal = self.cluster_search(goal_cluster, g, None);
+        let mut expansions = from_start.expansions + to_goal.expansions;
+        let mut start_edges: Vec<(usize, f32)> = self
+            .cluster_nodes(start_cluster)
+            .into_iter()
+            .map(|n| (n, from_start.dist[self.local(start_rect, n)]))
+            .filter(|e| e.1.is_finite())
+            .collect();
+        if start_cluster == goal_cluster {
+            let direct = from_start.dist[self.local(start_rect, g)];
+            if direct.is_finite() {
+                start_edges.push((g, direct));
+            }
+        }
+        let goal_edges: BTreeMap<usize, f32> = self
+            .cluster_nodes(goal_cluster)
+            .into_iter()
+            .map(|n| (n, to_goal.dist[self.local(goal_rect, n)]))
+            .filter(|e| e.1.is_finite())
+            .collect();
+
+        // Abstract A*
+        let h = |n: usize| octile_distance(self.coords(n), goal);
+        let mut best: BTreeMap<usize, (f32, usize)> = BTreeMap::new();
+        let mut closed = BTreeSet::new();
+        let mut open = BinaryHeap::new();
+        best.insert(s, (0.0, usize::MAX));
+        open.push(MinScored::new(h(s), s));
+        let mut found = false;
+        while let Some(MinScored { item: n, .. }) = open.pop() {
+            if !closed.insert(n) {
+                continue;
+            }
+            if n == g {
+                found = true;
+                break;
+            }
+            expansions += 1;
+            let g_n = best[&n].0;
+            let mut edges = self.abstract_neighbors(n);
+            if n == s {
+                edges.extend_from_slice(&start_edges);
+            }
+            if let Some(&cost) = goal_edges.get(&n) {
+                edges.push((g, cost));
+            }
+            for (next, cost) in edges {
+                let total = g_n + cost;
+                if !closed.contains(&next) && best.get(&next).is_none_or(|b| total < b.0) {
+                    best.insert(next, (total, n));
+                    open.push(MinScored::new(total + h(next), next));
+                }
+            }
+        }
+
+        // Transitions sit at fixed places on each border, so between
+        // neighbouring clusters the abstract route can be far from the
+        // direct one; search those clusters alone as well
+        let (sx, sy) = (start.0 / self.cluster_size, start.1 / self.cluster_size);
+        let (gx, gy) = (goal.0 / self.cluster_size, goal.1 / self.cluster_size);
+        let mut nearby = None;
+        if start_cluster != goal_cluster && sx.abs_diff(gx) <= 1 && sy.abs_diff(gy) <= 1 {
+            let (a, b) = (start_rect, goal_rect);
+            let rect = (a.0.min(b.0), a.1.max(b.1), a.2.min(b.2), a.3.max(b.3));
+            let search = self.rect_search(rect, s, Some(g));
+            expansions += search.expansions;
+            let cost = search.dist[self.local(rect, g)];
+            if cost.is_finite() && (!found || cost < best[&g].0) {
+                nearby = Some(self.trace(rect, &search, g));
+            }
+        }
+        if let Some(segment) = nearby {
+            let mut cells = alloc::vec![start];
+            cells.extend(segment.into_iter().map(|c| self.coords(c)));
+            return Some(self.finish(cells, expansions));
+        }
+        if !found {
+            return None;
+        }
+
+        let mut hops = alloc::vec![g];
+        while let Some(&(_, parent)) = best.get(hops.last().unwrap()) {
+            if parent == usize::MAX {
+                break;
+            }
+            hops.push(parent);
+        }
+        hops.reverse();
+
+        // Refine each hop into cells
+        let mut cells = alloc::vec![start];
+        for w in hops.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            let (ca, cb) = (self.cluster_of(a), self.cluster_of(b));
+            if ca != cb {
+                cells.push(self.coords(b));
+                continue;
+            }
+            let local = self.cluster_search(ca, a, Some(b));
+            expansions += local.expansions;
+            let segment = self.trace(self.bounds(ca), &local, b);
+            cells.extend(segment.into_iter().map(|c| self.coords(c)));
+        }
+        Some(self.finish(cells, expansions))
+    }
+
+    fn finish(&self, cells: Vec<Cell>, expansions: usize) -> Path {
+        let cost = cells
+            .windows(2)
+            .map(|w| self.map.move_cost(w[0], w[1]))
+            .sum();
+        Path {
+            cells,
+            cost,
+            expansions,
+        }
+    }
+
+    // Cells of a local search's path to `to`, without the cell it started at
+    fn trace(&self, rect: Rect, search: &LocalSearch, to: usize) -> Vec<usize> {
+        let mut segment = Vec::new();
+        let mut i = self.local(rect, to);
+        while search.parent[i] != usize::MAX {
+            segment.push(self.global(rect, i));
+            i = search.parent[i];
+        }
+        segment.reverse();
+        segment
+    }
+
+    fn coords(&self, cell: usize) -> Cell {
+        (cell % self.map.width, cell / self.map.width)
+    }
+
+    fn cluster_id(&self, cx: usize, cy: usize) -> usize {
+        cy * self.clusters_x + cx
+    }
+
+    fn cluster_of(&self, cell: usize) -> usize {
+        let (x, y) = self.coords(cell);
+        self.cluster_id(x / self.cluster_size, y / self.cluster_size)
+    }
+
+    fn bounds(&self, cluster: usize) -> Rect {
+        let cs = self.cluster_size;
+        let (cx, cy) = (cluster % self.clusters_x, cluster / self.clusters_x);
+        let (x0, y0) = (cx * cs, cy * cs);
+        (
+            x0,
+            (x0 + cs).min(self.map.width),
+            y0,
+            (y0 + cs).min(self.map.height),
+        )
+    }
+
+    fn local(&self, (x0, x1, y0, _): Rect, cell: usize) -> usize {
+        let (x, y) = self.coords(cell);
+        (y - y0) * (x1 - x0) + (x - x0)
+    }
+
+    fn global(&self, (x0, x1, y0, _): Rect, local: usize) -> usize {
+        let w = x1 - x0;
+        self.map.index(x0 + local % w, y0 + local / w)
+    }
+
+    // Scan the border between `cluster` and its neighbour at x + 1 (or
+    // y + 1) for runs of cells that are free on both sides and equally
+    // costly to cross. Short runs get one transition in the middle, long ones
+    // one at each end.
+    fn build_border(&mut self, cluster: usize, along_x: bool) {
+        let (cx, cy) = (cluster % self.clusters_x, cluster / self.clusters_x);
+        let (x0, x1, y0, y1) = self.bounds(cluster);
+        let mut pairs = Vec::new();
+        let has_neighbor = if along_x {
+            cx + 1 < self.clusters_x
+        } else {
+            cy + 1 < self.clusters_y
+        };
+        if has_neighbor {
+            // (inside cell, outside cell) at position i along the border
+            let cell_pair = |i: usize| {
+                if along_x {
+                    ((x1 - 1, y0 + i), (x1, y0 + i))
+                } else {
+                    ((x0 + i, y1 - 1), (x0 + i, y1))
+                }
+            };
+            let len = if along_x { y1 - y0 } else { x1 - x0 };
+            let crossing = |i: usize| {
+                let (a, b) = cell_pair(i);
+                self.map.move_cost(a, b)
+            };
+            let mut i = 0;
+            while i < len {
+                let cost = crossing(i);
+                if !cost.is_finite() {
+                    i += 1;
+                    continue;
+                }
+                // A change in cost starts a new run, so cheap stretches of
+                // the border get transitions of their own
+                let run_start = i;
+                while i < len && crossing(i) == cost {
+                    i += 1;
+                }
+                let run_end = i - 1;
+                let picks = if run_end - run_start + 1 < 6 {
+                    alloc::vec![(run_start + run_end) / 2]
+                } else {
+                    alloc::vec![run_start, run_end]
+                };
+                for p in picks {
+                    let (a, b) = cell_pair(p);
+                    pairs.push((self.map.index(a.0, a.1), self.map.index(b.0, b.1)));
+                }
+            }
+        }
+        if along_x {
+            self.x_transitions[cluster] = pairs;
+        } else {
+            self.y_transitions[cluster] = pairs;
+        }
+    }
+
+    // Transition cells inside a cluster, from all four borders
+    fn cluster_nodes(&self, cluster: usize) -> Vec<usize> {
+        let (cx, cy) = (cluster % self.clusters_x, cluster / self.clusters_x);
+        let mut nodes = BTreeSet::new();
+        nodes.extend(self.x_transitions[cluster].iter().map(|p| p.0));
+        nodes.extend(self.y_transitions[cluster].iter().map(|p| p.0));
+        if cx > 0 {
+            nodes.extend(self.x_transitions[cluster - 1].iter().map(|p| p.1));
+        }
+        if cy > 0 {
+            nodes.extend(
+                self.y_transitions[cluster - self.clusters_x]
+                    .iter()
+                    .map(|p| p.1),
+            );
+        }
+        nodes.into_iter().collect()
+    }
+
+    fn build_intra(&mut self, cluster: usize) {
+        let nodes = self.cluster_nodes(cluster);
+        let mut edges = BTreeMap::new();
+        for &a in &nodes {
+            let search = self.cluster_search(cluster, a, None);
+            let list: Vec<(usize, f32)> = nodes
+                .iter()
+                .filter(|&&b| b != a)
+                .map(|&b| (b, search.dist[self.local(self.bounds(cluster), b)]))
+                .filter(|e| e.1.is_finite())
+                .collect();
+            edges.insert(a, list);
+        }
+        self.intra[cluster] = edges;
+    }
+
+    // Intra-cluster edges plus border crossings of a transition cell
+    fn abstract_neighbors(&self, cell: usize) -> Vec<(usize, f32)> {
+        let cluster = self.cluster_of(cell);
+        let mut out = self.intra[cluster].get(&cell).cloned().unwrap_or_default();
+        let (cx, cy) = (cluster % self.clusters_x, cluster / self.clusters_x);
+        let mut lists = alloc::vec![&self.x_transitions[cluster], &self.y_transitions[cluster]];
+        if cx > 0 {
+            lists.push(&self.x_transitions[cluster - 1]);
+        }
+        if cy > 0 {
+            lists.push(&self.y_transitions[cluster - self.clusters_x]);
+        }
+        for &(a, b) in lists.into_iter().flatten() {
+            let other = if a == cell {
+                b
+            } else if b == cell {
+                a
+            } else {
+                continue;
+            };
+            out.push((other, self.map.move_cost(self.coords(a), self.coords(b))));
+        }
+        out
+    }
+
+    fn cluster_search(&self, cluster: usize, from: usize, target: Option<usize>) -> LocalSearch {
+        self.rect_search(self.bounds(cluster), from, target)
+    }
+
+    // Search from `from` that never leaves `rect`: Dijkstra over the whole
+    // rectangle, or A* that stops once `target` is settled
+    fn rect_search(&self, rect: Rect, from: usize, target: Option<usize>) -> LocalSearch {
+        let (x0, x1, y0, y1) = rect;
+        let n = (x1 - x0) * (y1 - y0);
+        let mut dist = alloc::vec![f32::INFINITY; n];
+        let mut parent = alloc::vec![usize::MAX; n];
+        let mut closed = alloc::vec![false; n];
+        let mut open = BinaryHeap::new();
+        let mut expansions = 0;
+        let h =
+            |x: usize, y: usize| target.map_or(0.0, |t| octile_distance((x, y), self.coords(t)));
+        let root = self.local(rect, from);
+        dist[root] = 0.0;
+        open.push(MinScored::new(0.0, root));
+        let target = target.map(|t| self.local(rect, t));
+
+        while let Some(MinScored { item, .. }) = open.pop() {
+            if closed[item] {
+                continue;
+            }
+            closed[item] = true;
+            if Some(item) == target {
+                break;
+            }
+            expansions += 1;
+            let (x, y) = self.coords(self.global(rect, item));
+            for ((nx, ny), cost) in self.map.neighbors(x, y) {
+                if nx < x0 || nx >= x1 || ny < y0 || ny >= y1 {
+                    continue;
+                }
+                let j = (ny - y0) * (x1 - x0) + (nx - x0);
+                if dist[item] + cost < dist[j] {
+                    dist[j] = dist[item] + cost;
+                    parent[j] = item;
+                    open.push(MinScored::new(dist[j] + h(nx, ny), j));
+                }
+            }
+        }
+        LocalSearch {
+            dist,
+            parent,
+            expansions,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "hpa_tests.rs"]
+mod tests;
@@ -0,0 +1,249 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::grid_search::{GridSearch, Path};
+    use crate::hpa::HpaStar;
+    use crate::mapgen::random_rects;
+    use alloc::vec;
+
+    fn assert_valid(map: &GridMap, path: &Path, start: (usize, usize), goal: (usize, usize)) {
+        assert_eq!(path.cells[0], start);
+        assert_eq!(*path.cells.last().unwrap(), goal);
+        let mut cost = 0.0;
+        for w in path.cells.windows(2) {
+            let step = map.move_cost(w[0], w[1]);
+            assert!(step.is_finite(), "{:?} -> {:?}", w[0], w[1]);
+            cost += step;
+        }
+        assert!((cost - path.cost).abs() < 1e-3);
+    }
+
+    fn optimal(map: &GridMap, start: (usize, usize), goal: (usize, usize)) -> Option<f32> {
+        GridSearch::default().plan(map, start, goal).map(|p| p.cost)
+    }
+
+    // ==================== ABSTRACT GRAPH ====================
+
+    #[test]
+    fn test_open_map_entrances() {
+        // 2 x 2 clusters of 10: every border is one long run with a
+        // transition at each end
+        let hpa = HpaStar::new(GridMap::new(20, 20), 10);
+        assert_eq!(hpa.clusters(), (2, 2));
+        let entrances = hpa.entrances();
+        assert_eq!(entrances.len(), 8);
+        assert!(entrances.contains(&((9, 0), (10, 0))));
+        assert!(entrances.contains(&((9, 9), (10, 9))));
+        assert!(entrances.contains(&((0, 9), (0, 10))));
+        // Three transition cells per cluster (one sits on both borders),
+        // fully connected
+        assert_eq!(hpa.abstract_edges(), 4 * 3);
+    }
+
+    #[test]
+    fn test_short_gap_gets_one_entrance() {
+        let mut map = GridMap::new(20, 10);
+        for y in 0..10 {
+            if !(4..7).contains(&y) {
+                map.set_obstacle(10, y, true);
+            }
+        }
+        let hpa = HpaStar::new(map, 10);
+        assert_eq!(hpa.entrances(), vec![((9, 5), (10, 5))]);
+    }
+
+    #[test]
+    fn test_partial_clusters() {
+        let hpa = HpaStar::new(GridMap::new(25, 13), 10);
+        assert_eq!(hpa.clusters(), (3, 2));
+        let path = hpa.plan((0, 0), (24, 12)).unwrap();
+        assert_valid(hpa.map(), &path, (0, 0), (24, 12));
+    }
+
+    // ==================== PLANNING ====================
+
+    #[test]
+    fn test_open_map_is_optimal() {
+        let map = GridMap::new(40, 40);
+        let hpa = HpaStar::new(map.clone(), 10);
+        let path = hpa.plan((2, 3), (37, 31)).unwrap();
+        assert_valid(&map, &path, (2, 3), (37, 31));
+        let best = optimal(&map, (2, 3), (37, 31)).unwrap();
+        assert!(path.cost < best * 1.05, "{} vs {}", path.cost, best);
+    }
+
+    #[test]
+    fn test_cluttered_maps_near_optimal() {
+        for seed in 0..6 {
+            let generated = random_rects(60, 60, 0.25, 5, seed);
+            let map = generated.map;
+            let hpa = HpaStar::new(map.clone(), 10);
+            let (start, goal) = (generated.start, generated.goal);
+            match (hpa.plan(start, goal), optimal(&map, start, goal)) {
+                (Some(path), Some(best)) => {
+                    assert_valid(&map, &path, start, goal);
+                    assert!(path.cost >= best - 1e-3);
+                    assert!(path.cost < best * 1.2, "{} vs {}", path.cost, best);
+                }
+                (None, None) => {}
+                (a, b) => panic!("seed {}: hpa {:?} flat {:?}", seed, a.is_some(), b),
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_cluster_detour() {
+        // Start and goal share a cluster but a wall forces the path out
+        let mut map = GridMap::new(30, 30);
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        let hpa = HpaStar::new(map.clone(), 10);
+        let path = hpa.plan((2, 2), (8, 2)).unwrap();
+        assert_valid(&map, &path, (2, 2), (8, 2));
+        assert!(path.cells.iter().any(|&(_, y)| y >= 10));
+
+        let direct = hpa.plan((1, 1), (3, 4)).unwrap();
+        assert!((direct.cost - optimal(&map, (1, 1), (3, 4)).unwrap()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_cost_map_near_optimal() {
+        // Rough terrain crossed by two free roads; transitions have to sit
+        // on the roads for the abstract graph to use them
+        let mut map = GridMap::new(40, 40);
+        for y in 0..40 {
+            for x in 0..40 {
+                if y != 14 && x != 23 {
+                    map.set_cost(x, y, 60 + ((x * 7 + y * 3) % 5) as u8 * 20);
+                }
+            }
+        }
+        let hpa = HpaStar::new(map.clone(), 10);
+        // Adjacent cells across a border
+        let step = hpa.plan((9, 14), (10, 14)).unwrap();
+        assert_eq!(step.cells, vec![(9, 14), (10, 14)]);
+        assert!((step.cost - 1.0).abs() < 1e-5);
+        let pairs = [
+            ((0, 14), (39, 14)),
+            ((2, 14), (23, 38)),
+            ((5, 12), (15, 16)),
+            ((8, 3), (12, 17)),
+            ((23, 0), (31, 14)),
+            ((1, 1), (38, 38)),
+            ((18, 22), (27, 9)),
+        ];
+        for (start, goal) in pairs {
+            let path = hpa.plan(start, goal).unwrap();
+            assert_valid(&map, &path, start, goal);
+            let best = optimal(&map, start, goal).unwrap();
+            assert!(path.cost >= best - 1e-3);
+            assert!(
+                path.cost < best * 1.2,
+                "{:?} -> {:?}: {} vs {}",
+                start,
+                goal,
+                path.cost,
+                best
+            );
+        }
+    }
+
+    #[test]
+    fn test_unreachable_and_invalid() {
+        let mut map = GridMap::new(30, 30);
+        for y in 0..30 {
+            map.set_obstacle(15, y, true);
+        }
+        let hpa = HpaStar::new(map, 10);
+        assert!(hpa.plan((0, 0), (29, 29)).is_none());
+        assert!(hpa.plan((15, 3), (0, 0)).is_none());
+        assert!(hpa.plan((0, 0), (30, 0)).is_none());
+        assert_eq!(hpa.plan((4, 4), (4, 4)).unwrap().cells, vec![(4, 4)]);
+    }
+
+    #[test]
+    fn test_large_grid_expands_less_than_flat_search() {
+        let map = random_rects(300, 300, 0.2, 8, 4).map;
+        let hpa = HpaStar::new(map.clone(), 20);
+        let (start, goal) = ((1, 1), (298, 298));
+        let flat = GridSearch::default().plan(&map, start, goal).unwrap();
+        let path = hpa.plan(start, goal).unwrap();
+        assert_valid(&map, &path, start, goal);
+        assert!(
+            path.expansions * 2 < flat.expansions,
+            "hpa {} flat {}",
+            path.expansions,
+            flat.expansions
+        );
+    }
+
+    // ==================== REPAIR ====================
+
+    #[test]
+    fn test_interior_change_repairs_one_cluster() {
+        let mut hpa = HpaStar::new(GridMap::new(50, 50), 10);
+        hpa.set_obstacle(25, 25, true);
+        assert_eq!(hpa.repaired_clusters, 1);
+        hpa.set_obstacle(25, 25, true);
+        assert_eq!(hpa.repaired_clusters, 0);
+        // On a border: both sides
+        hpa.set_obstacle(29, 25, true);
+        assert_eq!(hpa.repaired_clusters, 2);
+        // On a corner: own cluster and the two across its borders
+        hpa.set_obstacle(30, 30, true);
+        assert_eq!(hpa.repaired_clusters, 3);
+    }
+
+    #[test]
+    fn test_repair_matches_rebuild() {
+        let mut hpa = HpaStar::new(GridMap::new(40, 40), 10);
+        let changes = [
+            (9, 3, true),
+            (10, 5, true),
+            (19, 19, true),
+            (20, 20, true),
+            (15, 15, true),
+            (9, 3, false),
+            (33, 29, true),
+        ];
+        for &(x, y, obs) in &changes {
+            hpa.set_obstacle(x, y, obs);
+        }
+        let fresh = HpaStar::new(hpa.map().clone(), 10);
+        let mut a = hpa.entrances();
+        let mut b = fresh.entrances();
+        a.sort();
+        b.sort();
+        assert_eq!(a, b);
+        assert_eq!(hpa.abstract_edges(), fresh.abstract_edges());
+        for (start, goal) in [((0, 0), (39, 39)), ((5, 30), (35, 2))] {
+            assert_eq!(
+                hpa.plan(start, goal).unwrap().cost,
+                fresh.plan(start, goal).unwrap().cost
+            );
+        }
+    }
+
+    #[test]
+    fn test_closing_a_door_reroutes() {
+        // Wall at x = 20 with two doors
+        let mut map = GridMap::new(40, 40);
+        for y in 0..40 {
+            if y != 5 && y != 35 {
+                map.set_obstacle(20, y, true);
+            }
+        }
+        let mut hpa = HpaStar::new(map, 10);
+        let before = hpa.plan((2, 5), (38, 5)).unwrap();
+        assert!(before.cells.contains(&(20, 5)));
+
+        hpa.set_obstacle(20, 5, true);
+        let after = hpa.plan((2, 5), (38, 5)).unwrap();
+        assert_valid(hpa.map(), &after, (2, 5), (38, 5));
+        assert!(after.cells.contains(&(20, 35)));
+
+        hpa.set_obstacle(20, 35, true);
+        assert!(hpa.plan((2, 5), (38, 5)).is_none());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::collision::CollisionChecker;
+use crate::dstar::GridMap;
+use crate::dubins::{advance, DubinsPath, Steer};
+use crate::grid_search::{GridSearch, MinScored};
+use crate::math::{wrap_angle, Pose};
+use crate::reeds_shepp::ReedsSheppPath;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HybridAStarConfig {
+    pub wheelbase: f32,
+    /// Maximum steering angle in radians.
+    pub max_steer: f32,
+    /// Arc length driven by one motion primitive, in cells.
+    pub step: f32,
+    /// Steering angles per direction, spread evenly over [-max, max].
+    pub steer_samples: usize,
+    /// Heading discretisation for duplicate detection.
+    pub heading_bins: usize,
+    /// Allow reversing; switches the analytic expansion from Dubins to
+    /// Reeds-Shepp curves.
+    pub allow_reverse: bool,
+    /// Cost multiplier for driving in reverse.
+    pub reverse_penalty: f32,
+    /// Extra cost per unit length and radian of steering.
+    pub steer_penalty: f32,
+    /// Fixed cost for every change between forward and reverse.
+    pub switch_penalty: f32,
+    /// Try an analytic expansion to the goal every this many expansions.
+    pub analytic_interval: usize,
+    pub max_expansions: usize,
+}
+
+impl Default for HybridAStarConfig {
+    fn default() -> Self {
+        Self {
+            wheelbase: 1.0,
+            max_steer: 0.6,
+            step: 1.5,
+            steer_samples: 5,
+            heading_bins: 72,
+            allow_reverse: true,
+            reverse_penalty: 2.0,
+            steer_penalty: 0.1,
+            switch_penalty: 5.0,
+            analytic_interval: 1,
+            max_expansions: 50_000,
+        }
+    }
+}
+
+impl HybridAStarConfig {
+    /// Turning radius at full steering lock.
+    pub fn min_turn_radius(&self) -> f32 {
+        self.wheelbase / libm::tanf(self.max_steer)
+    }
+}
+
+/// A drivable path: poses in order with the gear used to reach each one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HybridPath {
+    pub poses: Vec<Pose>,
+    /// `reverse[i]` is true if `poses[i]` was reached driving backwards.
+    pub reverse: Vec<bool>,
+    /// Driven length in cells.
+    pub length: f32,
+    pub expansions: usize,
+}
+
+impl HybridPath {
+    /// Number of forward/reverse switches.
+    pub fn cusps(&self) -> usize {
+        self.reverse
+            .windows(2)
+            .skip(1)
+            .filter(|w| w[0] != w[1])
+            .count()
+    }
+}
+
+struct Node {
+    pose: Pose,
+    g: f32,
+    reverse: bool,
+    parent: usize,
+}
+
+/// Hybrid A* for car-like robots on a `GridMap`.
+///
+/// States are continuous (x, y, θ) poses in cell units, binned into grid
+/// cells and heading bins so each bin is expanded once. Successors come from
+/// a kinematic bicycle model at a few steering angles, forwards and
+/// optionally backwards. The heuristic is the larger of the obstacle-free
+/// Dubins/Reeds-Shepp length and the obstacle-aware grid distance, and the
+/// search finishes with an analytic curve to the exact goal pose. The robot
+/// is a point; plan on an inflated map for a robot with a footprint.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HybridAStar {
+    pub config: HybridAStarConfig,
+}
+
+impl HybridAStar {
+    pub fn new(config: HybridAStarConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn plan(&self, map: &GridMap, start: Pose, goal: Pose) -> Option<HybridPath> {
+        if !map.is_free(start.position()) || !map.is_free(goal.position()) {
+            return None;
+        }
+        let config = &self.config;
+        let radius = config.min_turn_radius();
+        let bins = config.heading_bins.max(1);
+        let key = |p: &Pose| {
+            let bin = (wrap_angle(p.theta) + PI) / (2.0 * PI) * bins as f32;
+            let bin = (bin as usize).min(bins - 1);
+            (map.index(p.x as usize, p.y as usize)) * bins + bin
+        };
+
+        // Obstacle-aware part of the heuristic
+        let grid_cost = GridSearch::default().cost_field(map, (goal.x as usize, goal.y as usize));
+        let heuristic = |p: &Pose| {
+            let holonomic = grid_cost[map.index(p.x as usize, p.y as usize)];
+            let curve = if config.allow_reverse {
+                ReedsSheppPath::shortest(*p, goal, radius).map(|c| c.length())
+            } else {
+                DubinsPath::shortest(*p, goal, radius).map(|c| c.length())
+            };
+            holonomic.max(curve.unwrap_or(0.0))
+        };
+
+        let mut nodes = alloc::vec![Node {
+            pose: start,
+            g: 0.0,
+            reverse: false,
+            parent: usize::MAX,
+        }];
+        let mut best_g = alloc::vec![f32::INFINITY; map.width * map.height * bins];
+        let mut closed = alloc::vec![false; best_g.len()];
+        let mut open = BinaryHeap::new();
+        best_g[key(&start)] = 0.0;
+        open.push(MinScored::new(heuristic(&start), 0));
+        let mut expansions = 0;
+
+        let primitives = self.primitives();
+        while let Some(MinScored { item: id, .. }) = open.pop() {
+            let pose = nodes[id].pose;
+            let k = key(&pose);
+            if closed[k] {
+                continue;
+            }
+            closed[k] = true;
+            expansions += 1;
+            if expansions > config.max_expansions {
+                return None;
+            }
+
+            if (expansions - 1) % config.analytic_interval.max(1) == 0 {
+                if let Some(tail) = self.analytic_expansion(map, pose, goal, radius) {
+                    return Some(self.finish(&nodes, id, tail, expansions));
+                }
+            }
+
+            for &(steer, reverse) in &primitives {
+                let Some(next) = self.drive(map, pose, steer, reverse) else {
+                    continue;
+                };
+                let nk = key(&next);
+                if closed[nk] {
+                    continue;
+                }
+                let mut cost = config.step * (1.0 + config.steer_penalty * steer.abs());
+                if reverse {
+                    cost *= config.reverse_penalty;
+                }
+                if id != 0 && reverse != nodes[id].reverse {
+                    cost += config.switch_penalty;
+                }
+                let g = nodes[id].g + cost;
+                if g < best_g[nk] {
+                    best_g[nk] = g;
+                    nodes.push(Node {
+                        pose: next,
+                        g,
+                        reverse,
+                        parent: id,
+                    });
+                    open.push(MinScored::new(g + heuristic(&next), nodes.len() - 1));
+                }
+            }
+        }
+        None
+    }
+
+    // (steering angle, reverse) pairs
+    fn primitives(&self) -> Vec<(f32, bool)> {
+        let n = self.config.steer_samples.max(1);
+        let angles: Vec<f32> = if n == 1 {
+            alloc::vec![0.0]
+        } else {
+            (0..n)
+                .map(|i| {
+                    -self.config.max_steer + 2.0 * self.config.max_steer * i as f32 / (n - 1) as f32
+                })
+                .collect()
+        };
+        let mut out: Vec<(f32, bool)> = angles.iter().map(|&a| (a, false)).collect();
+        if self.config.allow_reverse {
+            out.extend(angles.iter().map(|&a| (a, true)));
+        }
+        out
+    }
+
+    // Integrate the bicycle model for one step, checking collisions on the way
+    fn drive(&self, map: &GridMap, pose: Pose, steer: f32, reverse: bool) -> Option<Pose> {
+        let config = &self.config;
+        let sign = if reverse { -1.0 } else { 1.0 };
+        let substeps = libm::ceilf(config.step / 0.25).max(1.0) as usize;
+        let ds = sign * config.step / substeps as f32;
+        let curvature = libm::tanf(steer) / config.wheelbase;
+        let mut p = pose;
+        for _ in 0..substeps {
+            let next = if curvature.abs() < 1e-6 {
+                Pose::new(
+                    p.x + ds * libm::cosf(p.theta),
+                    p.y + ds * libm::sinf(p.theta),
+                    p.theta,
+                )
+            } else {
+                let theta = p.theta + ds * curvature;
+                Pose::new(
+                    p.x + (libm::sinf(theta) - libm::sinf(p.theta)) / curvature,
+                    p.y - (libm::cosf(theta) - libm::cosf(p.theta)) / curvature,
+                    wrap_angle(theta),
+                )
+            };
+            if !map.segment_free(p.position(), next.position()) {
+                return None;
+            }
+            p = next;
+        }
+        Some(p)
+    }
+
+    // Collision-free curve from `pose` to the goal, sampled about every
+    // `step`, as (pose, reverse) pairs plus the curve length
+    fn analytic_expansion(
+        &self,
+        map: &GridMap,
+        pose: Pose,
+        goal: Pose,
+        radius: f32,
+    ) -> Option<(Vec<(Pose, bool)>, f32)> {
+        let segments: Vec<(Steer, f32)> = if self.config.allow_reverse {
+            ReedsSheppPath::shortest(pose, goal, radius)?.segments
+        } else {
+            DubinsPath::shortest(pose, goal, radius)?
+                .segments()
+                .to_vec()
+        };
+
+        let mut samples = Vec::new();
+        let mut current = pose;
+        let mut total = 0.0;
+        let mut since_sample = 0.0;
+        for (steer, length) in segments {
+            let reverse = length < 0.0;
+            let n = libm::ceilf(length.abs() / 0.25).max(1.0) as usize;
+            for i in 0..n {
+                let next = advance(current, steer, length / n as f32, radius);
+                if !map.segment_free(current.position(), next.position()) {
+                    return None;
+                }
+                current = next;
+                since_sample += length.abs() / n as f32;
+                if since_sample >= self.config.step || i + 1 == n {
+                    samples.push((current, reverse));
+                    since_sample = 0.0;
+                }
+            }
+            total += length.abs();
+        }
+        // Finish exactly on the goal to hide accumulated rounding
+        if let Some(last) = samples.last_mut() {
+            last.0 = goal;
+        }
+        Some((samples, total))
+    }
+
+    fn finish(
+        &self,
+        nodes: &[Node],
+        last: usize,
+        (tail, tail_length): (Vec<(Pose, bool)>, f32),
+        expansions: usize,
+    ) -> HybridPath {
+        let mut chain = Vec::new();
+        let mut i = last;
+        while i != usize::MAX {
+            chain.push((nodes[i].pose, nodes[i].reverse));
+            i = nodes[i].parent;
+        }
+        chain.reverse();
+        let length = (chain.len() - 1) as f32 * self.config.step + tail_length;
+        chain.extend(tail);
+        let (poses, reverse) = chain.into_iter().unzip();
+        HybridPath {
+            poses,
+            reverse,
+            length,
+            expansions,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "hybrid_astar_tests.rs"]
+mod tests;
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::CollisionChecker;
+    use crate::dstar::GridMap;
+    use crate::hybrid_astar::{HybridAStar, HybridAStarConfig, HybridPath};
+    use crate::math::{wrap_angle, Pose};
+    use core::f32::consts::PI;
+
+    fn assert_drivable(map: &GridMap, path: &HybridPath, start: Pose, goal: Pose) {
+        assert_eq!(path.poses[0], start);
+        assert_eq!(*path.poses.last().unwrap(), goal);
+        assert_eq!(path.poses.len(), path.reverse.len());
+        for w in path.poses.windows(2) {
+            assert!(map.is_free(w[1].position()));
+            // Consecutive poses are close and heading changes gradually
+            assert!(w[0].position().distance(&w[1].position()) < 2.0);
+            assert!(wrap_angle(w[1].theta - w[0].theta).abs() < 1.5);
+        }
+    }
+
+    // ==================== OPEN SPACE ====================
+
+    #[test]
+    fn test_direct_analytic_solution() {
+        let map = GridMap::new(30, 30);
+        let start = Pose::new(5.5, 5.5, 0.0);
+        let goal = Pose::new(20.5, 12.5, PI / 2.0);
+        let path = HybridAStar::default().plan(&map, start, goal).unwrap();
+        assert_drivable(&map, &path, start, goal);
+        assert_eq!(path.expansions, 1);
+        assert!(path.length >= start.position().distance(&goal.position()));
+    }
+
+    #[test]
+    fn test_forward_only_uses_dubins() {
+        let map = GridMap::new(30, 30);
+        let start = Pose::new(10.5, 10.5, 0.0);
+        let goal = Pose::new(10.5, 15.5, PI);
+        let planner = HybridAStar::new(HybridAStarConfig {
+            allow_reverse: false,
+            ..Default::default()
+        });
+        let path = planner.plan(&map, start, goal).unwrap();
+        assert_drivable(&map, &path, start, goal);
+        assert!(path.reverse.iter().all(|r| !r));
+    }
+
+    #[test]
+    fn test_turn_radius() {
+        let config = HybridAStarConfig {
+            wheelbase: 2.0,
+            max_steer: PI / 4.0,
+            ..Default::default()
+        };
+        assert!((config.min_turn_radius() - 2.0).abs() < 1e-5);
+    }
+
+    // ==================== OBSTACLES ====================
+
+    fn wall_map() -> GridMap {
+        // Wall across the middle with a gap at the top
+        let mut map = GridMap::new(40, 30);
+        for y in 0..22 {
+            map.set_obstacle(20, y, true);
+            map.set_obstacle(21, y, true);
+        }
+        map
+    }
+
+    #[test]
+    fn test_drives_around_wall() {
+        let map = wall_map();
+        let start = Pose::new(8.5, 5.5, 0.0);
+        let goal = Pose::new(32.5, 5.5, 0.0);
+        let path = HybridAStar::default().plan(&map, start, goal).unwrap();
+        assert_drivable(&map, &path, start, goal);
+        assert!(path.poses.iter().any(|p| p.y > 22.0));
+        assert!(path.expansions > 1);
+    }
+
+    #[test]
+    fn test_enclosed_goal_fails() {
+        let mut map = GridMap::new(20, 20);
+        for i in 12..18 {
+            map.set_obstacle(i, 12, true);
+            map.set_obstacle(i, 17, true);
+            map.set_obstacle(12, i, true);
+            map.set_obstacle(17, i, true);
+        }
+        let config = HybridAStarConfig {
+            max_expansions: 5_000,
+            ..Default::default()
+        };
+        let start = Pose::new(3.5, 3.5, 0.0);
+        assert!(HybridAStar::new(config)
+            .plan(&map, start, Pose::new(14.5, 14.5, 0.0))
+            .is_none());
+        assert!(HybridAStar::new(config)
+            .plan(&map, start, Pose::new(12.5, 12.5, 0.0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_reverse_into_dead_end() {
+        // Narrow bay open to the left; the goal faces out of it, so the car
+        // must back in or turn around inside a 3-cell-wide slot
+        let mut map = GridMap::new(30, 20);
+        for x in 15..30 {
+            map.set_obstacle(x, 8, true);
+            map.set_obstacle(x, 12, true);
+        }
+        for y in 8..13 {
+            map.set_obstacle(29, y, true);
+        }
+        let start = Pose::new(5.5, 10.5, 0.0);
+        let goal = Pose::new(24.5, 10.5, PI);
+        let path = HybridAStar::default().plan(&map, start, goal).unwrap();
+        assert_drivable(&map, &path, start, goal);
+        assert!(path.cusps() > 0);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::dstar::{GridMap, FREE, LETHAL, NEIGHBORS_8};
+use crate::grid_search::MinScored;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+const NO_SOURCE: usize = usize::MAX;
+
+/// Robot footprint and cost decay, in cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InflationConfig {
+    /// Cells closer than this to an obstacle are lethal for the robot centre.
+    pub robot_radius: f32,
+    /// Cells out to this distance get a decaying cost. Set equal to
+    /// `robot_radius` for a plain configuration-space grid.
+    pub inflation_radius: f32,
+    /// Exponential decay rate of the cost beyond `robot_radius` (ROS style).
+    pub cost_scaling_factor: f32,
+}
+
+impl InflationConfig {
+    /// Configuration-space obstacles only, no decaying cost band.
+    pub fn new(robot_radius: f32) -> Self {
+        Self {
+            robot_radius,
+            inflation_radius: robot_radius,
+            cost_scaling_factor: 0.0,
+        }
+    }
+
+    /// Cost of a cell at `distance` cells from the nearest obstacle.
+    pub fn cost(&self, distance: f32) -> u8 {
+        if distance <= self.robot_radius {
+            LETHAL
+        } else if distance <= self.inflation_radius {
+            let decay = libm::expf(-self.cost_scaling_factor * (distance - self.robot_radius));
+            ((LETHAL - 1) as f32 * decay) as u8
+        } else {
+            FREE
+        }
+    }
+}
+
+/// A base map plus its inflated configuration-space map.
+///
+/// Obstacle distances come from a brushfire that tracks the nearest obstacle
+/// cell of every cell. Edits through [`InflatedMap::set_obstacle`] only
+/// re-propagate around the changed cell (dynamic brushfire, Lau et al.).
+#[derive(Clone, Debug)]
+pub struct InflatedMap {
+    pub config: InflationConfig,
+    base: GridMap,
+    inflated: GridMap,
+    dist: Vec<f32>,
+    source: Vec<usize>,
+    /// Cells whose distance was recomputed by the last update.
+    pub touched: usize,
+}
+
+impl InflatedMap {
+    pub fn new(base: GridMap, config: InflationConfig) -> Self {
+        let n = base.costs.len();
+        let mut map = Self {
+            config,
+            inflated: base.clone(),
+            base,
+            dist: alloc::vec![f32::INFINITY; n],
+            source: alloc::vec![NO_SOURCE; n],
+            touched: 0,
+        };
+        map.rebuild();
+        map
+    }
+
+    /// The map the robot-radius inflation was computed from.
+    pub fn base(&self) -> &GridMap {
+        &self.base
+    }
+
+    /// Inflated map for planning with a point robot.
+    pub fn map(&self) -> &GridMap {
+        &self.inflated
+    }
+
+    /// Inflation costs alone, for a `costmap::INFLATION_LAYER`.
+    pub fn layer(&self) -> Vec<u8> {
+        self.dist.iter().map(|&d| self.config.cost(d)).collect()
+    }
+
+    /// Distance in cells to the nearest obstacle, `f32::INFINITY` if none is
+    /// within `inflation_radius` or (x, y) is outside the map.
+    pub fn distance(&self, x: usize, y: usize) -> f32 {
+        if x < self.base.width && y < self.base.height {
+            self.dist[self.base.index(x, y)]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    /// Obstacle cell nearest to cell (x, y), None if none is within
+    /// `inflation_radius`.
+    pub fn nearest_obstacle(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        if x >= self.base.width || y >= self.base.height {
+            return None;
+        }
+        let source = self.source[self.base.index(x, y)];
+        (source != NO_SOURCE).then(|| (source % self.base.width, source / self.base.width))
+    }
+
+    /// Recompute the whole brushfire from scratch.
+    pub fn rebuild(&mut self) {
+        self.dist.fill(f32::INFINITY);
+        self.source.fill(NO_SOURCE);
+        let mut open = BinaryHeap::new();
+        for i in 0..self.base.costs.len() {
+            if self.base.costs[i] >= LETHAL {
+                self.dist[i] = 0.0;
+                self.source[i] = i;
+                open.push(MinScored::new(0.0, i));
+            }
+        }
+        self.touched = 0;
+        let mut changed = Vec::new();
+        self.lower(open, &mut changed);
+        for i in 0..self.base.costs.len() {
+            self.refresh(i);
+        }
+    }
+
+    /// Change one base cell and repair the inflation around it.
+    /// Returns the inflated cells whose cost changed, ready for
+    /// `DStarLite::update_costs`.
+    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) -> Vec<(usize, usize, u8)> {
+        self.set_cost(x, y, if is_obs { LETHAL } else { FREE })
+    }
+
+    /// Change the cost of one base cell; see [`InflatedMap::set_obstacle`].
+    pub fn set_cost(&mut self, x: usize, y: usize, cost: u8) -> Vec<(usize, usize, u8)> {
+        self.touched = 0;
+        if x >= self.base.width || y >= self.base.height {
+            return Vec::new();
+        }
+        let i = self.base.index(x, y);
+        let was_obs = self.base.costs[i] >= LETHAL;
+        self.base.costs[i] = cost;
+        let is_obs = cost >= LETHAL;
+
+        let mut changed = alloc::vec![i];
+        if is_obs && !was_obs {
+            self.dist[i] = 0.0;
+            self.source[i] = i;
+            let mut open = BinaryHeap::new();
+            open.push(MinScored::new(0.0, i));
+            self.lower(open, &mut changed);
+        } else if was_obs && !is_obs {
+            let open = self.raise(i, &mut changed);
+            self.lower(open, &mut changed);
+        }
+
+        changed.sort_unstable();
+        changed.dedup();
+        let mut updates = Vec::new();
+        for i in changed {
+            let before = self.inflated.costs[i];
+            self.refresh(i);
+            if self.inflated.costs[i] != before {
+                updates.push((
+                    i % self.base.width,
+                    i / self.base.width,
+                    self.inflated.costs[i],
+                ));
+            }
+        }
+        updates
+    }
+
+    // Clear every cell that took its distance from the removed obstacle and
+    // return the frontier of still-valid cells that must re-propagate.
+    fn raise(&mut self, removed: usize, changed: &mut Vec<usize>) -> BinaryHeap<MinScored<usize>> {
+        let mut open = BinaryHeap::new();
+        let mut stack = alloc::vec![removed];
+        self.dist[removed] = f32::INFINITY;
+        self.source[removed] = NO_SOURCE;
+
+        while let Some(cell) = stack.pop() {
+            self.touched += 1;
+            changed.push(cell);
+            for next in neighbor_cells(self.base.width, self.base.height, cell) {
+                if self.source[next] == removed {
+                    self.dist[next] = f32::INFINITY;
+                    self.source[next] = NO_SOURCE;
+                    stack.push(next);
+                } else if self.source[next] != NO_SOURCE {
+                    open.push(MinScored::new(self.dist[next], next));
+                }
+            }
+        }
+        open
+    }
+
+    // Brushfire: spread each cell's nearest obstacle to its neighbours
+    fn lower(&mut self, mut open: BinaryHeap<MinScored<usize>>, changed: &mut Vec<usize>) {
+        let max_dist = self.config.inflation_radius.max(self.config.robot_radius);
+        while let Some(MinScored {
+            score, item: cell, ..
+        }) = open.pop()
+        {
+            if score > self.dist[cell] {
+                continue;
+            }
+            self.touched += 1;
+            let source = self.source[cell];
+            for next in neighbor_cells(self.base.width, self.base.height, cell) {
+                let d = self.cell_distance(next, source);
+                if d <= max_dist && d < self.dist[next] {
+                    self.dist[next] = d;
+                    self.source[next] = source;
+                    changed.push(next);
+                    open.push(MinScored::new(d, next));
+                }
+            }
+        }
+    }
+
+    fn refresh(&mut self, i: usize) {
+        self.inflated.costs[i] = self.base.costs[i].max(self.config.cost(self.dist[i]));
+    }
+
+    fn cell_distance(&self, a: usize, b: usize) -> f32 {
+        let w = self.base.width;
+        let dx = (a % w) as f32 - (b % w) as f32;
+        let dy = (a / w) as f32 - (b / w) as f32;
+        libm::sqrtf(dx * dx + dy * dy)
+    }
+}
+
+fn neighbor_cells(width: usize, height: usize, cell: usize) -> impl Iterator<Item = usize> {
+    let (x, y) = ((cell % width) as isize, (cell / width) as isize);
+    NEIGHBORS_8.iter().filter_map(move |&(dx, dy)| {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+            Some(ny as usize * width + nx as usize)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+#[path = "inflation_tests.rs"]
+mod tests;
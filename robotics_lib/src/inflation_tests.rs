@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod tests {
+    use crate::costmap::{LayeredCostmap, INFLATION_LAYER, STATIC_LAYER};
+    use crate::dstar::{DStarLite, GridMap, FREE, LETHAL};
+    use crate::inflation::{InflatedMap, InflationConfig};
+
+    fn decaying() -> InflationConfig {
+        InflationConfig {
+            robot_radius: 1.5,
+            inflation_radius: 4.0,
+            cost_scaling_factor: 1.0,
+        }
+    }
+
+    fn scattered_map(width: usize, height: usize) -> GridMap {
+        let mut map = GridMap::new(width, height);
+        for (x, y) in [
+            (3, 3),
+            (10, 4),
+            (11, 4),
+            (12, 4),
+            (6, 12),
+            (15, 15),
+            (2, 17),
+        ] {
+            map.set_obstacle(x, y, true);
+        }
+        map
+    }
+
+    fn brute_force_distance(map: &GridMap, x: usize, y: usize) -> f32 {
+        let mut best = f32::INFINITY;
+        for oy in 0..map.height {
+            for ox in 0..map.width {
+                if map.is_obstacle(ox, oy) {
+                    let dx = ox as f32 - x as f32;
+                    let dy = oy as f32 - y as f32;
+                    best = best.min(libm::sqrtf(dx * dx + dy * dy));
+                }
+            }
+        }
+        best
+    }
+
+    fn assert_matches_rebuild(inflated: &InflatedMap) {
+        let fresh = InflatedMap::new(inflated.base().clone(), inflated.config);
+        assert_eq!(inflated.map().costs, fresh.map().costs);
+    }
+
+    // ==================== COST FUNCTION ====================
+
+    #[test]
+    fn test_config_cost_bands() {
+        let config = decaying();
+        assert_eq!(config.cost(0.0), LETHAL);
+        assert_eq!(config.cost(1.5), LETHAL);
+        let near = config.cost(2.0);
+        let far = config.cost(3.5);
+        assert!(near < LETHAL && near > far && far > FREE);
+        assert_eq!(config.cost(4.5), FREE);
+    }
+
+    #[test]
+    fn test_config_without_decay() {
+        let config = InflationConfig::new(2.0);
+        assert_eq!(config.cost(2.0), LETHAL);
+        assert_eq!(config.cost(2.01), FREE);
+    }
+
+    // ==================== FULL BRUSHFIRE ====================
+
+    #[test]
+    fn test_robot_radius_grows_obstacles() {
+        let mut map = GridMap::new(11, 11);
+        map.set_obstacle(5, 5, true);
+        let inflated = InflatedMap::new(map, InflationConfig::new(1.5));
+        for y in 4..=6 {
+            for x in 4..=6 {
+                assert!(inflated.map().is_obstacle(x, y));
+            }
+        }
+        assert!(!inflated.map().is_obstacle(7, 5));
+        assert!(!inflated.base().is_obstacle(4, 4));
+    }
+
+    #[test]
+    fn test_distances_match_brute_force() {
+        let inflated = InflatedMap::new(scattered_map(20, 20), decaying());
+        let base = inflated.base();
+        for y in 0..20 {
+            for x in 0..20 {
+                let exact = brute_force_distance(base, x, y);
+                let d = inflated.distance(x, y);
+                if exact <= 4.0 {
+                    assert!(
+                        (d - exact).abs() < 1e-4,
+                        "({}, {}): {} vs {}",
+                        x,
+                        y,
+                        d,
+                        exact
+                    );
+                } else {
+                    assert!(d.is_infinite());
+                }
+            }
+        }
+        assert!(inflated.distance(20, 0).is_infinite());
+        assert!(inflated.distance(0, 20).is_infinite());
+    }
+
+    #[test]
+    fn test_base_costs_are_kept() {
+        let mut map = GridMap::new(10, 10);
+        map.set_cost(8, 8, 77);
+        let inflated = InflatedMap::new(map, decaying());
+        assert_eq!(inflated.map().cost(8, 8), 77);
+    }
+
+    #[test]
+    fn test_layer_feeds_costmap() {
+        let inflated = InflatedMap::new(scattered_map(20, 20), decaying());
+        let mut costmap = LayeredCostmap::standard(20, 20);
+        costmap.set_layer(STATIC_LAYER, &inflated.base().costs);
+        costmap.set_layer(INFLATION_LAYER, &inflated.layer());
+        assert_eq!(costmap.master().costs, inflated.map().costs);
+    }
+
+    // ==================== INCREMENTAL UPDATES ====================
+
+    #[test]
+    fn test_add_obstacle_matches_rebuild() {
+        let mut inflated = InflatedMap::new(scattered_map(20, 20), decaying());
+        let updates = inflated.set_obstacle(8, 8, true);
+        assert!(!updates.is_empty());
+        assert!(updates
+            .iter()
+            .all(|&(x, y, _)| x.abs_diff(8) <= 4 && y.abs_diff(8) <= 4));
+        assert_matches_rebuild(&inflated);
+    }
+
+    #[test]
+    fn test_remove_obstacle_matches_rebuild() {
+        let mut inflated = InflatedMap::new(scattered_map(20, 20), decaying());
+        inflated.set_obstacle(11, 4, false);
+        assert_matches_rebuild(&inflated);
+        inflated.set_obstacle(3, 3, false);
+        assert_matches_rebuild(&inflated);
+        assert!(inflated.distance(3, 3).is_infinite());
+    }
+
+    #[test]
+    fn test_sequence_of_edits_matches_rebuild() {
+        let mut inflated = InflatedMap::new(scattered_map(20, 20), decaying());
+        let edits = [
+            (5, 5, true),
+            (6, 5, true),
+            (5, 5, false),
+            (10, 4, false),
+            (12, 5, true),
+            (12, 4, false),
+            (0, 0, true),
+        ];
+        for (x, y, obs) in edits {
+            inflated.set_obstacle(x, y, obs);
+            assert_matches_rebuild(&inflated);
+        }
+    }
+
+    #[test]
+    fn test_unchanged_edit_returns_nothing() {
+        let mut inflated = InflatedMap::new(scattered_map(20, 20), decaying());
+        assert!(inflated.set_obstacle(3, 3, true).is_empty());
+        assert!(inflated.set_obstacle(50, 50, true).is_empty());
+    }
+
+    #[test]
+    fn test_incremental_touches_few_cells() {
+        let mut map = GridMap::new(200, 200);
+        for i in 0..200 {
+            map.set_obstacle(i, 100, true);
+        }
+        let mut inflated = InflatedMap::new(map, decaying());
+        let full = inflated.touched;
+
+        inflated.set_obstacle(20, 20, true);
+        assert!(inflated.touched * 10 < full);
+        inflated.set_obstacle(20, 20, false);
+        assert!(inflated.touched * 10 < full);
+    }
+
+    #[test]
+    fn test_updates_drive_dstar_lite() {
+        let mut inflated = InflatedMap::new(GridMap::new(30, 30), InflationConfig::new(1.0));
+        let mut ds = DStarLite::new(30, 30);
+        ds.map = inflated.map().clone();
+        ds.compute_shortest_path();
+
+        let updates = inflated.set_obstacle(15, 15, true);
+        ds.update_costs(&updates);
+        assert_eq!(ds.map.costs, inflated.map().costs);
+        assert!(!ds.path.is_empty());
+        for &(x, y) in &ds.path {
+            assert!(inflated.distance(x, y) > 1.0);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use crate::dstar::{octile_distance, GridMap, NEIGHBORS_8};
+use crate::grid_search::{MinScored, Path};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+type Dir = (isize, isize);
+
+fn walkable(map: &GridMap, x: isize, y: isize) -> bool {
+    map.in_bounds(x, y) && !map.is_obstacle(x as usize, y as usize)
+}
+
+// Directions worth exploring after arriving with `dir` (`None` at the start).
+// Diagonal moves never cut corners, so the pruning follows the
+// "diagonal only when both orthogonal cells are free" JPS variant.
+fn pruned_directions(dir: Option<Dir>) -> Vec<Dir> {
+    match dir {
+        None => NEIGHBORS_8.to_vec(),
+        Some((dx, 0)) => alloc::vec![(dx, 0), (dx, 1), (dx, -1), (0, 1), (0, -1)],
+        Some((0, dy)) => alloc::vec![(0, dy), (1, dy), (-1, dy), (1, 0), (-1, 0)],
+        Some((dx, dy)) => alloc::vec![(0, dy), (dx, 0), (dx, dy)],
+    }
+}
+
+fn can_step(map: &GridMap, x: isize, y: isize, (dx, dy): Dir) -> bool {
+    walkable(map, x + dx, y + dy) && walkable(map, x + dx, y) && walkable(map, x, y + dy)
+}
+
+// A cell reached by a straight move has a forced neighbour beside it
+fn has_forced_neighbor(map: &GridMap, x: isize, y: isize, (dx, dy): Dir) -> bool {
+    if dx != 0 {
+        (walkable(map, x, y - 1) && !walkable(map, x - dx, y - 1))
+            || (walkable(map, x, y + 1) && !walkable(map, x - dx, y + 1))
+    } else {
+        (walkable(map, x - 1, y) && !walkable(map, x - 1, y - dy))
+            || (walkable(map, x + 1, y) && !walkable(map, x + 1, y - dy))
+    }
+}
+
+fn direction(from: usize, to: usize, width: usize) -> Dir {
+    let dx = (to % width) as isize - (from % width) as isize;
+    let dy = (to / width) as isize - (from / width) as isize;
+    (dx.signum(), dy.signum())
+}
+
+// A* over jump points; `successors` maps (cell, arrival direction) to the
+// next jump points.
+fn search(
+    map: &GridMap,
+    start: (usize, usize),
+    goal: (usize, usize),
+    mut successors: impl FnMut(usize, Option<Dir>, &mut Vec<usize>),
+) -> Option<Path> {
+    if map.is_obstacle(start.0, start.1) || map.is_obstacle(goal.0, goal.1) {
+        return None;
+    }
+
+    let n = map.width * map.height;
+    let coords = |i: usize| (i % map.width, i / map.width);
+    let mut g = alloc::vec![f32::INFINITY; n];
+    let mut parent = alloc::vec![usize::MAX; n];
+    let mut closed = alloc::vec![false; n];
+    let mut open = BinaryHeap::new();
+    let mut next = Vec::new();
+    let mut expansions = 0;
+
+    let start_idx = map.index(start.0, start.1);
+    let goal_idx = map.index(goal.0, goal.1);
+    g[start_idx] = 0.0;
+    open.push(MinScored::new(octile_distance(start, goal), start_idx));
+
+    while let Some(MinScored { item: current, .. }) = open.pop() {
+        if closed[current] {
+            continue;
+        }
+        closed[current] = true;
+        if current == goal_idx {
+            return Some(Path {
+                cells: interpolate(map, &parent, current),
+                cost: g[current],
+                expansions,
+            });
+        }
+        expansions += 1;
+
+        let dir = if parent[current] == usize::MAX {
+            None
+        } else {
+            Some(direction(parent[current], current, map.width))
+        };
+        next.clear();
+        successors(current, dir, &mut next);
+        for &jp in &next {
+            if closed[jp] {
+                continue;
+            }
+            let cost = g[current] + octile_distance(coords(current), coords(jp));
+            if cost < g[jp] {
+                g[jp] = cost;
+                parent[jp] = current;
+                let h = octile_distance(coords(jp), goal);
+                open.push(MinScored {
+                    score: cost + h,
+                    tie: h,
+                    item: jp,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+// Expand the jump-point chain into every cell along the way
+fn interpolate(map: &GridMap, parent: &[usize], goal: usize) -> Vec<(usize, usize)> {
+    let mut jump_points = alloc::vec![goal];
+    while let Some(&last) = jump_points.last() {
+        if parent[last] == usize::MAX {
+            break;
+        }
+        jump_points.push(parent[last]);
+    }
+    jump_points.reverse();
+
+    let mut cells = alloc::vec![(jump_points[0] % map.width, jump_points[0] / map.width)];
+    for w in jump_points.windows(2) {
+        let (dx, dy) = direction(w[0], w[1], map.width);
+        let (mut x, mut y) = (w[0] % map.width, w[0] / map.width);
+        let target = (w[1] % map.width, w[1] / map.width);
+        while (x, y) != target {
+            x = (x as isize + dx) as usize;
+            y = (y as isize + dy) as usize;
+            cells.push((x, y));
+        }
+    }
+    cells
+}
+
+/// Jump Point Search: optimal 8-connected paths on uniform-cost grids.
+///
+/// Produces the same path cost as `GridSearch` with the octile heuristic on
+/// maps whose free cells all cost the same, while expanding only jump points.
+/// Cells below `LETHAL` are treated as free; use `GridSearch` for weighted maps.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JumpPointSearch;
+
+impl JumpPointSearch {
+    pub fn plan(&self, map: &GridMap, start: (usize, usize), goal: (usize, usize)) -> Option<Path> {
+        let goal_pos = (goal.0 as isize, goal.1 as isize);
+        search(map, start, goal, |cell, dir, out| {
+            let (x, y) = ((cell % map.width) as isize, (cell / map.width) as isize);
+            for d in pruned_directions(dir) {
+                if !can_step(map, x, y, d) {
+                    continue;
+                }
+                if let Some((jx, jy)) = jump(map, x + d.0, y + d.1, d, goal_pos) {
+                    out.push(map.index(jx as usize, jy as usize));
+                }
+            }
+        })
+    }
+}
+
+fn jump(
+    map: &GridMap,
+    mut x: isize,
+    mut y: isize,
+    d: Dir,
+    goal: (isize, isize),
+) -> Option<(isize, isize)> {
+    loop {
+        if !walkable(map, x, y) {
+            return None;
+        }
+        if (x, y) == goal {
+            return Some((x, y));
+        }
+        if d.0 != 0 && d.1 != 0 {
+            if jump(map, x + d.0, y, (d.0, 0), goal).is_some()
+                || jump(map, x, y + d.1, (0, d.1), goal).is_some()
+            {
+                return Some((x, y));
+            }
+            if !can_step(map, x, y, d) {
+                return None;
+            }
+        } else if has_forced_neighbor(map, x, y, d) {
+            return Some((x, y));
+        }
+        x += d.0;
+        y += d.1;
+    }
+}
+
+/// JPS+: Jump Point Search with a precomputed jump-distance table.
+///
+/// For every cell and direction the table stores the number of steps to the
+/// next jump point (positive) or to the last free cell before a wall (zero or
+/// negative). The map is owned so that [`JpsPlus::set_obstacle`] can
+/// invalidate the table; it is rebuilt on the next plan.
+pub struct JpsPlus {
+    map: GridMap,
+    table: Option<Vec<[i32; 8]>>,
+}
+
+impl JpsPlus {
+    pub fn new(map: GridMap) -> Self {
+        Self { map, table: None }
+    }
+
+    pub fn map(&self) -> &GridMap {
+        &self.map
+    }
+
+    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) {
+        if x < self.map.width && y < self.map.height && self.map.is_obstacle(x, y) != is_obs {
+            self.map.set_obstacle(x, y, is_obs);
+            self.table = None;
+        }
+    }
+
+    /// True if the jump table matches the current map.
+    pub fn is_preprocessed(&self) -> bool {
+        self.table.is_some()
+    }
+
+    /// Build the jump table now instead of on the next plan.
+    pub fn preprocess(&mut self) {
+        if self.table.is_none() {
+            self.table = Some(build_table(&self.map));
+        }
+    }
+
+    pub fn plan(&mut self, start: (usize, usize), goal: (usize, usize)) -> Option<Path> {
+        self.preprocess();
+        let map = &self.map;
+        let table = self.table.as_ref()?;
+        let (gx, gy) = (goal.0 as isize, goal.1 as isize);
+
+        search(map, start, goal, |cell, dir, out| {
+            let (x, y) = ((cell % map.width) as isize, (cell / map.width) as isize);
+            for d in pruned_directions(dir) {
+                let k = direction_index(d);
+                let dist = table[cell][k] as isize;
+                let reach = dist.abs();
+                let (tx, ty) = (gx - x, gy - y);
+
+                if d.0 == 0 || d.1 == 0 {
+                    // Stop on the goal if it lies on this ray within reach
+                    let on_ray = if d.0 != 0 {
+                        ty == 0 && tx.signum() == d.0
+                    } else {
+                        tx == 0 && ty.signum() == d.1
+                    };
+                    let steps = tx.abs().max(ty.abs());
+                    if on_ray && steps <= reach {
+                        out.push(map.index(gx as usize, gy as usize));
+                        continue;
+                    }
+                } else if tx.signum() == d.0 && ty.signum() == d.1 {
+                    // Target jump point where the goal's row or column is crossed
+                    let steps = tx.abs().min(ty.abs());
+                    if steps <= reach {
+                        let (jx, jy) = (x + d.0 * steps, y + d.1 * steps);
+                        out.push(map.index(jx as usize, jy as usize));
+                        continue;
+                    }
+                }
+                if dist > 0 {
+                    let (jx, jy) = (x + d.0 * dist, y + d.1 * dist);
+                    out.push(map.index(jx as usize, jy as usize));
+                }
+            }
+        })
+    }
+}
+
+fn direction_index(d: Dir) -> usize {
+    NEIGHBORS_8.iter().position(|&n| n == d).unwrap_or(0)
+}
+
+fn build_table(map: &GridMap) -> Vec<[i32; 8]> {
+    let mut table = alloc::vec![[0i32; 8]; map.width * map.height];
+    // Straight directions first: diagonal entries depend on them
+    for (k, &d) in NEIGHBORS_8.iter().enumerate() {
+        let xs: Vec<usize> = if d.0 > 0 {
+            (0..map.width).rev().collect()
+        } else {
+            (0..map.width).collect()
+        };
+        let ys: Vec<usize> = if d.1 > 0 {
+            (0..map.height).rev().collect()
+        } else {
+            (0..map.height).collect()
+        };
+        // Cells are visited so that `cell + d` is always filled in first
+        for &y in &ys {
+            for &x in &xs {
+                let (xi, yi) = (x as isize, y as isize);
+                if !walkable(map, xi, yi) || !can_step(map, xi, yi, d) {
+                    continue;
+                }
+                let (nx, ny) = (xi + d.0, yi + d.1);
+                let next = map.index(nx as usize, ny as usize);
+                let is_jump_point = if d.0 != 0 && d.1 != 0 {
+                    table[next][direction_index((d.0, 0))] > 0
+                        || table[next][direction_index((0, d.1))] > 0
+                } else {
+                    has_forced_neighbor(map, nx, ny, d)
+                };
+                let onward = table[next][k];
+                table[map.index(x, y)][k] = if is_jump_point {
+                    1
+                } else if onward > 0 {
+                    onward + 1
+                } else {
+                    onward - 1
+                };
+            }
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+#[path = "jps_tests.rs"]
+mod tests;
@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::grid_search::{GridSearch, Path};
+    use crate::jps::{JpsPlus, JumpPointSearch};
+
+    // Deterministic scattered obstacles
+    fn random_map(width: usize, height: usize, density: u32, seed: u32) -> GridMap {
+        let mut map = GridMap::new(width, height);
+        let mut state = seed;
+        for y in 0..height {
+            for x in 0..width {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                if (state >> 16) % 100 < density {
+                    map.set_obstacle(x, y, true);
+                }
+            }
+        }
+        map.set_obstacle(0, 0, false);
+        map.set_obstacle(width - 1, height - 1, false);
+        map
+    }
+
+    fn assert_valid(map: &GridMap, path: &Path, start: (usize, usize), goal: (usize, usize)) {
+        assert_eq!(path.cells[0], start);
+        assert_eq!(*path.cells.last().unwrap(), goal);
+        let summed: f32 = path
+            .cells
+            .windows(2)
+            .map(|w| map.move_cost(w[0], w[1]))
+            .sum();
+        assert!((summed - path.cost).abs() < 1e-3);
+    }
+
+    // ==================== JPS ====================
+
+    #[test]
+    fn test_jps_open_grid() {
+        let map = GridMap::new(50, 50);
+        let path = JumpPointSearch.plan(&map, (0, 0), (49, 20)).unwrap();
+        let astar = GridSearch::default().plan(&map, (0, 0), (49, 20)).unwrap();
+        assert_valid(&map, &path, (0, 0), (49, 20));
+        assert!((path.cost - astar.cost).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_jps_matches_astar_on_random_maps() {
+        for seed in 0..20 {
+            let map = random_map(40, 30, 25, seed);
+            let astar = GridSearch::default().plan(&map, (0, 0), (39, 29));
+            let jps = JumpPointSearch.plan(&map, (0, 0), (39, 29));
+            match (astar, jps) {
+                (Some(a), Some(j)) => {
+                    assert!((a.cost - j.cost).abs() < 1e-3, "seed {}", seed);
+                    assert_valid(&map, &j, (0, 0), (39, 29));
+                }
+                (None, None) => {}
+                _ => panic!("reachability differs for seed {}", seed),
+            }
+        }
+    }
+
+    #[test]
+    fn test_jps_expands_fewer_nodes() {
+        let mut map = GridMap::new(120, 120);
+        for y in 10..110 {
+            map.set_obstacle(60, y, true);
+        }
+        let astar = GridSearch::default()
+            .plan(&map, (5, 60), (115, 60))
+            .unwrap();
+        let jps = JumpPointSearch.plan(&map, (5, 60), (115, 60)).unwrap();
+        assert!((astar.cost - jps.cost).abs() < 1e-3);
+        assert!(
+            jps.expansions * 5 < astar.expansions,
+            "{} vs {}",
+            jps.expansions,
+            astar.expansions
+        );
+    }
+
+    #[test]
+    fn test_jps_trivial_and_unreachable() {
+        let mut map = GridMap::new(10, 10);
+        let path = JumpPointSearch.plan(&map, (3, 3), (3, 3)).unwrap();
+        assert_eq!(path.cells, [(3, 3)]);
+
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        assert!(JumpPointSearch.plan(&map, (0, 0), (9, 9)).is_none());
+        assert!(JumpPointSearch.plan(&map, (5, 5), (9, 9)).is_none());
+    }
+
+    #[test]
+    fn test_jps_no_corner_cutting() {
+        let mut map = GridMap::new(3, 3);
+        map.set_obstacle(1, 0, true);
+        map.set_obstacle(0, 1, true);
+        assert!(JumpPointSearch.plan(&map, (0, 0), (2, 2)).is_none());
+    }
+
+    // ==================== JPS+ ====================
+
+    #[test]
+    fn test_jps_plus_matches_astar_on_random_maps() {
+        for seed in 0..20 {
+            let map = random_map(40, 30, 25, seed + 100);
+            let astar = GridSearch::default().plan(&map, (0, 0), (39, 29));
+            let mut jps = JpsPlus::new(map.clone());
+            match (astar, jps.plan((0, 0), (39, 29))) {
+                (Some(a), Some(j)) => {
+                    assert!((a.cost - j.cost).abs() < 1e-3, "seed {}", seed);
+                    assert_valid(&map, &j, (0, 0), (39, 29));
+                }
+                (None, None) => {}
+                _ => panic!("reachability differs for seed {}", seed),
+            }
+        }
+    }
+
+    #[test]
+    fn test_jps_plus_goal_in_open_space() {
+        let map = random_map(30, 30, 15, 7);
+        let mut jps = JpsPlus::new(map.clone());
+        for goal in [(15, 15), (29, 3), (4, 22), (17, 0)] {
+            if map.is_obstacle(goal.0, goal.1) {
+                continue;
+            }
+            let astar = GridSearch::default().plan(&map, (0, 0), goal);
+            let plus = jps.plan((0, 0), goal);
+            assert_eq!(astar.is_some(), plus.is_some());
+            if let (Some(a), Some(p)) = (astar, plus) {
+                assert!((a.cost - p.cost).abs() < 1e-3, "goal {:?}", goal);
+                assert_valid(&map, &p, (0, 0), goal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jps_plus_expands_no_more_than_jps() {
+        let map = random_map(80, 80, 20, 3);
+        let jps = JumpPointSearch.plan(&map, (0, 0), (79, 79));
+        let mut plus = JpsPlus::new(map);
+        let plus = plus.plan((0, 0), (79, 79));
+        if let (Some(j), Some(p)) = (jps, plus) {
+            assert!((j.cost - p.cost).abs() < 1e-3);
+            assert!(p.expansions <= j.expansions + 1);
+        }
+    }
+
+    #[test]
+    fn test_jps_plus_invalidated_by_set_obstacle() {
+        let mut jps = JpsPlus::new(GridMap::new(20, 20));
+        let open = jps.plan((0, 10), (19, 10)).unwrap();
+        assert!(jps.is_preprocessed());
+        assert_eq!(open.cost, 19.0);
+
+        for y in 0..19 {
+            jps.set_obstacle(10, y, true);
+        }
+        assert!(!jps.is_preprocessed());
+        let detour = jps.plan((0, 10), (19, 10)).unwrap();
+        assert!(detour.cost > open.cost);
+        assert!(detour.cells.contains(&(10, 19)));
+
+        let astar = GridSearch::default()
+            .plan(jps.map(), (0, 10), (19, 10))
+            .unwrap();
+        assert!((astar.cost - detour.cost).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_jps_plus_unchanged_cell_keeps_table() {
+        let mut jps = JpsPlus::new(GridMap::new(10, 10));
+        jps.preprocess();
+        jps.set_obstacle(3, 3, false);
+        jps.set_obstacle(30, 3, true);
+        assert!(jps.is_preprocessed());
+    }
+}
@@ -1,13 +1,41 @@
 #![no_std]
 
 extern crate alloc; // needed for Vec
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod boids;
+pub mod cbs;
+pub mod collision;
+pub mod costmap;
+pub mod coverage;
 pub mod dstar;
+pub mod dubins;
 pub mod ekf;
+pub mod ekf_slam;
+pub mod exploration;
+pub mod flow_field;
+pub mod grid_search;
+pub mod hpa;
+pub mod hybrid_astar;
+pub mod inflation;
+pub mod jps;
+pub mod map_io;
+pub mod mapgen;
 pub mod math;
+pub mod navmesh;
+pub mod particle_filter;
+pub mod path;
 pub mod physics;
+pub mod prm;
+pub mod reeds_shepp;
+pub mod rng;
+pub mod rrt;
 pub mod spatial;
+pub mod theta_star;
+pub mod tour;
+pub mod ukf;
+pub mod visibility;
 
 #[cfg(test)]
 mod integration_tests;
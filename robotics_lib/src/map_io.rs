@@ -0,0 +1,423 @@
+use crate::dstar::{GridMap, FREE, LETHAL, UNKNOWN};
+use crate::math::{Pose, Vec2};
+use alloc::vec::Vec;
+
+/// How image pixels map to cell costs, as in ROS `map_server`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapMode {
+    /// Free, lethal or unknown only.
+    Trinary,
+    /// Like `Trinary`, but pixels between the thresholds become graded costs
+    /// instead of unknown. Unknown cells are saved as lethal.
+    Scale,
+    /// Pixel value is the cost.
+    Raw,
+}
+
+/// Metric placement of a grid and its image thresholds.
+///
+/// Cell (x, y) covers `[x, x + 1) * resolution` by `[y, y + 1) * resolution`
+/// in the map frame, whose lower-left corner sits at `origin` in the world.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapInfo {
+    /// Metres per cell.
+    pub resolution: f32,
+    /// World pose of the lower-left corner of cell (0, 0).
+    pub origin: Pose,
+    /// Dark pixels are free instead of occupied.
+    pub negate: bool,
+    pub occupied_thresh: f32,
+    pub free_thresh: f32,
+    pub mode: MapMode,
+}
+
+impl Default for MapInfo {
+    fn default() -> Self {
+        Self::new(1.0, Pose::new(0.0, 0.0, 0.0))
+    }
+}
+
+impl MapInfo {
+    pub fn new(resolution: f32, origin: Pose) -> Self {
+        Self {
+            resolution,
+            origin,
+            negate: false,
+            occupied_thresh: 0.65,
+            free_thresh: 0.196,
+            mode: MapMode::Trinary,
+        }
+    }
+
+    /// Continuous grid coordinates (in cells) to world coordinates.
+    pub fn grid_to_world(&self, g: Vec2) -> Vec2 {
+        let (s, c) = (libm::sinf(self.origin.theta), libm::cosf(self.origin.theta));
+        let local = g * self.resolution;
+        Vec2::new(
+            self.origin.x + c * local.x - s * local.y,
+            self.origin.y + s * local.x + c * local.y,
+        )
+    }
+
+    /// World coordinates to continuous grid coordinates (in cells).
+    pub fn world_to_grid(&self, p: Vec2) -> Vec2 {
+        let (s, c) = (libm::sinf(self.origin.theta), libm::cosf(self.origin.theta));
+        let d = Vec2::new(p.x - self.origin.x, p.y - self.origin.y);
+        Vec2::new(c * d.x + s * d.y, -s * d.x + c * d.y) / self.resolution
+    }
+
+    /// World position of a cell's centre.
+    pub fn cell_to_world(&self, cell: (usize, usize)) -> Vec2 {
+        self.grid_to_world(Vec2::new(cell.0 as f32 + 0.5, cell.1 as f32 + 0.5))
+    }
+
+    /// Cell containing a world position, or `None` outside the map.
+    pub fn world_to_cell(&self, map: &GridMap, p: Vec2) -> Option<(usize, usize)> {
+        let g = self.world_to_grid(p);
+        let (x, y) = (libm::floorf(g.x), libm::floorf(g.y));
+        if x < 0.0 || y < 0.0 || x >= map.width as f32 || y >= map.height as f32 {
+            return None;
+        }
+        Some((x as usize, y as usize))
+    }
+
+    /// Cell path to world waypoints at cell centres.
+    pub fn path_to_world(&self, cells: &[(usize, usize)]) -> Vec<Vec2> {
+        cells.iter().map(|&c| self.cell_to_world(c)).collect()
+    }
+
+    // Occupancy probability encoded by a pixel
+    fn occupancy(&self, pixel: u8) -> f32 {
+        let v = pixel as f32 / 255.0;
+        if self.negate {
+            v
+        } else {
+            1.0 - v
+        }
+    }
+
+    fn pixel(&self, occupancy: f32) -> u8 {
+        let v = if self.negate {
+            occupancy
+        } else {
+            1.0 - occupancy
+        };
+        libm::roundf(v.clamp(0.0, 1.0) * 255.0) as u8
+    }
+
+    /// Cell cost for an image pixel.
+    pub fn cost_from_pixel(&self, pixel: u8) -> u8 {
+        if self.mode == MapMode::Raw {
+            return pixel;
+        }
+        let p = self.occupancy(pixel);
+        if p > self.occupied_thresh {
+            LETHAL
+        } else if p < self.free_thresh {
+            FREE
+        } else if self.mode == MapMode::Trinary {
+            UNKNOWN
+        } else {
+            let t = (p - self.free_thresh) / (self.occupied_thresh - self.free_thresh);
+            1 + libm::roundf(t * (LETHAL - 2) as f32) as u8
+        }
+    }
+
+    /// Image pixel for a cell cost; the inverse of [`MapInfo::cost_from_pixel`]
+    /// up to the mode's precision.
+    pub fn pixel_from_cost(&self, cost: u8) -> u8 {
+        let unknown = 0.5 * (self.free_thresh + self.occupied_thresh);
+        match self.mode {
+            MapMode::Raw => cost,
+            _ if cost == FREE => self.pixel(0.0),
+            MapMode::Trinary if cost == UNKNOWN => self.pixel(unknown),
+            _ if cost >= LETHAL => self.pixel(1.0),
+            MapMode::Trinary => {
+                // Graded costs snap to the nearest class, like map_saver
+                let p = cost as f32 / LETHAL as f32;
+                if p > self.occupied_thresh {
+                    self.pixel(1.0)
+                } else if p < self.free_thresh {
+                    self.pixel(0.0)
+                } else {
+                    self.pixel(unknown)
+                }
+            }
+            MapMode::Scale => {
+                let t = (cost - 1) as f32 / (LETHAL - 2) as f32;
+                self.pixel(self.free_thresh + t * (self.occupied_thresh - self.free_thresh))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::files::*;
+
+// PGM + YAML reading and writing
+#[cfg(feature = "std")]
+mod files {
+    use super::{MapInfo, MapMode};
+    use crate::dstar::GridMap;
+    use crate::math::Pose;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::fmt;
+    use std::path::Path;
+
+    #[derive(Debug)]
+    pub enum MapIoError {
+        Io(std::io::Error),
+        /// Malformed or unsupported file contents.
+        Format(String),
+    }
+
+    impl fmt::Display for MapIoError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MapIoError::Io(e) => write!(f, "map I/O error: {}", e),
+                MapIoError::Format(msg) => write!(f, "invalid map file: {}", msg),
+            }
+        }
+    }
+
+    impl std::error::Error for MapIoError {}
+
+    impl From<std::io::Error> for MapIoError {
+        fn from(e: std::io::Error) -> Self {
+            MapIoError::Io(e)
+        }
+    }
+
+    fn format_error(msg: &str) -> MapIoError {
+        MapIoError::Format(msg.to_string())
+    }
+
+    /// A greyscale image, rows stored top to bottom.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Pgm {
+        pub width: usize,
+        pub height: usize,
+        pub pixels: Vec<u8>,
+    }
+
+    /// Parse a binary (P5) or ASCII (P2) PGM with up to 8-bit samples.
+    pub fn parse_pgm(bytes: &[u8]) -> Result<Pgm, MapIoError> {
+        let mut pos = 0;
+        let mut header = [0usize; 3];
+        let magic = next_token(bytes, &mut pos).ok_or_else(|| format_error("empty PGM"))?;
+        let binary = match magic {
+            b"P5" => true,
+            b"P2" => false,
+            _ => return Err(format_error("not a P2/P5 PGM")),
+        };
+        for value in header.iter_mut() {
+            *value = next_number(bytes, &mut pos)?;
+        }
+        let [width, height, maxval] = header;
+        if maxval == 0 || maxval > 255 {
+            return Err(format_error("only 8-bit PGMs are supported"));
+        }
+        // Every sample takes at least one byte, so a header promising more
+        // than the file holds is rejected before anything is allocated
+        let n = width
+            .checked_mul(height)
+            .filter(|&n| n <= bytes.len().saturating_sub(pos))
+            .ok_or_else(|| format_error("PGM data truncated"))?;
+        let scale = |v: usize| ((v.min(maxval) * 255 + maxval / 2) / maxval) as u8;
+
+        let pixels: Vec<u8> = if binary {
+            // Exactly one whitespace byte separates the header from the data
+            let data = bytes
+                .get(pos + 1..pos + 1 + n)
+                .ok_or_else(|| format_error("PGM data truncated"))?;
+            data.iter().map(|&v| scale(v as usize)).collect()
+        } else {
+            let mut pixels = Vec::new();
+            for _ in 0..n {
+                pixels.push(scale(next_number(bytes, &mut pos)?));
+            }
+            pixels
+        };
+        Ok(Pgm {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Encode a binary (P5) PGM.
+    pub fn write_pgm(pgm: &Pgm) -> Vec<u8> {
+        let mut out = std::format!("P5\n{} {}\n255\n", pgm.width, pgm.height).into_bytes();
+        out.extend_from_slice(&pgm.pixels);
+        out
+    }
+
+    // Next whitespace-separated header token, skipping `#` comments
+    fn next_token<'a>(bytes: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+        loop {
+            while *pos < bytes.len() && bytes[*pos].is_ascii_whitespace() {
+                *pos += 1;
+            }
+            if *pos < bytes.len() && bytes[*pos] == b'#' {
+                while *pos < bytes.len() && bytes[*pos] != b'\n' {
+                    *pos += 1;
+                }
+                continue;
+            }
+            break;
+        }
+        let start = *pos;
+        while *pos < bytes.len() && !bytes[*pos].is_ascii_whitespace() && bytes[*pos] != b'#' {
+            *pos += 1;
+        }
+        (*pos > start).then(|| &bytes[start..*pos])
+    }
+
+    fn next_number(bytes: &[u8], pos: &mut usize) -> Result<usize, MapIoError> {
+        next_token(bytes, pos)
+            .and_then(|t| core::str::from_utf8(t).ok())
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| format_error("bad number in PGM"))
+    }
+
+    /// Parse `map_server` YAML metadata. Returns the image file name and the
+    /// map info; unknown keys are ignored.
+    pub fn parse_yaml(text: &str) -> Result<(String, MapInfo), MapIoError> {
+        let mut image = None;
+        let mut resolution = None;
+        let mut info = MapInfo::default();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            let number = || {
+                value
+                    .parse::<f32>()
+                    .map_err(|_| MapIoError::Format(std::format!("bad value for {}", key)))
+            };
+            match key.trim() {
+                "image" => image = Some(value.to_string()),
+                "resolution" => resolution = Some(number()?),
+                "occupied_thresh" => info.occupied_thresh = number()?,
+                "free_thresh" => info.free_thresh = number()?,
+                "negate" => info.negate = matches!(value, "1" | "true" | "True"),
+                "mode" => {
+                    info.mode = match value {
+                        "trinary" => MapMode::Trinary,
+                        "scale" => MapMode::Scale,
+                        "raw" => MapMode::Raw,
+                        _ => return Err(format_error("unknown mode")),
+                    }
+                }
+                "origin" => {
+                    let values: Vec<f32> = value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(|v| v.trim().parse::<f32>())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| format_error("bad origin"))?;
+                    if values.len() != 3 {
+                        return Err(format_error("origin needs [x, y, yaw]"));
+                    }
+                    info.origin = Pose::new(values[0], values[1], values[2]);
+                }
+                _ => {}
+            }
+        }
+        info.resolution = resolution.ok_or_else(|| format_error("missing resolution"))?;
+        // The float parser accepts "nan" and "inf", which would poison
+        // every world/cell conversion
+        if !(info.resolution.is_finite() && info.resolution > 0.0) {
+            return Err(format_error("resolution must be positive and finite"));
+        }
+        let image = image.ok_or_else(|| format_error("missing image"))?;
+        Ok((image, info))
+    }
+
+    /// Encode `map_server` YAML metadata.
+    pub fn write_yaml(image: &str, info: &MapInfo) -> String {
+        let mode = match info.mode {
+            MapMode::Trinary => "trinary",
+            MapMode::Scale => "scale",
+            MapMode::Raw => "raw",
+        };
+        std::format!(
+            "image: {}\nmode: {}\nresolution: {}\norigin: [{}, {}, {}]\nnegate: {}\noccupied_thresh: {}\nfree_thresh: {}\n",
+            image,
+            mode,
+            info.resolution,
+            info.origin.x,
+            info.origin.y,
+            info.origin.theta,
+            info.negate as u8,
+            info.occupied_thresh,
+            info.free_thresh,
+        )
+    }
+
+    /// Build a grid from an image; the image's bottom row becomes y = 0.
+    pub fn map_from_pgm(pgm: &Pgm, info: &MapInfo) -> GridMap {
+        let mut map = GridMap::new(pgm.width, pgm.height);
+        for row in 0..pgm.height {
+            let y = pgm.height - 1 - row;
+            for x in 0..pgm.width {
+                let pixel = pgm.pixels[row * pgm.width + x];
+                map.set_cost(x, y, info.cost_from_pixel(pixel));
+            }
+        }
+        map
+    }
+
+    /// Render a grid as an image (inverse of [`map_from_pgm`]).
+    pub fn map_to_pgm(map: &GridMap, info: &MapInfo) -> Pgm {
+        let mut pixels = Vec::with_capacity(map.width * map.height);
+        for row in 0..map.height {
+            let y = map.height - 1 - row;
+            for x in 0..map.width {
+                pixels.push(info.pixel_from_cost(map.cost(x, y)));
+            }
+        }
+        Pgm {
+            width: map.width,
+            height: map.height,
+            pixels,
+        }
+    }
+
+    /// Load a map from its YAML file; the image path is relative to it.
+    pub fn load_map(yaml_path: impl AsRef<Path>) -> Result<(GridMap, MapInfo), MapIoError> {
+        let yaml_path = yaml_path.as_ref();
+        let (image, info) = parse_yaml(&std::fs::read_to_string(yaml_path)?)?;
+        let image_path = match yaml_path.parent() {
+            Some(dir) => dir.join(&image),
+            None => image.into(),
+        };
+        let pgm = parse_pgm(&std::fs::read(image_path)?)?;
+        Ok((map_from_pgm(&pgm, &info), info))
+    }
+
+    /// Save a map as `<name>.yaml` plus `<name>.pgm` next to it.
+    pub fn save_map(
+        map: &GridMap,
+        info: &MapInfo,
+        yaml_path: impl AsRef<Path>,
+    ) -> Result<(), MapIoError> {
+        let yaml_path = yaml_path.as_ref();
+        let image_path = yaml_path.with_extension("pgm");
+        let image = image_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format_error("bad file name"))?;
+        std::fs::write(&image_path, write_pgm(&map_to_pgm(map, info)))?;
+        std::fs::write(yaml_path, write_yaml(image, info))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "map_io_tests.rs"]
+mod tests;
@@ -0,0 +1,236 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::{GridMap, FREE, LETHAL, UNKNOWN};
+    use crate::map_io::{MapInfo, MapMode};
+    use crate::math::{Pose, Vec2};
+    use core::f32::consts::FRAC_PI_2;
+
+    fn near(a: Vec2, b: Vec2) -> bool {
+        a.distance(&b) < 1e-4
+    }
+
+    // ==================== COORDINATES ====================
+
+    #[test]
+    fn test_cell_world_round_trip() {
+        let info = MapInfo::new(0.05, Pose::new(-10.0, -5.0, 0.0));
+        let map = GridMap::new(400, 200);
+        assert!(near(info.cell_to_world((0, 0)), Vec2::new(-9.975, -4.975)));
+        assert!(near(
+            info.cell_to_world((200, 100)),
+            Vec2::new(0.025, 0.025)
+        ));
+        assert_eq!(
+            info.world_to_cell(&map, Vec2::new(0.01, 0.04)),
+            Some((200, 100))
+        );
+        assert_eq!(info.world_to_cell(&map, Vec2::new(-10.01, 0.0)), None);
+        assert_eq!(info.world_to_cell(&map, Vec2::new(10.01, 0.0)), None);
+        for &cell in &[(0, 0), (17, 3), (399, 199)] {
+            assert_eq!(
+                info.world_to_cell(&map, info.cell_to_world(cell)),
+                Some(cell)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotated_origin() {
+        // Map frame rotated a quarter turn: grid +x points along world +y
+        let info = MapInfo::new(0.5, Pose::new(1.0, 2.0, FRAC_PI_2));
+        assert!(near(
+            info.grid_to_world(Vec2::new(2.0, 0.0)),
+            Vec2::new(1.0, 3.0)
+        ));
+        assert!(near(
+            info.grid_to_world(Vec2::new(0.0, 2.0)),
+            Vec2::new(0.0, 2.0)
+        ));
+        let g = Vec2::new(3.3, 1.7);
+        assert!(near(info.world_to_grid(info.grid_to_world(g)), g));
+    }
+
+    #[test]
+    fn test_path_to_world() {
+        let info = MapInfo::new(0.1, Pose::new(0.0, 0.0, 0.0));
+        let path = info.path_to_world(&[(0, 0), (1, 0), (2, 1)]);
+        assert_eq!(path.len(), 3);
+        assert!(near(path[2], Vec2::new(0.25, 0.15)));
+    }
+
+    // ==================== PIXELS ====================
+
+    #[test]
+    fn test_trinary_pixels() {
+        let info = MapInfo::default();
+        assert_eq!(info.cost_from_pixel(254), FREE);
+        assert_eq!(info.cost_from_pixel(0), LETHAL);
+        assert_eq!(info.cost_from_pixel(205), UNKNOWN);
+        for cost in [FREE, LETHAL, UNKNOWN] {
+            assert_eq!(info.cost_from_pixel(info.pixel_from_cost(cost)), cost);
+        }
+        // Graded costs snap to a class
+        assert_eq!(info.cost_from_pixel(info.pixel_from_cost(10)), FREE);
+        assert_eq!(info.cost_from_pixel(info.pixel_from_cost(240)), LETHAL);
+    }
+
+    #[test]
+    fn test_negate() {
+        let info = MapInfo {
+            negate: true,
+            ..MapInfo::default()
+        };
+        assert_eq!(info.cost_from_pixel(0), FREE);
+        assert_eq!(info.cost_from_pixel(255), LETHAL);
+        assert_eq!(info.pixel_from_cost(FREE), 0);
+    }
+
+    #[test]
+    fn test_scale_mode_keeps_grades() {
+        let info = MapInfo {
+            mode: MapMode::Scale,
+            ..MapInfo::default()
+        };
+        assert_eq!(info.cost_from_pixel(info.pixel_from_cost(FREE)), FREE);
+        assert_eq!(info.cost_from_pixel(info.pixel_from_cost(LETHAL)), LETHAL);
+        for cost in (1..LETHAL).step_by(7) {
+            let back = info.cost_from_pixel(info.pixel_from_cost(cost));
+            assert!(
+                (back as i32 - cost as i32).abs() <= 2,
+                "{} -> {}",
+                cost,
+                back
+            );
+        }
+    }
+
+    #[test]
+    fn test_raw_mode() {
+        let info = MapInfo {
+            mode: MapMode::Raw,
+            ..MapInfo::default()
+        };
+        for v in [0u8, 17, 254, 255] {
+            assert_eq!(info.cost_from_pixel(v), v);
+            assert_eq!(info.pixel_from_cost(v), v);
+        }
+    }
+
+    // ==================== FILES ====================
+
+    #[cfg(feature = "std")]
+    mod files {
+        use crate::dstar::{GridMap, FREE, LETHAL, UNKNOWN};
+        use crate::map_io::{
+            load_map, map_from_pgm, map_to_pgm, parse_pgm, parse_yaml, save_map, write_pgm,
+            write_yaml, MapInfo, MapMode,
+        };
+        use crate::math::Pose;
+
+        #[test]
+        fn test_parse_ascii_pgm_with_comments() {
+            let text = b"P2\n# made by hand\n3 2 # width height\n15\n0 15 7\n15 15 0\n";
+            let pgm = parse_pgm(text).unwrap();
+            assert_eq!((pgm.width, pgm.height), (3, 2));
+            assert_eq!(pgm.pixels, alloc::vec![0, 255, 119, 255, 255, 0]);
+        }
+
+        #[test]
+        fn test_binary_pgm_round_trip() {
+            let pgm = crate::map_io::Pgm {
+                width: 4,
+                height: 2,
+                pixels: alloc::vec![0, 10, 32, 255, 205, 254, 35, 9],
+            };
+            assert_eq!(parse_pgm(&write_pgm(&pgm)).unwrap(), pgm);
+        }
+
+        #[test]
+        fn test_pgm_errors() {
+            assert!(parse_pgm(b"").is_err());
+            assert!(parse_pgm(b"P6\n1 1\n255\n\0\0\0").is_err());
+            assert!(parse_pgm(b"P5\n2 2\n255\n\0").is_err());
+            assert!(parse_pgm(b"P5\n1 1\n65535\n\0\0").is_err());
+            assert!(parse_pgm(b"P2\n2 1\n255\n1 x").is_err());
+            // Headers promising more than the file holds
+            assert!(parse_pgm(b"P5 4294967296 4294967297 255\n\0").is_err());
+            assert!(parse_pgm(b"P5 18446744073709551615 1 255\n\0").is_err());
+            assert!(parse_pgm(b"P2 100000000000 100000000000 255\n1").is_err());
+        }
+
+        #[test]
+        fn test_parse_ros_yaml() {
+            let yaml = "image: lab.pgm\nresolution: 0.050000\norigin: [-12.2, -6.5, 0.0]\n\
+                        negate: 0\noccupied_thresh: 0.65\nfree_thresh: 0.196 # default\n";
+            let (image, info) = parse_yaml(yaml).unwrap();
+            assert_eq!(image, "lab.pgm");
+            assert_eq!(info.resolution, 0.05);
+            assert_eq!(info.origin, Pose::new(-12.2, -6.5, 0.0));
+            assert!(!info.negate);
+            assert_eq!(info.mode, MapMode::Trinary);
+            assert_eq!(info.free_thresh, 0.196);
+        }
+
+        #[test]
+        fn test_yaml_round_trip_and_errors() {
+            let info = MapInfo {
+                negate: true,
+                mode: MapMode::Scale,
+                ..MapInfo::new(0.1, Pose::new(1.5, -2.0, 0.25))
+            };
+            let (image, parsed) = parse_yaml(&write_yaml("m.pgm", &info)).unwrap();
+            assert_eq!(image, "m.pgm");
+            assert_eq!(parsed, info);
+
+            assert!(parse_yaml("image: a.pgm\n").is_err());
+            assert!(parse_yaml("resolution: 0.1\n").is_err());
+            assert!(parse_yaml("image: a.pgm\nresolution: 0.1\norigin: [1, 2]\n").is_err());
+            assert!(parse_yaml("image: a.pgm\nresolution: abc\n").is_err());
+            for bad in ["0", "-0.05", "nan", "inf", "-inf"] {
+                let yaml = std::format!("image: a.pgm\nresolution: {}\n", bad);
+                assert!(parse_yaml(&yaml).is_err(), "{}", bad);
+            }
+        }
+
+        #[test]
+        fn test_image_rows_are_flipped() {
+            let pgm = crate::map_io::Pgm {
+                width: 2,
+                height: 2,
+                pixels: alloc::vec![0, 254, 254, 205],
+            };
+            let map = map_from_pgm(&pgm, &MapInfo::default());
+            // Top-left pixel is the cell with the largest y
+            assert_eq!(map.cost(0, 1), LETHAL);
+            assert_eq!(map.cost(1, 1), FREE);
+            assert_eq!(map.cost(1, 0), UNKNOWN);
+            assert_eq!(map_to_pgm(&map, &MapInfo::default()).pixels[0], 0);
+        }
+
+        #[test]
+        fn test_save_and_load_map() {
+            let mut map = GridMap::new(7, 5);
+            map.set_obstacle(1, 1, true);
+            map.set_obstacle(6, 4, true);
+            map.set_cost(3, 2, UNKNOWN);
+            let info = MapInfo::new(0.05, Pose::new(-1.0, 2.0, 0.0));
+
+            let dir = std::env::temp_dir().join(std::format!("map_io_test_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let yaml = dir.join("office.yaml");
+            save_map(&map, &info, &yaml).unwrap();
+            assert!(dir.join("office.pgm").exists());
+
+            let (loaded, loaded_info) = load_map(&yaml).unwrap();
+            std::fs::remove_dir_all(&dir).unwrap();
+            assert_eq!(loaded.costs, map.costs);
+            assert_eq!(loaded_info, info);
+        }
+
+        #[test]
+        fn test_load_missing_file() {
+            let err = load_map("/nonexistent/dir/map.yaml").unwrap_err();
+            assert!(matches!(err, crate::map_io::MapIoError::Io(_)));
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use crate::dstar::{GridMap, NEIGHBORS_8};
+use crate::rng::Rng;
+use alloc::vec::Vec;
+
+type Cell = (usize, usize);
+
+/// A generated map with a suggested start and goal.
+#[derive(Clone, Debug)]
+pub struct GeneratedMap {
+    pub map: GridMap,
+    pub start: Cell,
+    pub goal: Cell,
+    /// True if `goal` can be reached from `start` with 8-connected moves.
+    pub connected: bool,
+}
+
+impl GeneratedMap {
+    fn new(map: GridMap, start: Cell, goal: Cell) -> Self {
+        let connected = connected(&map, start, goal);
+        Self {
+            map,
+            start,
+            goal,
+            connected,
+        }
+    }
+}
+
+/// True if `b` is reachable from `a` with the moves planners use (8-connected,
+/// no corner cutting).
+pub fn connected(map: &GridMap, a: Cell, b: Cell) -> bool {
+    if a.0 >= map.width || a.1 >= map.height || map.is_obstacle(a.0, a.1) {
+        return false;
+    }
+    let mut seen = alloc::vec![false; map.width * map.height];
+    let mut stack = alloc::vec![a];
+    seen[map.index(a.0, a.1)] = true;
+    while let Some(cell) = stack.pop() {
+        if cell == b {
+            return true;
+        }
+        for (next, _) in map.neighbors(cell.0, cell.1) {
+            let idx = map.index(next.0, next.1);
+            if !seen[idx] {
+                seen[idx] = true;
+                stack.push(next);
+            }
+        }
+    }
+    false
+}
+
+/// Fraction of cells that are obstacles.
+pub fn obstacle_density(map: &GridMap) -> f32 {
+    let blocked = (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| map.is_obstacle(x, y))
+        .count();
+    blocked as f32 / (map.width * map.height).max(1) as f32
+}
+
+fn filled(width: usize, height: usize) -> GridMap {
+    let mut map = GridMap::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            map.set_obstacle(x, y, true);
+        }
+    }
+    map
+}
+
+// Maze cells sit on odd coordinates with walls in between
+fn maze_corners(width: usize, height: usize) -> (Cell, Cell) {
+    let last = |n: usize| if n.is_multiple_of(2) { n - 3 } else { n - 2 };
+    ((1, 1), (last(width), last(height)))
+}
+
+/// Perfect maze (exactly one route between any two cells) carved by a
+/// randomised depth-first backtracker. Corridors are one cell wide. Start
+/// and goal are opposite corners and always connected. Needs at least 3x3.
+pub fn perfect_maze(width: usize, height: usize, seed: u64) -> GeneratedMap {
+    let width = width.max(3);
+    let height = height.max(3);
+    let mut rng = Rng::new(seed);
+    let mut map = filled(width, height);
+
+    let (cols, rows) = ((width - 1) / 2, (height - 1) / 2);
+    let mut visited = alloc::vec![false; cols * rows];
+    let mut stack = alloc::vec![(0usize, 0usize)];
+    visited[0] = true;
+    map.set_obstacle(1, 1, false);
+
+    let mut options = Vec::with_capacity(4);
+    while let Some(&(cx, cy)) = stack.last() {
+        options.clear();
+        for &(dx, dy) in &NEIGHBORS_8[..4] {
+            let nx = cx as isize + dx;
+            let ny = cy as isize + dy;
+            if nx >= 0 && ny >= 0 && (nx as usize) < cols && (ny as usize) < rows {
+                let (nx, ny) = (nx as usize, ny as usize);
+                if !visited[ny * cols + nx] {
+                    options.push((nx, ny));
+                }
+            }
+        }
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = options[rng.index(options.len())];
+        visited[ny * cols + nx] = true;
+        // Open the target cell and the wall between
+        map.set_obstacle(2 * nx + 1, 2 * ny + 1, false);
+        map.set_obstacle(cx + nx + 1, cy + ny + 1, false);
+        stack.push((nx, ny));
+    }
+
+    let (start, goal) = maze_corners(width, height);
+    GeneratedMap::new(map, start, goal)
+}
+
+/// Dead ends of a maze: open cells with a single open orthogonal neighbour.
+pub fn dead_ends(map: &GridMap) -> Vec<Cell> {
+    let mut out = Vec::new();
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if !map.is_obstacle(x, y) && open_sides(map, x, y) == 1 {
+                out.push((x, y));
+            }
+        }
+    }
+    out
+}
+
+fn open_sides(map: &GridMap, x: usize, y: usize) -> usize {
+    NEIGHBORS_8[..4]
+        .iter()
+        .filter(|&&(dx, dy)| {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            map.in_bounds(nx, ny) && !map.is_obstacle(nx as usize, ny as usize)
+        })
+        .count()
+}
+
+/// Perfect maze with a fraction `braid` (0..=1) of its dead ends knocked
+/// through into a neighbouring corridor, which adds loops.
+pub fn braided_maze(width: usize, height: usize, braid: f32, seed: u64) -> GeneratedMap {
+    let mut generated = perfect_maze(width, height, seed);
+    let map = &mut generated.map;
+    let mut rng = Rng::new(seed ^ 0xB4A1_D000);
+
+    let mut options = Vec::with_capacity(3);
+    for (x, y) in dead_ends(map) {
+        // An earlier removal may already have opened this one
+        if open_sides(map, x, y) != 1 || rng.next_f32() >= braid {
+            continue;
+        }
+        options.clear();
+        for &(dx, dy) in &NEIGHBORS_8[..4] {
+            let (wx, wy) = (x as isize + dx, y as isize + dy);
+            let (cx, cy) = (x as isize + 2 * dx, y as isize + 2 * dy);
+            let inner = |v: isize, n: usize| v > 0 && (v as usize) < n - 1;
+            if inner(cx, map.width)
+                && inner(cy, map.height)
+                && map.is_obstacle(wx as usize, wy as usize)
+            {
+                options.push((wx as usize, wy as usize));
+            }
+        }
+        if !options.is_empty() {
+            let (wx, wy) = options[rng.index(options.len())];
+            map.set_obstacle(wx, wy, false);
+        }
+    }
+    generated
+}
+
+/// Cellular-automata caves. Cells start as rock with probability `fill`
+/// (about 0.45 works well) and are smoothed `iterations` times: a cell becomes
+/// rock with five or more rock neighbours and floor with three or fewer.
+/// Start and goal are the floor cells nearest two opposite corners; caves
+/// can split into pockets, so check `connected`.
+pub fn caves(width: usize, height: usize, fill: f32, iterations: usize, seed: u64) -> GeneratedMap {
+    let mut rng = Rng::new(seed);
+    let mut map = GridMap::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let border = x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+            map.set_obstacle(x, y, border || rng.next_f32() < fill);
+        }
+    }
+
+    for _ in 0..iterations {
+        let previous = map.clone();
+        for y in 1..height.saturating_sub(1) {
+            for x in 1..width.saturating_sub(1) {
+                let rock = NEIGHBORS_8
+                    .iter()
+                    .filter(|&&(dx, dy)| {
+                        previous.is_obstacle((x as isize + dx) as usize, (y as isize + dy) as usize)
+                    })
+                    .count();
+                if rock >= 5 {
+                    map.set_obstacle(x, y, true);
+                } else if rock <= 3 {
+                    map.set_obstacle(x, y, false);
+                }
+            }
+        }
+    }
+
+    let start = nearest_free(&map, (0, 0)).unwrap_or((0, 0));
+    let goal =
+        nearest_free(&map, (width.saturating_sub(1), height.saturating_sub(1))).unwrap_or((0, 0));
+    GeneratedMap::new(map, start, goal)
+}
+
+fn nearest_free(map: &GridMap, target: Cell) -> Option<Cell> {
+    let d = |c: Cell| {
+        let dx = c.0 as isize - target.0 as isize;
+        let dy = c.1 as isize - target.1 as isize;
+        dx * dx + dy * dy
+    };
+    (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| !map.is_obstacle(x, y))
+        .min_by_key(|&c| d(c))
+}
+
+// Start/goal for open fields, with a clear 3x3 patch around each
+fn field_corners(width: usize, height: usize) -> (Cell, Cell) {
+    (
+        (
+            1.min(width.saturating_sub(1)),
+            1.min(height.saturating_sub(1)),
+        ),
+        (width.saturating_sub(2), height.saturating_sub(2)),
+    )
+}
+
+fn near_cell(x: usize, y: usize, c: Cell) -> bool {
+    x.abs_diff(c.0) <= 1 && y.abs_diff(c.1) <= 1
+}
+
+/// Random axis-aligned rectangles with sides up to `max_size` until about
+/// `density` of the map is blocked. The start and goal corners are kept
+/// clear; check `connected`.
+pub fn random_rects(
+    width: usize,
+    height: usize,
+    density: f32,
+    max_size: usize,
+    seed: u64,
+) -> GeneratedMap {
+    let (start, goal) = field_corners(width, height);
+    let mut rng = Rng::new(seed);
+    let mut map = GridMap::new(width, height);
+    let target = (density.clamp(0.0, 1.0) * (width * height) as f32) as usize;
+    let mut blocked = 0;
+    let mut attempts = 0;
+    while blocked < target && attempts < 100 * target.max(1) {
+        attempts += 1;
+        let (w, h) = (
+            1 + rng.index(max_size.max(1)),
+            1 + rng.index(max_size.max(1)),
+        );
+        let (x0, y0) = (rng.index(width), rng.index(height));
+        for y in y0..(y0 + h).min(height) {
+            for x in x0..(x0 + w).min(width) {
+                if blocked < target
+                    && !near_cell(x, y, start)
+                    && !near_cell(x, y, goal)
+                    && !map.is_obstacle(x, y)
+                {
+                    map.set_obstacle(x, y, true);
+                    blocked += 1;
+                }
+            }
+        }
+    }
+    GeneratedMap::new(map, start, goal)
+}
+
+/// Random discs with radius up to `max_radius` until about `density` of the
+/// map is blocked. The start and goal corners are kept clear; check
+/// `connected`.
+pub fn random_circles(
+    width: usize,
+    height: usize,
+    density: f32,
+    max_radius: f32,
+    seed: u64,
+) -> GeneratedMap {
+    let (start, goal) = field_corners(width, height);
+    let mut rng = Rng::new(seed);
+    let mut map = GridMap::new(width, height);
+    let target = (density.clamp(0.0, 1.0) * (width * height) as f32) as usize;
+    let mut blocked = 0;
+    let mut attempts = 0;
+    while blocked < target && attempts < 100 * target.max(1) {
+        attempts += 1;
+        let r = rng.range(0.5, max_radius.max(0.5));
+        let (cx, cy) = (rng.range(0.0, width as f32), rng.range(0.0, height as f32));
+        let x0 = libm::floorf(cx - r).max(0.0) as usize;
+        let y0 = libm::floorf(cy - r).max(0.0) as usize;
+        let x1 = (libm::ceilf(cx + r) as usize).min(width);
+        let y1 = (libm::ceilf(cy + r) as usize).min(height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                if dx * dx + dy * dy <= r * r
+                    && blocked < target
+                    && !near_cell(x, y, start)
+                    && !near_cell(x, y, goal)
+                    && !map.is_obstacle(x, y)
+                {
+                    map.set_obstacle(x, y, true);
+                    blocked += 1;
+                }
+            }
+        }
+    }
+    GeneratedMap::new(map, start, goal)
+}
+
+/// Up to `rooms` non-overlapping rectangular rooms (sides 3..=`max_room`)
+/// joined in creation order by L-shaped corridors. Start is the centre of the
+/// first room and goal the centre of the last, so they are always connected.
+pub fn rooms_and_corridors(
+    width: usize,
+    height: usize,
+    rooms: usize,
+    max_room: usize,
+    seed: u64,
+) -> GeneratedMap {
+    let mut rng = Rng::new(seed);
+    let mut map = filled(width, height);
+    let max_room = max_room.max(3);
+    // (x, y, w, h) of placed rooms
+    let mut placed: Vec<(usize, usize, usize, usize)> = Vec::new();
+
+    for _ in 0..rooms * 20 {
+        if placed.len() >= rooms {
+            break;
+        }
+        let w = 3 + rng.index(max_room - 2);
+        let h = 3 + rng.index(max_room - 2);
+        if w + 2 > width || h + 2 > height {
+            continue;
+        }
+        let x = 1 + rng.index(width - w - 1);
+        let y = 1 + rng.index(height - h - 1);
+        // Keep a wall between rooms
+        let overlaps = placed
+            .iter()
+            .any(|&(ox, oy, ow, oh)| x <= ox + ow && ox <= x + w && y <= oy + oh && oy <= y + h);
+        if overlaps {
+            continue;
+        }
+        for cy in y..y + h {
+            for cx in x..x + w {
+                map.set_obstacle(cx, cy, false);
+            }
+        }
+        placed.push((x, y, w, h));
+    }
+
+    let centre = |&(x, y, w, h): &(usize, usize, usize, usize)| (x + w / 2, y + h / 2);
+    for pair in placed.windows(2) {
+        let (a, b) = (centre(&pair[0]), centre(&pair[1]));
+        // Horizontal then vertical, or the other way round
+        let corner = if rng.next_f32() < 0.5 {
+            (b.0, a.1)
+        } else {
+            (a.0, b.1)
+        };
+        carve_line(&mut map, a, corner);
+        carve_line(&mut map, corner, b);
+    }
+
+    let start = placed.first().map(centre).unwrap_or((0, 0));
+    let goal = placed.last().map(centre).unwrap_or((0, 0));
+    GeneratedMap::new(map, start, goal)
+}
+
+// Clear an axis-aligned run of cells
+fn carve_line(map: &mut GridMap, a: Cell, b: Cell) {
+    for y in a.1.min(b.1)..=a.1.max(b.1) {
+        for x in a.0.min(b.0)..=a.0.max(b.0) {
+            map.set_obstacle(x, y, false);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "mapgen_tests.rs"]
+mod tests;
@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::mapgen::{
+        braided_maze, caves, connected, dead_ends, obstacle_density, perfect_maze, random_circles,
+        random_rects, rooms_and_corridors,
+    };
+
+    fn free_cells(map: &GridMap) -> usize {
+        (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| !map.is_obstacle(x, y))
+            .count()
+    }
+
+    // Free cell pairs sharing an edge
+    fn open_edges(map: &GridMap) -> usize {
+        let mut n = 0;
+        for y in 0..map.height {
+            for x in 0..map.width {
+                if map.is_obstacle(x, y) {
+                    continue;
+                }
+                if x + 1 < map.width && !map.is_obstacle(x + 1, y) {
+                    n += 1;
+                }
+                if y + 1 < map.height && !map.is_obstacle(x, y + 1) {
+                    n += 1;
+                }
+            }
+        }
+        n
+    }
+
+    // ==================== CONNECTIVITY ====================
+
+    #[test]
+    fn test_connected_detects_wall() {
+        let mut map = GridMap::new(10, 10);
+        assert!(connected(&map, (0, 0), (9, 9)));
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        assert!(!connected(&map, (0, 0), (9, 9)));
+        assert!(!connected(&map, (5, 0), (5, 0)));
+    }
+
+    #[test]
+    fn test_connected_respects_corner_cutting() {
+        let mut map = GridMap::new(2, 2);
+        map.set_obstacle(1, 0, true);
+        map.set_obstacle(0, 1, true);
+        assert!(!connected(&map, (0, 0), (1, 1)));
+    }
+
+    // ==================== MAZES ====================
+
+    #[test]
+    fn test_maze_is_seeded() {
+        let a = perfect_maze(21, 15, 7);
+        let b = perfect_maze(21, 15, 7);
+        let c = perfect_maze(21, 15, 8);
+        assert_eq!(a.map.costs, b.map.costs);
+        assert_ne!(a.map.costs, c.map.costs);
+    }
+
+    #[test]
+    fn test_perfect_maze_is_a_spanning_tree() {
+        for seed in 0..5 {
+            let maze = perfect_maze(31, 21, seed);
+            assert!(maze.connected);
+            assert_eq!((maze.start, maze.goal), ((1, 1), (29, 19)));
+            // All 15 x 10 maze cells carved, with no loops
+            let free = free_cells(&maze.map);
+            assert_eq!(free, 15 * 10 + 15 * 10 - 1);
+            assert_eq!(open_edges(&maze.map), free - 1);
+        }
+    }
+
+    #[test]
+    fn test_maze_even_size() {
+        let maze = perfect_maze(20, 12, 3);
+        assert!(maze.connected);
+        assert_eq!(maze.goal, (17, 9));
+    }
+
+    #[test]
+    fn test_braided_maze_has_loops() {
+        let perfect = perfect_maze(31, 31, 4);
+        let braided = braided_maze(31, 31, 1.0, 4);
+        assert!(braided.connected);
+        assert!(dead_ends(&braided.map).len() < dead_ends(&perfect.map).len() / 4);
+        assert!(open_edges(&braided.map) > free_cells(&braided.map));
+    }
+
+    #[test]
+    fn test_zero_braid_is_perfect() {
+        let perfect = perfect_maze(25, 25, 9);
+        let braided = braided_maze(25, 25, 0.0, 9);
+        assert_eq!(perfect.map.costs, braided.map.costs);
+    }
+
+    // ==================== CAVES ====================
+
+    #[test]
+    fn test_caves_are_seeded_and_walled() {
+        let a = caves(40, 30, 0.45, 4, 11);
+        let b = caves(40, 30, 0.45, 4, 11);
+        assert_eq!(a.map.costs, b.map.costs);
+        for x in 0..40 {
+            assert!(a.map.is_obstacle(x, 0) && a.map.is_obstacle(x, 29));
+        }
+        assert!(!a.map.is_obstacle(a.start.0, a.start.1));
+        assert!(!a.map.is_obstacle(a.goal.0, a.goal.1));
+        assert_eq!(a.connected, connected(&a.map, a.start, a.goal));
+    }
+
+    #[test]
+    fn test_caves_smoothing_opens_space() {
+        let density = obstacle_density(&caves(40, 40, 0.45, 5, 2).map);
+        assert!(density > 0.2 && density < 0.7, "{}", density);
+    }
+
+    // ==================== OBSTACLE FIELDS ====================
+
+    #[test]
+    fn test_rect_field_density() {
+        for &density in &[0.1, 0.25, 0.4] {
+            let field = random_rects(50, 50, density, 6, 5);
+            let actual = obstacle_density(&field.map);
+            assert!((actual - density).abs() < 0.01, "{} vs {}", actual, density);
+            assert!(!field.map.is_obstacle(1, 1));
+            assert!(!field.map.is_obstacle(48, 48));
+            assert_eq!(field.connected, connected(&field.map, (1, 1), (48, 48)));
+        }
+    }
+
+    #[test]
+    fn test_circle_field_density() {
+        let field = random_circles(50, 50, 0.2, 4.0, 6);
+        let actual = obstacle_density(&field.map);
+        assert!((actual - 0.2).abs() < 0.01, "{}", actual);
+        assert!(field.connected);
+        assert_ne!(
+            field.map.costs,
+            random_circles(50, 50, 0.2, 4.0, 7).map.costs
+        );
+    }
+
+    #[test]
+    fn test_dense_field_reports_disconnection() {
+        let field = random_rects(30, 30, 0.9, 5, 1);
+        assert!(!field.connected);
+    }
+
+    #[test]
+    fn test_empty_fields() {
+        for field in [
+            random_rects(0, 5, 0.2, 3, 1),
+            random_rects(5, 0, 0.2, 3, 1),
+            random_circles(0, 5, 0.2, 2.0, 1),
+            random_circles(5, 0, 0.2, 2.0, 1),
+        ] {
+            assert!(field.map.costs.is_empty());
+            assert!(!field.connected);
+        }
+    }
+
+    // ==================== ROOMS ====================
+
+    #[test]
+    fn test_rooms_are_connected() {
+        for seed in 0..5 {
+            let dungeon = rooms_and_corridors(60, 40, 8, 8, seed);
+            assert!(dungeon.connected);
+            assert_ne!(dungeon.start, dungeon.goal);
+            let free = free_cells(&dungeon.map);
+            assert!(free > 8 * 9, "{}", free);
+        }
+    }
+}
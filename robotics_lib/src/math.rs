@@ -1,4 +1,4 @@
-use core::ops::{Add, Div, Mul, Sub};
+use core::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vec2 {
@@ -47,6 +47,19 @@ impl Vec2 {
         let dy = self.y - other.y;
         dx * dx + dy * dy
     }
+
+    pub fn distance(&self, other: &Vec2) -> f32 {
+        libm::sqrtf(self.distance_sq(other))
+    }
+
+    pub fn dot(&self, other: &Vec2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    // z component of the 3D cross product
+    pub fn cross(&self, other: &Vec2) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
 }
 
 impl Add for Vec2 {
@@ -93,6 +106,330 @@ impl Div<f32> for Vec2 {
     }
 }
 
+/// Wrap an angle into (-PI, PI].
+pub fn wrap_angle(angle: f32) -> f32 {
+    let pi = core::f32::consts::PI;
+    let mut a = libm::fmodf(angle + pi, 2.0 * pi);
+    if a <= 0.0 {
+        a += 2.0 * pi;
+    }
+    a - pi
+}
+
+/// Planar pose: position plus heading in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub x: f32,
+    pub y: f32,
+    pub theta: f32,
+}
+
+impl Pose {
+    pub fn new(x: f32, y: f32, theta: f32) -> Self {
+        Self { x, y, theta }
+    }
+
+    pub fn position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn heading(&self) -> Vec2 {
+        Vec2::new(libm::cosf(self.theta), libm::sinf(self.theta))
+    }
+}
+
+// Pivots below this fraction of the largest entry count as singular
+const SINGULAR_EPS: f32 = f32::EPSILON;
+
+/// Dense `R` x `C` matrix, row-major and stored inline, so the filters
+/// built on it need no allocation. Shapes are checked at compile time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix<const R: usize, const C: usize> {
+    pub data: [[f32; C]; R],
+}
+
+/// Column vector.
+pub type Vector<const N: usize> = Matrix<N, 1>;
+
+impl<const R: usize, const C: usize> Default for Matrix<R, C> {
+    fn default() -> Self {
+        Self::zeros()
+    }
+}
+
+impl<const R: usize, const C: usize> Matrix<R, C> {
+    pub fn new(data: [[f32; C]; R]) -> Self {
+        Self { data }
+    }
+
+    pub fn zeros() -> Self {
+        Self {
+            data: [[0.0; C]; R],
+        }
+    }
+
+    /// Matrix with entry (i, j) set to `f(i, j)`.
+    pub fn from_fn(f: impl Fn(usize, usize) -> f32) -> Self {
+        let mut out = Self::zeros();
+        for (i, row) in out.data.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = f(i, j);
+            }
+        }
+        out
+    }
+
+    pub fn transpose(&self) -> Matrix<C, R> {
+        Matrix::from_fn(|i, j| self.data[j][i])
+    }
+
+    pub fn column(&self, j: usize) -> Vector<R> {
+        Matrix::from_fn(|i, _| self.data[i][j])
+    }
+
+    pub fn set_column(&mut self, j: usize, v: &Vector<R>) {
+        for (i, row) in self.data.iter_mut().enumerate() {
+            row[j] = v.data[i][0];
+        }
+    }
+
+    /// Largest absolute entry.
+    pub fn max_abs(&self) -> f32 {
+        self.data
+            .iter()
+            .flatten()
+            .fold(0.0, |m: f32, v| m.max(v.abs()))
+    }
+}
+
+impl<const N: usize> Matrix<N, N> {
+    pub fn identity() -> Self {
+        Self::from_fn(|i, j| if i == j { 1.0 } else { 0.0 })
+    }
+
+    pub fn diagonal(d: [f32; N]) -> Self {
+        Self::from_fn(|i, j| if i == j { d[i] } else { 0.0 })
+    }
+
+    pub fn trace(&self) -> f32 {
+        (0..N).map(|i| self.data[i][i]).sum()
+    }
+
+    /// (A + A') / 2, to undo rounding drift in a covariance.
+    pub fn symmetrize(&self) -> Self {
+        Self::from_fn(|i, j| 0.5 * (self.data[i][j] + self.data[j][i]))
+    }
+
+    /// Lower-triangular L with A = L L'. Only the lower triangle of A is
+    /// read. None unless A is positive definite.
+    pub fn cholesky(&self) -> Option<Self> {
+        let mut l = Self::zeros();
+        for j in 0..N {
+            let d = self.data[j][j] - (0..j).map(|k| l.data[j][k] * l.data[j][k]).sum::<f32>();
+            if d.is_nan() || d <= 0.0 {
+                return None;
+            }
+            let d = libm::sqrtf(d);
+            l.data[j][j] = d;
+            for i in j + 1..N {
+                let s = self.data[i][j] - (0..j).map(|k| l.data[i][k] * l.data[j][k]).sum::<f32>();
+                l.data[i][j] = s / d;
+            }
+        }
+        Some(l)
+    }
+
+    /// Solve A X = B for a symmetric positive definite A by Cholesky
+    /// factorisation. None if A is not positive definite.
+    pub fn cholesky_solve<const K: usize>(&self, b: &Matrix<N, K>) -> Option<Matrix<N, K>> {
+        let l = self.cholesky()?;
+        let mut x = *b;
+        for c in 0..K {
+            // L y = b, then L' x = y
+            for i in 0..N {
+                let s: f32 = (0..i).map(|k| l.data[i][k] * x.data[k][c]).sum();
+                x.data[i][c] = (x.data[i][c] - s) / l.data[i][i];
+            }
+            for i in (0..N).rev() {
+                let s: f32 = (i + 1..N).map(|k| l.data[k][i] * x.data[k][c]).sum();
+                x.data[i][c] = (x.data[i][c] - s) / l.data[i][i];
+            }
+        }
+        Some(x)
+    }
+
+    /// LU factorisation with partial pivoting. None if A is singular.
+    pub fn lu(&self) -> Option<Lu<N>> {
+        let tolerance = self.max_abs() * SINGULAR_EPS;
+        let mut lu = *self;
+        let mut perm = [0; N];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = i;
+        }
+        let mut sign = 1.0;
+        for col in 0..N {
+            let pivot = (col..N)
+                .max_by(|&a, &b| lu.data[a][col].abs().total_cmp(&lu.data[b][col].abs()))?;
+            let p = lu.data[pivot][col].abs();
+            if p.is_nan() || p <= tolerance {
+                return None;
+            }
+            if pivot != col {
+                lu.data.swap(pivot, col);
+                perm.swap(pivot, col);
+                sign = -sign;
+            }
+            for row in col + 1..N {
+                let factor = lu.data[row][col] / lu.data[col][col];
+                lu.data[row][col] = factor;
+                for j in col + 1..N {
+                    lu.data[row][j] -= factor * lu.data[col][j];
+                }
+            }
+        }
+        Some(Lu { lu, perm, sign })
+    }
+
+    /// Solve A X = B by LU factorisation. None if A is singular.
+    pub fn solve<const K: usize>(&self, b: &Matrix<N, K>) -> Option<Matrix<N, K>> {
+        Some(self.lu()?.solve(b))
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        self.solve(&Self::identity())
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.lu().map_or(0.0, |lu| lu.determinant())
+    }
+}
+
+impl<const N: usize> Matrix<N, 1> {
+    pub fn from_array(v: [f32; N]) -> Self {
+        Self::from_fn(|i, _| v[i])
+    }
+
+    pub fn to_array(&self) -> [f32; N] {
+        core::array::from_fn(|i| self.data[i][0])
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        (0..N).map(|i| self.data[i][0] * other.data[i][0]).sum()
+    }
+}
+
+/// LU factorisation P A = L U from [`Matrix::lu`], reusable for several
+/// right-hand sides.
+#[derive(Clone, Copy, Debug)]
+pub struct Lu<const N: usize> {
+    // L below the diagonal (unit diagonal implied), U on and above it
+    lu: Matrix<N, N>,
+    // Original row of each factorised row
+    perm: [usize; N],
+    sign: f32,
+}
+
+impl<const N: usize> Lu<N> {
+    /// Solve A X = B.
+    pub fn solve<const K: usize>(&self, b: &Matrix<N, K>) -> Matrix<N, K> {
+        let mut x = Matrix::from_fn(|i, j| b.data[self.perm[i]][j]);
+        for c in 0..K {
+            for i in 0..N {
+                let s: f32 = (0..i).map(|k| self.lu.data[i][k] * x.data[k][c]).sum();
+                x.data[i][c] -= s;
+            }
+            for i in (0..N).rev() {
+                let s: f32 = (i + 1..N).map(|k| self.lu.data[i][k] * x.data[k][c]).sum();
+                x.data[i][c] = (x.data[i][c] - s) / self.lu.data[i][i];
+            }
+        }
+        x
+    }
+
+    pub fn determinant(&self) -> f32 {
+        (0..N).fold(self.sign, |d, i| d * self.lu.data[i][i])
+    }
+}
+
+impl<const N: usize> From<[f32; N]> for Vector<N> {
+    fn from(v: [f32; N]) -> Self {
+        Self::from_array(v)
+    }
+}
+
+impl From<Vec2> for Vector<2> {
+    fn from(v: Vec2) -> Self {
+        Self::from_array([v.x, v.y])
+    }
+}
+
+impl From<Vector<2>> for Vec2 {
+    fn from(v: Vector<2>) -> Self {
+        Vec2::new(v.data[0][0], v.data[1][0])
+    }
+}
+
+impl<const R: usize, const C: usize> Index<(usize, usize)> for Matrix<R, C> {
+    type Output = f32;
+    fn index(&self, (i, j): (usize, usize)) -> &f32 {
+        &self.data[i][j]
+    }
+}
+
+impl<const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<R, C> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f32 {
+        &mut self.data[i][j]
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = f32;
+    fn index(&self, i: usize) -> &f32 {
+        &self.data[i][0]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Vector<N> {
+    fn index_mut(&mut self, i: usize) -> &mut f32 {
+        &mut self.data[i][0]
+    }
+}
+
+impl<const R: usize, const C: usize> Add for Matrix<R, C> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::from_fn(|i, j| self.data[i][j] + other.data[i][j])
+    }
+}
+
+impl<const R: usize, const C: usize> Sub for Matrix<R, C> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::from_fn(|i, j| self.data[i][j] - other.data[i][j])
+    }
+}
+
+impl<const R: usize, const C: usize> Neg for Matrix<R, C> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self * -1.0
+    }
+}
+
+impl<const R: usize, const C: usize> Mul<f32> for Matrix<R, C> {
+    type Output = Self;
+    fn mul(self, scalar: f32) -> Self {
+        Self::from_fn(|i, j| self.data[i][j] * scalar)
+    }
+}
+
+impl<const R: usize, const C: usize, const K: usize> Mul<Matrix<C, K>> for Matrix<R, C> {
+    type Output = Matrix<R, K>;
+    fn mul(self, other: Matrix<C, K>) -> Matrix<R, K> {
+        Matrix::from_fn(|i, j| (0..C).map(|k| self.data[i][k] * other.data[k][j]).sum())
+    }
+}
+
 #[cfg(test)]
 #[path = "math_tests.rs"]
 mod tests;
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use crate::math::Vec2;
+    use crate::math::{wrap_angle, Matrix, Pose, Vec2, Vector};
+    use core::f32::consts::PI;
 
     // ==================== CONSTRUCTION ====================
 
@@ -201,6 +202,32 @@ mod tests {
         assert_eq!(a.distance_sq(&b), b.distance_sq(&a));
     }
 
+    #[test]
+    fn test_vec2_distance() {
+        let a = Vec2::new(1.0, 1.0);
+        let b = Vec2::new(4.0, 5.0);
+        assert!((a.distance(&b) - 5.0).abs() < 1e-6);
+    }
+
+    // ==================== PRODUCTS ====================
+
+    #[test]
+    fn test_vec2_dot() {
+        let a = Vec2::new(1.0, 2.0);
+        let b = Vec2::new(3.0, -4.0);
+        assert_eq!(a.dot(&b), -5.0);
+        assert_eq!(Vec2::new(1.0, 0.0).dot(&Vec2::new(0.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn test_vec2_cross() {
+        let x = Vec2::new(1.0, 0.0);
+        let y = Vec2::new(0.0, 1.0);
+        assert_eq!(x.cross(&y), 1.0);
+        assert_eq!(y.cross(&x), -1.0);
+        assert_eq!(x.cross(&x), 0.0);
+    }
+
     // ==================== EQUALITY ====================
 
     #[test]
@@ -283,4 +310,99 @@ mod tests {
         assert!((scaled.x - v.x).abs() < 1e-5);
         assert!((scaled.y - v.y).abs() < 1e-5);
     }
+
+    // ==================== ANGLES & POSES ====================
+
+    #[test]
+    fn test_wrap_angle() {
+        assert!((wrap_angle(0.5) - 0.5).abs() < 1e-6);
+        assert!((wrap_angle(3.0 * PI) - PI).abs() < 1e-5);
+        assert!((wrap_angle(-PI) - PI).abs() < 1e-5);
+        assert!((wrap_angle(-2.5 * PI) + 0.5 * PI).abs() < 1e-5);
+        assert!((wrap_angle(7.0) - (7.0 - 2.0 * PI)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_pose_position_and_heading() {
+        let p = Pose::new(1.0, 2.0, PI / 2.0);
+        assert_eq!(p.position(), Vec2::new(1.0, 2.0));
+        let h = p.heading();
+        assert!(h.x.abs() < 1e-6);
+        assert!((h.y - 1.0).abs() < 1e-6);
+    }
+
+    // ==================== MATRIX ====================
+
+    fn assert_close<const R: usize, const C: usize>(a: &Matrix<R, C>, b: &Matrix<R, C>) {
+        assert!((*a - *b).max_abs() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    // Symmetric positive definite test matrix
+    fn spd() -> Matrix<3, 3> {
+        Matrix::new([[4.0, 1.0, 0.5], [1.0, 3.0, -0.4], [0.5, -0.4, 2.0]])
+    }
+
+    #[test]
+    fn test_matrix_multiply_and_transpose() {
+        let a = Matrix::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = Matrix::new([[1.0, 0.0], [0.0, 1.0], [2.0, -1.0]]);
+        assert_eq!(a * b, Matrix::new([[7.0, -1.0], [16.0, -1.0]]));
+        assert_eq!(a.transpose().data, [[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]);
+        assert_eq!((a * b).transpose(), b.transpose() * a.transpose());
+        assert_eq!(Matrix::<2, 2>::identity() * a, a);
+        assert_eq!(a * 2.0 - a, a);
+        assert_eq!(a[(1, 2)], 6.0);
+    }
+
+    #[test]
+    fn test_vector_access() {
+        let mut v = Vector::from_array([1.0, 2.0, 3.0]);
+        v[1] = 5.0;
+        assert_eq!(v.to_array(), [1.0, 5.0, 3.0]);
+        assert_eq!(v.dot(&v), 35.0);
+        let p: Vec2 = Vector::from_array([3.0, 4.0]).into();
+        assert_eq!(p, Vec2::new(3.0, 4.0));
+        let mut m = Matrix::<3, 2>::zeros();
+        m.set_column(1, &v);
+        assert_eq!(m.column(1), v);
+    }
+
+    #[test]
+    fn test_cholesky_reconstructs_matrix() {
+        let a = spd();
+        let l = a.cholesky().unwrap();
+        assert_eq!(l[(0, 1)], 0.0);
+        assert_close(&(l * l.transpose()), &a);
+        // Indefinite and singular matrices have no factor
+        assert!(Matrix::new([[1.0, 2.0], [2.0, 1.0]]).cholesky().is_none());
+        assert!(Matrix::<2, 2>::zeros().cholesky().is_none());
+    }
+
+    #[test]
+    fn test_solvers_agree() {
+        let a = spd();
+        let b = Matrix::new([[1.0, 0.0], [2.0, 1.0], [-1.0, 3.0]]);
+        let x = a.solve(&b).unwrap();
+        assert_close(&(a * x), &b);
+        assert_close(&a.cholesky_solve(&b).unwrap(), &x);
+        // LU also handles matrices that need pivoting
+        let p = Matrix::new([[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 1.0]]);
+        assert_close(&(p * p.solve(&b).unwrap()), &b);
+    }
+
+    #[test]
+    fn test_inverse_and_determinant() {
+        let a = Matrix::new([[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 1.0]]);
+        let inv = a.inverse().unwrap();
+        assert_close(&(a * inv), &Matrix::identity());
+        assert!((a.determinant() + 5.0).abs() < 1e-5);
+        // det(A) = det(L)^2
+        let l = spd().cholesky().unwrap();
+        let det_l = l[(0, 0)] * l[(1, 1)] * l[(2, 2)];
+        assert!((spd().determinant() - 20.21).abs() < 1e-3);
+        assert!((det_l * det_l - 20.21).abs() < 1e-3);
+        let singular = Matrix::new([[1.0, 2.0], [2.0, 4.0]]);
+        assert!(singular.inverse().is_none());
+        assert_eq!(singular.determinant(), 0.0);
+    }
 }
@@ -0,0 +1,307 @@
+use crate::collision::{CollisionChecker, PolygonWorld};
+use crate::grid_search::MinScored;
+use crate::math::Vec2;
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec::Vec;
+
+// Tolerance for points on triangle edges and vertices on segments
+const EPS: f32 = 1e-4;
+
+/// Triangulated navigation mesh of the free space of a [`PolygonWorld`].
+///
+/// The free space is triangulated greedily: every visible vertex pair is a
+/// candidate edge, and candidates are accepted shortest first unless they
+/// cross an accepted one. Obstacle edges cannot be crossed by a visible
+/// segment, so they always end up in the mesh. Queries run A* over the
+/// triangles and pull a string through the resulting corridor with the
+/// funnel algorithm.
+#[derive(Clone, Debug)]
+pub struct NavMesh {
+    pub vertices: Vec<Vec2>,
+    /// Vertex indices, counter-clockwise.
+    pub triangles: Vec<[usize; 3]>,
+    /// Triangle across edge `k` (from vertex `k` to `k + 1`) of each
+    /// triangle, None on obstacle and world boundaries.
+    pub neighbors: Vec<[Option<usize>; 3]>,
+}
+
+// True if the open segments a-b and c-d cross at a single interior point
+fn crosses(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let d1 = (b - a).cross(&(c - a));
+    let d2 = (b - a).cross(&(d - a));
+    let d3 = (d - c).cross(&(a - c));
+    let d4 = (d - c).cross(&(b - c));
+    let tol = EPS * (b - a).mag() * (d - c).mag();
+    ((d1 > tol && d2 < -tol) || (d1 < -tol && d2 > tol))
+        && ((d3 > tol && d4 < -tol) || (d3 < -tol && d4 > tol))
+}
+
+// True if p lies on the segment a-b strictly between its ends
+fn on_segment(a: Vec2, b: Vec2, p: Vec2) -> bool {
+    let ab = b - a;
+    let len_sq = ab.mag_sq();
+    let t = (p - a).dot(&ab) / len_sq;
+    t > EPS && t < 1.0 - EPS && (a + ab * t).distance(&p) < EPS
+}
+
+impl NavMesh {
+    pub fn new(world: &PolygonWorld) -> Self {
+        let mut vertices: Vec<Vec2> = Vec::new();
+        for v in world.vertices() {
+            if world.is_free(v) && !vertices.iter().any(|u| u.distance(&v) < EPS) {
+                vertices.push(v);
+            }
+        }
+        let n = vertices.len();
+
+        let mut candidates = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                let (a, b) = (vertices[i], vertices[j]);
+                if world.segment_free(a, b)
+                    && !(0..n).any(|k| k != i && k != j && on_segment(a, b, vertices[k]))
+                {
+                    candidates.push((a.distance(&b), i, j));
+                }
+            }
+        }
+        candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut adjacent = alloc::vec![alloc::vec![false; n]; n];
+        for (_, i, j) in candidates {
+            let (a, b) = (vertices[i], vertices[j]);
+            if !edges
+                .iter()
+                .any(|&(p, q)| crosses(a, b, vertices[p], vertices[q]))
+            {
+                edges.push((i, j));
+                adjacent[i][j] = true;
+                adjacent[j][i] = true;
+            }
+        }
+
+        // Faces are the empty edge triangles that are not obstacle interiors
+        let mut triangles = Vec::new();
+        for &(i, j) in &edges {
+            for k in j + 1..n {
+                if !adjacent[i][k] || !adjacent[j][k] {
+                    continue;
+                }
+                let (a, b, c) = (vertices[i], vertices[j], vertices[k]);
+                let area = (b - a).cross(&(c - a));
+                if area.abs() < EPS {
+                    continue;
+                }
+                let tri = if area > 0.0 { [i, j, k] } else { [i, k, j] };
+                let centroid = (a + b + c) / 3.0;
+                let empty = (0..n)
+                    .all(|m| tri.contains(&m) || !strictly_inside(&vertices, tri, vertices[m]));
+                if empty && world.is_free(centroid) {
+                    triangles.push(tri);
+                }
+            }
+        }
+
+        let mut edge_owner: BTreeMap<(usize, usize), Vec<(usize, usize)>> = BTreeMap::new();
+        for (t, tri) in triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                edge_owner
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((t, k));
+            }
+        }
+        let mut neighbors = alloc::vec![[None; 3]; triangles.len()];
+        for owners in edge_owner.values() {
+            if let [(t, k), (u, l)] = owners[..] {
+                neighbors[t][k] = Some(u);
+                neighbors[u][l] = Some(t);
+            }
+        }
+        Self {
+            vertices,
+            triangles,
+            neighbors,
+        }
+    }
+
+    fn corners(&self, t: usize) -> [Vec2; 3] {
+        self.triangles[t].map(|i| self.vertices[i])
+    }
+
+    /// Area covered by the mesh.
+    pub fn area(&self) -> f32 {
+        (0..self.triangles.len())
+            .map(|t| {
+                let [a, b, c] = self.corners(t);
+                (b - a).cross(&(c - a)) * 0.5
+            })
+            .sum()
+    }
+
+    /// Triangle containing `p`, boundary included.
+    pub fn locate(&self, p: Vec2) -> Option<usize> {
+        (0..self.triangles.len()).find(|&t| {
+            let [a, b, c] = self.corners(t);
+            [(a, b), (b, c), (c, a)]
+                .iter()
+                .all(|&(u, v)| (v - u).cross(&(p - u)) >= -EPS * (v - u).mag())
+        })
+    }
+
+    /// Triangles from the one containing `start` to the one containing
+    /// `goal`. A* runs over the triangles; each is entered at the point of
+    /// the shared edge that is closest to the straight line from the
+    /// previous entry to the goal, which keeps the cost close to the length
+    /// of the string-pulled path.
+    pub fn corridor(&self, start: Vec2, goal: Vec2) -> Option<Vec<usize>> {
+        let first = self.locate(start)?;
+        let last = self.locate(goal)?;
+        let count = self.triangles.len();
+        let mut dist = alloc::vec![f32::INFINITY; count];
+        let mut entry = alloc::vec![start; count];
+        let mut parent = alloc::vec![usize::MAX; count];
+        let mut open = BinaryHeap::new();
+        dist[first] = 0.0;
+        open.push(MinScored::new(start.distance(&goal), first));
+        while let Some(MinScored { item: t, .. }) = open.pop() {
+            if t == last {
+                let mut out = alloc::vec![t];
+                let mut c = t;
+                while parent[c] != usize::MAX {
+                    c = parent[c];
+                    out.push(c);
+                }
+                out.reverse();
+                return Some(out);
+            }
+            for k in 0..3 {
+                let Some(next) = self.neighbors[t][k] else {
+                    continue;
+                };
+                let (a, b) = self.edge(t, k);
+                let p = crossing_point(a, b, entry[t], goal);
+                let d = dist[t] + entry[t].distance(&p);
+                if d < dist[next] {
+                    dist[next] = d;
+                    entry[next] = p;
+                    parent[next] = t;
+                    open.push(MinScored::new(d + p.distance(&goal), next));
+                }
+            }
+        }
+        None
+    }
+
+    fn edge(&self, t: usize, k: usize) -> (Vec2, Vec2) {
+        let tri = self.triangles[t];
+        (self.vertices[tri[k]], self.vertices[tri[(k + 1) % 3]])
+    }
+
+    /// Edges crossed along a corridor as (left, right) pairs seen in the
+    /// direction of travel, framed by degenerate portals at both ends.
+    pub fn portals(&self, corridor: &[usize], start: Vec2, goal: Vec2) -> Vec<(Vec2, Vec2)> {
+        let mut out = alloc::vec![(start, start)];
+        for w in corridor.windows(2) {
+            let k = (0..3)
+                .find(|&k| self.neighbors[w[0]][k] == Some(w[1]))
+                .unwrap();
+            // Leaving a counter-clockwise triangle, the edge's end is on the left
+            let (a, b) = self.edge(w[0], k);
+            out.push((b, a));
+        }
+        out.push((goal, goal));
+        out
+    }
+
+    /// Shortest path through the triangle corridor from `start` to `goal`,
+    /// or None if either lies outside the mesh or they are not connected.
+    pub fn plan(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let corridor = self.corridor(start, goal)?;
+        Some(funnel(&self.portals(&corridor, start, goal)))
+    }
+}
+
+// Point of the edge a-b where the segment from `from` to `to` crosses it, or
+// the end of the edge that makes the shorter detour
+fn crossing_point(a: Vec2, b: Vec2, from: Vec2, to: Vec2) -> Vec2 {
+    let (e, d) = (b - a, to - from);
+    let denom = d.cross(&e);
+    if denom.abs() > EPS {
+        let t = (a - from).cross(&e) / denom;
+        let u = (a - from).cross(&d) / denom;
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            return a + e * u;
+        }
+    }
+    if from.distance(&a) + a.distance(&to) <= from.distance(&b) + b.distance(&to) {
+        a
+    } else {
+        b
+    }
+}
+
+fn strictly_inside(vertices: &[Vec2], tri: [usize; 3], p: Vec2) -> bool {
+    let [a, b, c] = tri.map(|i| vertices[i]);
+    [(a, b), (b, c), (c, a)]
+        .iter()
+        .all(|&(u, v)| (v - u).cross(&(p - u)) > EPS * (v - u).mag())
+}
+
+/// String pulling through a list of (left, right) portals (the "simple
+/// stupid funnel"). The funnel from the current apex narrows portal by
+/// portal; when one side would cross the other, the crossed side's vertex
+/// becomes a corner of the path and the new apex.
+pub fn funnel(portals: &[(Vec2, Vec2)]) -> Vec<Vec2> {
+    let Some(&(start, _)) = portals.first() else {
+        return Vec::new();
+    };
+    let mut path = alloc::vec![start];
+    let (mut apex, mut left, mut right) = (start, start, start);
+    let (mut left_i, mut right_i) = (0, 0);
+    let mut i = 1;
+    while i < portals.len() {
+        let (pl, pr) = portals[i];
+        // Tighten the right side
+        if (right - apex).cross(&(pr - apex)) >= 0.0 {
+            if apex == right || (left - apex).cross(&(pr - apex)) < 0.0 {
+                right = pr;
+                right_i = i;
+            } else {
+                if path.last() != Some(&left) {
+                    path.push(left);
+                }
+                apex = left;
+                (right, right_i) = (apex, left_i);
+                i = left_i + 1;
+                continue;
+            }
+        }
+        // Tighten the left side
+        if (left - apex).cross(&(pl - apex)) <= 0.0 {
+            if apex == left || (right - apex).cross(&(pl - apex)) > 0.0 {
+                left = pl;
+                left_i = i;
+            } else {
+                if path.last() != Some(&right) {
+                    path.push(right);
+                }
+                apex = right;
+                (left, left_i) = (apex, right_i);
+                i = right_i + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    let end = portals[portals.len() - 1].0;
+    if path.last() != Some(&end) {
+        path.push(end);
+    }
+    path
+}
+
+#[cfg(test)]
+#[path = "navmesh_tests.rs"]
+mod tests;
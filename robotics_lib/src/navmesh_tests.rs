@@ -0,0 +1,214 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::{CollisionChecker, Polygon, PolygonWorld};
+    use crate::math::Vec2;
+    use crate::navmesh::{funnel, NavMesh};
+    use crate::path::polyline_length;
+    use crate::rng::Rng;
+    use crate::visibility::VisibilityGraph;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Polygon {
+        Polygon::rect(Vec2::new(x0, y0), Vec2::new(x1, y1))
+    }
+
+    fn scenes() -> Vec<PolygonWorld> {
+        let bounds = (Vec2::new(0.0, 0.0), Vec2::new(20.0, 20.0));
+        let cluttered = vec![
+            rect(3.0, 3.0, 6.0, 5.0),
+            rect(9.0, 1.0, 11.0, 9.0),
+            rect(14.0, 4.0, 17.0, 7.0),
+            rect(4.0, 10.0, 8.0, 16.0),
+            rect(12.0, 12.0, 18.0, 14.0),
+            Polygon::new(vec![
+                Vec2::new(10.0, 15.0),
+                Vec2::new(12.0, 17.0),
+                Vec2::new(9.0, 19.0),
+            ]),
+        ];
+        let cup = vec![Polygon::new(vec![
+            Vec2::new(5.0, 5.0),
+            Vec2::new(15.0, 5.0),
+            Vec2::new(15.0, 6.0),
+            Vec2::new(6.0, 6.0),
+            Vec2::new(6.0, 14.0),
+            Vec2::new(15.0, 14.0),
+            Vec2::new(15.0, 15.0),
+            Vec2::new(5.0, 15.0),
+        ])];
+        // Walls poking out of the bounds with gaps at alternate ends
+        let slalom = vec![
+            rect(5.0, -1.0, 6.0, 15.0),
+            rect(10.0, 5.0, 11.0, 21.0),
+            rect(15.0, -1.0, 16.0, 15.0),
+        ];
+        [cluttered, cup, slalom]
+            .into_iter()
+            .map(|obstacles| PolygonWorld::with_obstacles(bounds.0, bounds.1, obstacles))
+            .collect()
+    }
+
+    fn free_point(world: &PolygonWorld, rng: &mut Rng) -> Vec2 {
+        loop {
+            let p = Vec2::new(rng.range(0.0, 20.0), rng.range(0.0, 20.0));
+            if world.is_free(p) {
+                return p;
+            }
+        }
+    }
+
+    // ==================== MESH ====================
+
+    #[test]
+    fn test_open_world_is_two_triangles() {
+        let world = PolygonWorld::new(Vec2::new(0.0, 0.0), Vec2::new(4.0, 3.0));
+        let mesh = NavMesh::new(&world);
+        assert_eq!(mesh.triangles.len(), 2);
+        assert!((mesh.area() - 12.0).abs() < 1e-4);
+        assert_eq!(mesh.neighbors[0].iter().flatten().count(), 1);
+    }
+
+    #[test]
+    fn test_mesh_covers_free_space() {
+        for world in scenes() {
+            let mesh = NavMesh::new(&world);
+            let mut free = 400.0;
+            for poly in &world.obstacles {
+                // Clip to the bounds; all test obstacles are convex or rects
+                let clipped: Vec<Vec2> = poly
+                    .vertices
+                    .iter()
+                    .map(|v| Vec2::new(v.x.clamp(0.0, 20.0), v.y.clamp(0.0, 20.0)))
+                    .collect();
+                let area: f32 = Polygon::new(clipped)
+                    .edges()
+                    .map(|(a, b)| a.cross(&b))
+                    .sum();
+                free -= area.abs() * 0.5;
+            }
+            assert!(
+                (mesh.area() - free).abs() < 1e-2,
+                "{} vs {}",
+                mesh.area(),
+                free
+            );
+            for (t, tri) in mesh.triangles.iter().enumerate() {
+                let [a, b, c] = tri.map(|i| mesh.vertices[i]);
+                assert!((b - a).cross(&(c - a)) > 0.0);
+                assert!(world.is_free((a + b + c) / 3.0));
+                for k in 0..3 {
+                    if let Some(u) = mesh.neighbors[t][k] {
+                        assert!(mesh.neighbors[u].contains(&Some(t)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_locate() {
+        let world = &scenes()[0];
+        let mesh = NavMesh::new(world);
+        assert!(mesh.locate(Vec2::new(1.0, 1.0)).is_some());
+        assert!(mesh.locate(Vec2::new(10.0, 5.0)).is_none());
+        assert!(mesh.locate(Vec2::new(-1.0, 5.0)).is_none());
+    }
+
+    // ==================== FUNNEL ====================
+
+    #[test]
+    fn test_funnel_straight_through() {
+        let portals = vec![
+            (Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0)),
+            (Vec2::new(1.0, 1.0), Vec2::new(1.0, -1.0)),
+            (Vec2::new(2.0, 1.0), Vec2::new(2.0, -1.0)),
+            (Vec2::new(3.0, 0.0), Vec2::new(3.0, 0.0)),
+        ];
+        assert_eq!(
+            funnel(&portals),
+            vec![Vec2::new(0.0, 0.0), Vec2::new(3.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn test_funnel_bends_at_corner() {
+        // The corridor turns left; the path hugs the inner corner
+        let portals = vec![
+            (Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0)),
+            (Vec2::new(2.0, 1.0), Vec2::new(2.0, -1.0)),
+            (Vec2::new(2.0, 1.0), Vec2::new(4.0, 1.0)),
+            (Vec2::new(2.0, 4.0), Vec2::new(4.0, 4.0)),
+            (Vec2::new(3.0, 6.0), Vec2::new(3.0, 6.0)),
+        ];
+        assert_eq!(
+            funnel(&portals),
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 1.0),
+                Vec2::new(3.0, 6.0)
+            ]
+        );
+    }
+
+    // ==================== NAVMESH VS VISIBILITY GRAPH ====================
+
+    #[test]
+    fn test_detour_matches_visibility_graph() {
+        let world = PolygonWorld::with_obstacles(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            vec![rect(4.0, 4.0, 6.0, 6.0)],
+        );
+        let (start, goal) = (Vec2::new(2.0, 5.0), Vec2::new(8.0, 5.0));
+        let exact = VisibilityGraph::new(world.clone())
+            .plan(start, goal)
+            .unwrap();
+        let mesh = NavMesh::new(&world).plan(start, goal).unwrap();
+        assert!((polyline_length(&mesh) - polyline_length(&exact)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_navmesh_paths_are_free_and_near_optimal() {
+        let mut rng = Rng::new(11);
+        for (s, world) in scenes().into_iter().enumerate() {
+            let graph = VisibilityGraph::new(world.clone());
+            let mesh = NavMesh::new(&world);
+            let mut total = (0.0, 0.0);
+            for _ in 0..30 {
+                let start = free_point(&world, &mut rng);
+                let goal = free_point(&world, &mut rng);
+                let exact = graph.plan(start, goal).unwrap();
+                let path = mesh.plan(start, goal).unwrap();
+                assert_eq!(path[0], start);
+                assert_eq!(*path.last().unwrap(), goal);
+                for w in path.windows(2) {
+                    assert!(world.segment_free(w[0], w[1]), "scene {}", s);
+                }
+                let (a, b) = (polyline_length(&exact), polyline_length(&path));
+                // The visibility graph is exact, the mesh corridor may not be
+                assert!(b >= a - 1e-3, "scene {}: {} < {}", s, b, a);
+                assert!(b <= a * 1.25 + 1e-3, "scene {}: {} vs {}", s, b, a);
+                total = (total.0 + a, total.1 + b);
+            }
+            assert!(total.1 <= total.0 * 1.05, "scene {}: {:?}", s, total);
+        }
+    }
+
+    #[test]
+    fn test_both_agree_on_unreachable() {
+        let world = PolygonWorld::with_obstacles(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            vec![rect(4.0, -1.0, 6.0, 11.0)],
+        );
+        let (start, goal) = (Vec2::new(1.0, 5.0), Vec2::new(9.0, 5.0));
+        assert!(VisibilityGraph::new(world.clone())
+            .plan(start, goal)
+            .is_none());
+        assert!(NavMesh::new(&world).plan(start, goal).is_none());
+        assert!(NavMesh::new(&world)
+            .plan(start, Vec2::new(2.0, 9.0))
+            .is_some());
+    }
+}
@@ -0,0 +1,551 @@
+use crate::dstar::GridMap;
+use crate::inflation::{InflatedMap, InflationConfig};
+use crate::math::{wrap_angle, Pose, Vec2};
+use crate::rng::Rng;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+// Below this odometry translation the direction of travel is noise
+const MIN_TRANSLATION: f32 = 0.01;
+
+/// Cast a ray through the grid from `origin` (grid coordinates) and return
+/// the distance to the first obstacle cell or the map edge, capped at
+/// `max_range`. Cells are walked exactly (Amanatides-Woo), so thin walls
+/// are never skipped.
+pub fn raycast(map: &GridMap, origin: Vec2, angle: f32, max_range: f32) -> f32 {
+    let (dx, dy) = (libm::cosf(angle), libm::sinf(angle));
+    let (mut cx, mut cy) = (
+        libm::floorf(origin.x) as isize,
+        libm::floorf(origin.y) as isize,
+    );
+    // Ray parameter at the next vertical and horizontal cell boundary
+    let axis = |p: f32, c: isize, d: f32| -> (isize, f32, f32) {
+        if d > 0.0 {
+            (1, (c as f32 + 1.0 - p) / d, 1.0 / d)
+        } else if d < 0.0 {
+            (-1, (c as f32 - p) / d, -1.0 / d)
+        } else {
+            (0, f32::INFINITY, f32::INFINITY)
+        }
+    };
+    let (step_x, mut next_x, delta_x) = axis(origin.x, cx, dx);
+    let (step_y, mut next_y, delta_y) = axis(origin.y, cy, dy);
+    let mut t: f32 = 0.0;
+    loop {
+        if !map.in_bounds(cx, cy) || map.is_obstacle(cx as usize, cy as usize) {
+            return t.min(max_range);
+        }
+        if t >= max_range {
+            return max_range;
+        }
+        if next_x < next_y {
+            cx += step_x;
+            t = next_x;
+            next_x += delta_x;
+        } else {
+            cy += step_y;
+            t = next_y;
+            next_y += delta_y;
+        }
+    }
+}
+
+/// Planar range scanner with beams spread evenly over its field of view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeSensor {
+    pub beams: usize,
+    /// Total field of view in radians; a full circle gives no duplicate beam.
+    pub fov: f32,
+    /// Readings at this range mean nothing was hit.
+    pub max_range: f32,
+}
+
+impl RangeSensor {
+    pub fn new(beams: usize, fov: f32, max_range: f32) -> Self {
+        Self {
+            beams,
+            fov,
+            max_range,
+        }
+    }
+
+    /// Direction of beam `i` relative to the robot heading.
+    pub fn beam_angle(&self, i: usize) -> f32 {
+        if self.beams <= 1 {
+            return 0.0;
+        }
+        let full_circle = self.fov >= core::f32::consts::TAU - 1e-3;
+        let gaps = if full_circle {
+            self.beams
+        } else {
+            self.beams - 1
+        };
+        -0.5 * self.fov + self.fov * i as f32 / gaps as f32
+    }
+
+    /// Noise-free scan of `map` from `pose`.
+    pub fn scan(&self, map: &GridMap, pose: Pose) -> Vec<f32> {
+        (0..self.beams)
+            .map(|i| {
+                raycast(
+                    map,
+                    pose.position(),
+                    pose.theta + self.beam_angle(i),
+                    self.max_range,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Odometry motion noise (Thrun et al., `sample_motion_model_odometry`).
+/// Each is a variance per unit of squared motion.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OdometryNoise {
+    /// Rotation noise from rotation.
+    pub rot_rot: f32,
+    /// Rotation noise from translation.
+    pub rot_trans: f32,
+    /// Translation noise from translation.
+    pub trans_trans: f32,
+    /// Translation noise from rotation.
+    pub trans_rot: f32,
+}
+
+impl Default for OdometryNoise {
+    fn default() -> Self {
+        Self {
+            rot_rot: 0.05,
+            rot_trans: 0.01,
+            trans_trans: 0.05,
+            trans_rot: 0.01,
+        }
+    }
+}
+
+/// Likelihood-field measurement model.
+///
+/// Each beam endpoint is scored by its distance to the nearest obstacle: a
+/// Gaussian of width `sigma` mixed with a uniform floor `z_rand` for
+/// unexplained readings. Max-range readings carry no information and are
+/// skipped. Distances are precomputed once with a brushfire, so a scan
+/// costs one lookup per beam instead of a raycast.
+#[derive(Clone, Debug)]
+pub struct LikelihoodField {
+    distance: InflatedMap,
+    pub sigma: f32,
+    pub z_hit: f32,
+    pub z_rand: f32,
+}
+
+impl LikelihoodField {
+    pub fn new(map: &GridMap, sigma: f32) -> Self {
+        let config = InflationConfig {
+            robot_radius: 0.0,
+            inflation_radius: (4.0 * sigma).max(2.0),
+            cost_scaling_factor: 0.0,
+        };
+        Self {
+            distance: InflatedMap::new(map.clone(), config),
+            sigma,
+            z_hit: 0.9,
+            z_rand: 0.1,
+        }
+    }
+
+    pub fn map(&self) -> &GridMap {
+        self.distance.base()
+    }
+
+    /// Distance from `p` to the nearest obstacle or map edge, capped beyond
+    /// a few `sigma`. The edge counts because `raycast` stops there, and
+    /// points past it score how far outside they are.
+    pub fn distance(&self, p: Vec2) -> f32 {
+        let map = self.map();
+        let (w, h) = (map.width as f32, map.height as f32);
+        let cap = self.distance.config.inflation_radius;
+        if p.x < 0.0 || p.y < 0.0 || p.x >= w || p.y >= h {
+            return p
+                .distance(&Vec2::new(p.x.clamp(0.0, w), p.y.clamp(0.0, h)))
+                .min(cap);
+        }
+        let edge = p.x.min(p.y).min(w - p.x).min(h - p.y).min(cap);
+        // The obstacle nearest the cell is taken as the one nearest `p`,
+        // measured to its square rather than its centre
+        match self.distance.nearest_obstacle(p.x as usize, p.y as usize) {
+            Some((ox, oy)) => {
+                let q = Vec2::new(
+                    p.x.clamp(ox as f32, ox as f32 + 1.0),
+                    p.y.clamp(oy as f32, oy as f32 + 1.0),
+                );
+                p.distance(&q).min(edge)
+            }
+            None => edge,
+        }
+    }
+
+    /// Log-likelihood of `ranges` taken by `sensor` from `pose`, minus
+    /// infinity if the pose itself is blocked.
+    pub fn log_likelihood(&self, sensor: &RangeSensor, pose: Pose, ranges: &[f32]) -> f32 {
+        let map = self.map();
+        let (x, y) = (libm::floorf(pose.x) as isize, libm::floorf(pose.y) as isize);
+        if !map.in_bounds(x, y) || map.is_obstacle(x as usize, y as usize) {
+            return f32::NEG_INFINITY;
+        }
+        let norm = -0.5 / (self.sigma * self.sigma);
+        ranges
+            .iter()
+            .enumerate()
+            .filter(|&(_, &r)| r < sensor.max_range)
+            .map(|(i, &r)| {
+                let angle = pose.theta + sensor.beam_angle(i);
+                let end = pose.position() + Vec2::new(libm::cosf(angle), libm::sinf(angle)) * r;
+                let d = self.distance(end);
+                libm::logf(self.z_hit * libm::expf(norm * d * d) + self.z_rand)
+            })
+            .sum()
+    }
+}
+
+/// KLD-sampling settings (Fox 2003): enough particles that the sample-based
+/// posterior is within `epsilon` of the true one with probability given by
+/// the normal quantile `z`, counted over pose bins.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KldConfig {
+    pub epsilon: f32,
+    pub z: f32,
+    /// Bin size in cells.
+    pub bin_size: f32,
+    /// Bin size in radians.
+    pub bin_angle: f32,
+    pub min_particles: usize,
+    pub max_particles: usize,
+}
+
+impl Default for KldConfig {
+    fn default() -> Self {
+        Self {
+            epsilon: 0.05,
+            z: 2.33,
+            bin_size: 1.0,
+            bin_angle: 0.25,
+            min_particles: 100,
+            max_particles: 3000,
+        }
+    }
+}
+
+/// Particles needed for `bins` occupied histogram bins.
+pub fn kld_sample_count(bins: usize, epsilon: f32, z: f32) -> usize {
+    if bins <= 1 {
+        return 1;
+    }
+    let k = (bins - 1) as f32;
+    let a = 2.0 / (9.0 * k);
+    let b = 1.0 - a + libm::sqrtf(a) * z;
+    libm::ceilf(k / (2.0 * epsilon) * b * b * b) as usize
+}
+
+/// Low-variance (systematic) resampling: `count` indices drawn with one
+/// random offset `r` in [0, 1) and evenly spaced steps through the
+/// cumulative weights. Weights need not be normalised.
+pub fn low_variance_resample(weights: &[f32], count: usize, r: f32) -> Vec<usize> {
+    let total: f32 = weights.iter().sum();
+    if weights.is_empty() || total <= 0.0 {
+        return Vec::new();
+    }
+    let step = total / count as f32;
+    let mut out = Vec::with_capacity(count);
+    let mut i = 0;
+    let mut cumulative = weights[0];
+    for m in 0..count {
+        let u = (r + m as f32) * step;
+        while u > cumulative && i + 1 < weights.len() {
+            i += 1;
+            cumulative += weights[i];
+        }
+        out.push(i);
+    }
+    out
+}
+
+/// Weighted pose hypothesis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    pub pose: Pose,
+    pub weight: f32,
+}
+
+/// Monte Carlo localisation on a [`GridMap`], in grid coordinates.
+///
+/// Particles are moved with the odometry motion model, weighted with the
+/// likelihood field and redrawn by low-variance resampling, with the count
+/// adapted by KLD-sampling. To recover from kidnapping (augmented MCL), a
+/// short- and a long-term average of the measurement likelihood are kept;
+/// when the short-term one drops below the long-term one, that fraction of
+/// the resampled particles is replaced with uniform random poses.
+#[derive(Clone, Debug)]
+pub struct ParticleFilter {
+    pub particles: Vec<Particle>,
+    pub sensor: RangeSensor,
+    pub field: LikelihoodField,
+    pub motion_noise: OdometryNoise,
+    pub kld: KldConfig,
+    /// Exponent on the scan likelihood. Neighbouring beams are far from
+    /// independent, so the raw product is overconfident and collapses a
+    /// global prior onto the first plausible pose.
+    pub tempering: f32,
+    /// Smoothing rates of the long- and short-term likelihood averages;
+    /// zero disables random particle injection.
+    pub alpha_slow: f32,
+    pub alpha_fast: f32,
+    w_slow: f32,
+    w_fast: f32,
+    free: Vec<(usize, usize)>,
+    rng: Rng,
+}
+
+impl ParticleFilter {
+    /// Filter with a uniform prior over the free cells of `map`, using
+    /// `kld.max_particles` particles.
+    pub fn new(map: &GridMap, sensor: RangeSensor, seed: u64) -> Self {
+        let mut free = Vec::new();
+        for y in 0..map.height {
+            for x in 0..map.width {
+                if !map.is_obstacle(x, y) {
+                    free.push((x, y));
+                }
+            }
+        }
+        let mut filter = Self {
+            particles: Vec::new(),
+            sensor,
+            field: LikelihoodField::new(map, 0.5),
+            motion_noise: OdometryNoise::default(),
+            kld: KldConfig::default(),
+            tempering: 0.1,
+            alpha_slow: 0.001,
+            alpha_fast: 0.1,
+            w_slow: 0.0,
+            w_fast: 0.0,
+            free,
+            rng: Rng::new(seed),
+        };
+        filter.reset_uniform();
+        filter
+    }
+
+    /// Spread `kld.max_particles` particles uniformly over the free space.
+    pub fn reset_uniform(&mut self) {
+        let count = self.kld.max_particles;
+        let weight = 1.0 / count as f32;
+        self.particles = (0..count)
+            .map(|_| Particle {
+                pose: self.uniform_pose(),
+                weight,
+            })
+            .collect();
+    }
+
+    /// Start tracking from a known pose with Gaussian spread.
+    pub fn reset_at(&mut self, pose: Pose, position_sd: f32, heading_sd: f32) {
+        let count = self.kld.min_particles.max(1);
+        let weight = 1.0 / count as f32;
+        self.particles = (0..count)
+            .map(|_| Particle {
+                pose: Pose::new(
+                    pose.x + position_sd * self.rng.gaussian(),
+                    pose.y + position_sd * self.rng.gaussian(),
+                    wrap_angle(pose.theta + heading_sd * self.rng.gaussian()),
+                ),
+                weight,
+            })
+            .collect();
+    }
+
+    fn uniform_pose(&mut self) -> Pose {
+        let theta = self
+            .rng
+            .range(-core::f32::consts::PI, core::f32::consts::PI);
+        if self.free.is_empty() {
+            return Pose::new(0.0, 0.0, theta);
+        }
+        let (x, y) = self.free[self.rng.index(self.free.len())];
+        Pose::new(
+            x as f32 + self.rng.next_f32(),
+            y as f32 + self.rng.next_f32(),
+            theta,
+        )
+    }
+
+    /// Move every particle by the odometry reading from `from` to `to`
+    /// (poses in the odometry frame), decomposed into a rotation, a
+    /// translation and a second rotation, each perturbed separately.
+    pub fn predict(&mut self, from: Pose, to: Pose) {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let trans = libm::hypotf(dx, dy);
+        let rot1 = if trans < MIN_TRANSLATION {
+            0.0
+        } else {
+            wrap_angle(libm::atan2f(dy, dx) - from.theta)
+        };
+        let rot2 = wrap_angle(to.theta - from.theta - rot1);
+        let n = self.motion_noise;
+        let sd_rot1 = libm::sqrtf(n.rot_rot * rot1 * rot1 + n.rot_trans * trans * trans);
+        let sd_trans =
+            libm::sqrtf(n.trans_trans * trans * trans + n.trans_rot * (rot1 * rot1 + rot2 * rot2));
+        let sd_rot2 = libm::sqrtf(n.rot_rot * rot2 * rot2 + n.rot_trans * trans * trans);
+        for p in &mut self.particles {
+            let r1 = rot1 + sd_rot1 * self.rng.gaussian();
+            let t = trans + sd_trans * self.rng.gaussian();
+            let r2 = rot2 + sd_rot2 * self.rng.gaussian();
+            let heading = p.pose.theta + r1;
+            p.pose.x += t * libm::cosf(heading);
+            p.pose.y += t * libm::sinf(heading);
+            p.pose.theta = wrap_angle(heading + r2);
+        }
+    }
+
+    /// Weight the particles by a scan taken with `sensor`. If no particle
+    /// is consistent with the scan the filter restarts from a uniform prior.
+    pub fn update(&mut self, ranges: &[f32]) {
+        let logs: Vec<f32> = self
+            .particles
+            .iter()
+            .map(|p| self.field.log_likelihood(&self.sensor, p.pose, ranges))
+            .collect();
+        let best = logs.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        if best == f32::NEG_INFINITY {
+            self.reset_uniform();
+            return;
+        }
+        // Per-beam geometric mean likelihood, comparable across scans and
+        // safe from underflow, drives the recovery averages
+        let beams = ranges.len().max(1) as f32;
+        let total: f32 = self.particles.iter().map(|p| p.weight).sum();
+        let w_avg: f32 = self
+            .particles
+            .iter()
+            .zip(&logs)
+            .map(|(p, &l)| p.weight / total * libm::expf(l / beams))
+            .sum();
+        if self.w_slow == 0.0 {
+            self.w_slow = w_avg;
+            self.w_fast = w_avg;
+        } else {
+            self.w_slow += self.alpha_slow * (w_avg - self.w_slow);
+            self.w_fast += self.alpha_fast * (w_avg - self.w_fast);
+        }
+
+        for (p, &l) in self.particles.iter_mut().zip(&logs) {
+            p.weight *= libm::expf(self.tempering * (l - best));
+        }
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        let total: f32 = self.particles.iter().map(|p| p.weight).sum();
+        for p in &mut self.particles {
+            p.weight /= total;
+        }
+    }
+
+    /// Redraw the particles in proportion to their weights, sized by
+    /// KLD-sampling, injecting random poses after a likelihood drop.
+    pub fn resample(&mut self) {
+        let weights: Vec<f32> = self.particles.iter().map(|p| p.weight).collect();
+        // Size the new set from the bins its posterior occupies
+        let draft = low_variance_resample(&weights, self.particles.len(), self.rng.next_f32());
+        let bins: BTreeSet<(i32, i32, i32)> = draft
+            .iter()
+            .map(|&i| self.bin(self.particles[i].pose))
+            .collect();
+        let kld = self.kld;
+        let count = kld_sample_count(bins.len(), kld.epsilon, kld.z)
+            .clamp(kld.min_particles.max(1), kld.max_particles.max(1));
+        let picks = if count == draft.len() {
+            draft
+        } else {
+            low_variance_resample(&weights, count, self.rng.next_f32())
+        };
+
+        let inject = if self.w_slow > 0.0 {
+            (1.0 - self.w_fast / self.w_slow).max(0.0)
+        } else {
+            0.0
+        };
+        if inject > 0.0 {
+            // Start the averages over so one drop does not inject forever
+            self.w_slow = 0.0;
+            self.w_fast = 0.0;
+        }
+        let weight = 1.0 / count as f32;
+        self.particles = picks
+            .iter()
+            .map(|&i| {
+                let pose = if self.rng.next_f32() < inject {
+                    self.uniform_pose()
+                } else {
+                    self.particles[i].pose
+                };
+                Particle { pose, weight }
+            })
+            .collect();
+    }
+
+    /// One filter cycle: odometry from `from` to `to`, then `ranges`.
+    pub fn step(&mut self, from: Pose, to: Pose, ranges: &[f32]) {
+        self.predict(from, to);
+        self.update(ranges);
+        self.resample();
+    }
+
+    fn bin(&self, pose: Pose) -> (i32, i32, i32) {
+        (
+            libm::floorf(pose.x / self.kld.bin_size) as i32,
+            libm::floorf(pose.y / self.kld.bin_size) as i32,
+            libm::floorf(pose.theta / self.kld.bin_angle) as i32,
+        )
+    }
+
+    /// Weighted mean pose, with the heading averaged on the circle.
+    pub fn estimate(&self) -> Pose {
+        let total: f32 = self.particles.iter().map(|p| p.weight).sum();
+        let (mut x, mut y, mut s, mut c) = (0.0, 0.0, 0.0, 0.0);
+        for p in &self.particles {
+            let w = p.weight / total;
+            x += w * p.pose.x;
+            y += w * p.pose.y;
+            s += w * libm::sinf(p.pose.theta);
+            c += w * libm::cosf(p.pose.theta);
+        }
+        Pose::new(x, y, libm::atan2f(s, c))
+    }
+
+    /// Weighted root-mean-square distance of the particles from the
+    /// estimate, in cells.
+    pub fn spread(&self) -> f32 {
+        let mean = self.estimate().position();
+        let total: f32 = self.particles.iter().map(|p| p.weight).sum();
+        let var: f32 = self
+            .particles
+            .iter()
+            .map(|p| p.weight / total * p.pose.position().distance_sq(&mean))
+            .sum();
+        libm::sqrtf(var)
+    }
+
+    /// Effective sample size 1 / sum(w^2) of the normalised weights.
+    pub fn effective_size(&self) -> f32 {
+        let total: f32 = self.particles.iter().map(|p| p.weight).sum();
+        1.0 / self
+            .particles
+            .iter()
+            .map(|p| (p.weight / total) * (p.weight / total))
+            .sum::<f32>()
+    }
+}
+
+#[cfg(test)]
+#[path = "particle_filter_tests.rs"]
+mod tests;
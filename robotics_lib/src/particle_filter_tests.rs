@@ -0,0 +1,285 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::grid_search::GridSearch;
+    use crate::mapgen::{random_rects, GeneratedMap};
+    use crate::math::{wrap_angle, Pose, Vec2};
+    use crate::particle_filter::{
+        kld_sample_count, low_variance_resample, raycast, LikelihoodField, OdometryNoise,
+        ParticleFilter, RangeSensor,
+    };
+    use crate::rng::Rng;
+    use alloc::vec::Vec;
+    use core::f32::consts::{PI, TAU};
+
+    fn walled(width: usize, height: usize) -> GridMap {
+        let mut map = GridMap::new(width, height);
+        for x in 0..width {
+            map.set_obstacle(x, 0, true);
+            map.set_obstacle(x, height - 1, true);
+        }
+        for y in 0..height {
+            map.set_obstacle(0, y, true);
+            map.set_obstacle(width - 1, y, true);
+        }
+        map
+    }
+
+    // First connected cluttered map from `seed` on
+    fn cluttered(seed: u64) -> GeneratedMap {
+        (seed..)
+            .map(|s| random_rects(40, 30, 0.15, 5, s))
+            .find(|g| g.connected)
+            .unwrap()
+    }
+
+    // Poses along a planned path, each facing the next cell
+    fn route(map: &GridMap, from: (usize, usize), to: (usize, usize)) -> Vec<Pose> {
+        let cells = GridSearch::default().plan(map, from, to).unwrap().cells;
+        cells
+            .windows(2)
+            .map(|w| {
+                let (a, b) = (w[0], w[1]);
+                let heading = libm::atan2f(b.1 as f32 - a.1 as f32, b.0 as f32 - a.0 as f32);
+                Pose::new(a.0 as f32 + 0.5, a.1 as f32 + 0.5, heading)
+            })
+            .collect()
+    }
+
+    // Odometry reading after `truth` moved from `a` to `b`: the same motion
+    // in the robot frame with a few percent error, applied to `odom`
+    fn odometry(odom: Pose, a: Pose, b: Pose, rng: &mut Rng) -> Pose {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let local = Vec2::new(
+            dx * libm::cosf(a.theta) + dy * libm::sinf(a.theta),
+            -dx * libm::sinf(a.theta) + dy * libm::cosf(a.theta),
+        ) * (1.0 + 0.03 * rng.gaussian());
+        let turn = wrap_angle(b.theta - a.theta) + 0.02 * rng.gaussian();
+        let (s, c) = (libm::sinf(odom.theta), libm::cosf(odom.theta));
+        Pose::new(
+            odom.x + c * local.x - s * local.y,
+            odom.y + s * local.x + c * local.y,
+            wrap_angle(odom.theta + turn),
+        )
+    }
+
+    fn noisy_scan(sensor: &RangeSensor, map: &GridMap, pose: Pose, rng: &mut Rng) -> Vec<f32> {
+        sensor
+            .scan(map, pose)
+            .into_iter()
+            .map(|r| {
+                if r < sensor.max_range {
+                    (r + 0.05 * rng.gaussian()).max(0.0)
+                } else {
+                    r
+                }
+            })
+            .collect()
+    }
+
+    // Drive `poses`, feeding odometry and scans to the filter
+    fn drive(filter: &mut ParticleFilter, map: &GridMap, poses: &[Pose], rng: &mut Rng) {
+        let mut odom = Pose::new(0.0, 0.0, 0.0);
+        for w in poses.windows(2) {
+            let next = odometry(odom, w[0], w[1], rng);
+            let scan = noisy_scan(&filter.sensor, map, w[1], rng);
+            filter.step(odom, next, &scan);
+            odom = next;
+        }
+    }
+
+    fn localised(filter: &ParticleFilter, truth: Pose) -> bool {
+        let estimate = filter.estimate();
+        estimate.position().distance(&truth.position()) < 1.0
+            && wrap_angle(estimate.theta - truth.theta).abs() < 0.3
+            && filter.spread() < 1.0
+    }
+
+    // ==================== RAYCASTING ====================
+
+    #[test]
+    fn test_raycast_hits_walls() {
+        let map = walled(10, 10);
+        let origin = Vec2::new(5.0, 5.0);
+        assert!((raycast(&map, origin, 0.0, 20.0) - 4.0).abs() < 1e-4);
+        assert!((raycast(&map, origin, PI, 20.0) - 4.0).abs() < 1e-4);
+        assert!((raycast(&map, origin, PI / 2.0, 20.0) - 4.0).abs() < 1e-4);
+        // Diagonal to the corner of the free area
+        let d = raycast(&map, origin, PI / 4.0, 20.0);
+        assert!((d - 4.0 * libm::sqrtf(2.0)).abs() < 1e-3, "{}", d);
+        assert_eq!(raycast(&map, origin, 0.0, 2.5), 2.5);
+        // Inside an obstacle the range is zero
+        assert_eq!(raycast(&map, Vec2::new(0.5, 5.0), 0.0, 20.0), 0.0);
+    }
+
+    #[test]
+    fn test_raycast_does_not_skip_thin_walls() {
+        let mut map = GridMap::new(20, 20);
+        map.set_obstacle(10, 10, true);
+        // Grazing the cell corner still hits it
+        let d = raycast(&map, Vec2::new(2.0, 2.0), PI / 4.0, 30.0);
+        assert!((d - 8.0 * libm::sqrtf(2.0)).abs() < 1e-3, "{}", d);
+        // Off the map counts as a hit at the edge
+        let edge = raycast(&map, Vec2::new(2.5, 3.0), PI, 30.0);
+        assert!((edge - 2.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_beam_angles() {
+        let full = RangeSensor::new(4, TAU, 10.0);
+        for (i, expected) in [-PI, -PI / 2.0, 0.0, PI / 2.0].iter().enumerate() {
+            assert!((full.beam_angle(i) - expected).abs() < 1e-6);
+        }
+        let front = RangeSensor::new(3, PI, 10.0);
+        assert!((front.beam_angle(0) + PI / 2.0).abs() < 1e-6);
+        assert!((front.beam_angle(2) - PI / 2.0).abs() < 1e-6);
+        assert_eq!(RangeSensor::new(1, PI, 10.0).beam_angle(0), 0.0);
+    }
+
+    // ==================== MODELS ====================
+
+    #[test]
+    fn test_likelihood_peaks_at_true_pose() {
+        let generated = random_rects(30, 30, 0.15, 4, 2);
+        let map = &generated.map;
+        let sensor = RangeSensor::new(24, TAU, 8.0);
+        let field = LikelihoodField::new(map, 0.5);
+        let truth = Pose::new(
+            generated.start.0 as f32 + 0.5,
+            generated.start.1 as f32 + 0.5,
+            0.3,
+        );
+        let scan = sensor.scan(map, truth);
+        let at_truth = field.log_likelihood(&sensor, truth, &scan);
+        for offset in [
+            Pose::new(truth.x + 1.0, truth.y, truth.theta),
+            Pose::new(truth.x, truth.y - 1.0, truth.theta),
+            Pose::new(truth.x, truth.y, truth.theta + 0.3),
+        ] {
+            if !map.is_obstacle(offset.x as usize, offset.y as usize) {
+                assert!(field.log_likelihood(&sensor, offset, &scan) < at_truth);
+            }
+        }
+        let blocked = generated
+            .map
+            .costs
+            .iter()
+            .position(|&c| c > 0)
+            .map(|i| Pose::new((i % 30) as f32 + 0.5, (i / 30) as f32 + 0.5, 0.0))
+            .unwrap();
+        assert_eq!(
+            field.log_likelihood(&sensor, blocked, &scan),
+            f32::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn test_predict_follows_odometry() {
+        let map = walled(20, 20);
+        let mut filter = ParticleFilter::new(&map, RangeSensor::new(8, TAU, 5.0), 1);
+        filter.motion_noise = OdometryNoise {
+            rot_rot: 0.0,
+            rot_trans: 0.0,
+            trans_trans: 0.0,
+            trans_rot: 0.0,
+        };
+        filter.reset_at(Pose::new(5.0, 5.0, PI / 2.0), 0.0, 0.0);
+        // Odometry frame rotated against the map frame: only the relative
+        // motion matters
+        filter.predict(Pose::new(1.0, 1.0, 0.0), Pose::new(3.0, 1.0, PI / 2.0));
+        for p in &filter.particles {
+            assert!((p.pose.x - 5.0).abs() < 1e-4 && (p.pose.y - 7.0).abs() < 1e-4);
+            assert!((p.pose.theta - PI).abs() < 1e-4 || (p.pose.theta + PI).abs() < 1e-4);
+        }
+        // With noise the cloud spreads around the same motion
+        filter.motion_noise = OdometryNoise::default();
+        filter.reset_at(Pose::new(5.0, 5.0, 0.0), 0.0, 0.0);
+        filter.predict(Pose::new(0.0, 0.0, 0.0), Pose::new(4.0, 0.0, 0.0));
+        let estimate = filter.estimate();
+        assert!(estimate.position().distance(&Vec2::new(9.0, 5.0)) < 0.5);
+        assert!(filter.spread() > 0.3);
+    }
+
+    // ==================== RESAMPLING ====================
+
+    #[test]
+    fn test_low_variance_resample_is_proportional() {
+        let weights = [0.1, 0.0, 0.6, 0.3];
+        let picks = low_variance_resample(&weights, 10, 0.5);
+        assert_eq!(picks.len(), 10);
+        let count = |i: usize| picks.iter().filter(|&&p| p == i).count();
+        assert_eq!((count(0), count(1), count(2), count(3)), (1, 0, 6, 3));
+        // Sorted and unnormalised weights work too
+        let picks = low_variance_resample(&[2.0, 2.0], 4, 0.5);
+        assert_eq!(picks, [0, 0, 1, 1]);
+        assert!(low_variance_resample(&[0.0, 0.0], 4, 0.3).is_empty());
+    }
+
+    #[test]
+    fn test_kld_count_grows_with_bins() {
+        assert_eq!(kld_sample_count(1, 0.05, 2.33), 1);
+        let counts: Vec<usize> = [2, 10, 100, 1000]
+            .iter()
+            .map(|&k| kld_sample_count(k, 0.05, 2.33))
+            .collect();
+        assert!(counts.windows(2).all(|w| w[0] < w[1]));
+        // Roughly (k - 1) / (2 epsilon) for many bins
+        assert!(counts[3] > 9990 && counts[3] < 12000, "{}", counts[3]);
+        assert!(kld_sample_count(100, 0.1, 2.33) < counts[2]);
+    }
+
+    // ==================== LOCALISATION ====================
+
+    #[test]
+    fn test_converges_from_uniform_prior() {
+        let generated = cluttered(11);
+        let map = &generated.map;
+        let mut filter = ParticleFilter::new(map, RangeSensor::new(24, TAU, 8.0), 3);
+        let initial = filter.particles.len();
+        let mut rng = Rng::new(5);
+        let mut poses = route(map, generated.start, generated.goal);
+        poses.extend(route(map, generated.goal, generated.start));
+        drive(&mut filter, map, &poses, &mut rng);
+        let truth = poses[poses.len() - 1];
+        assert!(
+            localised(&filter, truth),
+            "{:?} vs {:?}",
+            filter.estimate(),
+            truth
+        );
+        // KLD-sampling shrinks the set once the posterior is compact
+        assert!(
+            filter.particles.len() < initial / 3,
+            "{}",
+            filter.particles.len()
+        );
+    }
+
+    #[test]
+    fn test_recovers_from_kidnapping() {
+        let generated = cluttered(11);
+        let map = &generated.map;
+        let mut filter = ParticleFilter::new(map, RangeSensor::new(24, TAU, 8.0), 9);
+        let mut rng = Rng::new(6);
+        let mut poses = route(map, generated.start, generated.goal);
+        poses.extend(route(map, generated.goal, generated.start));
+        drive(&mut filter, map, &poses, &mut rng);
+        assert!(localised(&filter, poses[poses.len() - 1]));
+
+        // Carried to the goal without odometry, then driven back
+        let poses = route(map, generated.goal, generated.start);
+        let mut recovered = None;
+        let mut odom = Pose::new(0.0, 0.0, 0.0);
+        for (step, w) in poses.windows(2).enumerate() {
+            let next = odometry(odom, w[0], w[1], &mut rng);
+            let scan = noisy_scan(&filter.sensor, map, w[1], &mut rng);
+            filter.step(odom, next, &scan);
+            odom = next;
+            if step > 0 && localised(&filter, w[1]) {
+                recovered = Some(step);
+                break;
+            }
+        }
+        assert!(recovered.is_some(), "{:?}", filter.estimate());
+    }
+}
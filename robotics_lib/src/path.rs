@@ -0,0 +1,296 @@
+use crate::collision::CollisionChecker;
+use crate::dstar::GridMap;
+use crate::map_io::MapInfo;
+use crate::math::Vec2;
+use crate::theta_star::line_of_sight;
+use alloc::vec::Vec;
+
+// Post-processing works in continuous grid coordinates (one unit per cell,
+// cell (x, y) covering [x, x + 1) x [y, y + 1)), the frame `GridMap`
+// collision checks use. `to_world` maps the result into the world.
+
+/// Centres of a cell path in grid coordinates.
+pub fn cell_centers(cells: &[(usize, usize)]) -> Vec<Vec2> {
+    cells
+        .iter()
+        .map(|&(x, y)| Vec2::new(x as f32 + 0.5, y as f32 + 0.5))
+        .collect()
+}
+
+/// Grid-coordinate points to world points using the map resolution and
+/// origin.
+pub fn to_world(info: &MapInfo, points: &[Vec2]) -> Vec<Vec2> {
+    points.iter().map(|&p| info.grid_to_world(p)).collect()
+}
+
+/// Drop every waypoint that can be skipped with a straight, obstacle-free
+/// line: from each kept cell, jump to the farthest later cell in sight.
+/// Costs below `LETHAL` are ignored, so this may cut through expensive cells.
+pub fn shortcut(map: &GridMap, cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let Some(&first) = cells.first() else {
+        return Vec::new();
+    };
+    let mut out = alloc::vec![first];
+    let mut i = 0;
+    while i + 1 < cells.len() {
+        let mut j = cells.len() - 1;
+        while j > i + 1 && !line_of_sight(map, cells[i], cells[j]) {
+            j -= 1;
+        }
+        out.push(cells[j]);
+        i = j;
+    }
+    out
+}
+
+/// Insert points so that consecutive points are at most `spacing` apart.
+pub fn densify(points: &[Vec2], spacing: f32) -> Vec<Vec2> {
+    let mut out = Vec::new();
+    for w in points.windows(2) {
+        let n = libm::ceilf(w[0].distance(&w[1]) / spacing.max(1e-3)).max(1.0) as usize;
+        for k in 0..n {
+            out.push(w[0] + (w[1] - w[0]) * (k as f32 / n as f32));
+        }
+    }
+    out.extend(points.last());
+    out
+}
+
+/// Total length of a polyline.
+pub fn polyline_length(points: &[Vec2]) -> f32 {
+    points.windows(2).map(|w| w[0].distance(&w[1])).sum()
+}
+
+/// Weights of the smoothing objective.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SmoothConfig {
+    /// Pull towards the original point.
+    pub data_weight: f32,
+    /// Pull towards the midpoint of the neighbours.
+    pub smooth_weight: f32,
+    /// Push away from obstacles closer than `clearance`.
+    pub clearance_weight: f32,
+    /// Desired distance from obstacles, in cells.
+    pub clearance: f32,
+    pub iterations: usize,
+}
+
+impl Default for SmoothConfig {
+    fn default() -> Self {
+        Self {
+            data_weight: 0.05,
+            smooth_weight: 0.3,
+            clearance_weight: 0.2,
+            clearance: 1.5,
+            iterations: 200,
+        }
+    }
+}
+
+/// Gradient-descent smoothing of a grid-coordinate path. Each interior
+/// point moves along the gradient of the data, smoothness and clearance
+/// terms; a move that would put an adjacent segment in collision is
+/// skipped, so a collision-free input stays collision-free. The end points
+/// are fixed. Densify sparse paths first to give the smoother room to work.
+pub fn smooth(map: &GridMap, points: &[Vec2], config: &SmoothConfig) -> Vec<Vec2> {
+    let mut out = points.to_vec();
+    if out.len() < 3 {
+        return out;
+    }
+    for _ in 0..config.iterations {
+        for i in 1..out.len() - 1 {
+            let p = out[i];
+            let mut step = (points[i] - p) * config.data_weight
+                + (out[i - 1] + out[i + 1] - p * 2.0) * config.smooth_weight;
+            if let Some((q, d)) = nearest_obstacle(map, p, config.clearance) {
+                if d > 1e-6 {
+                    step = step + (p - q) / d * ((config.clearance - d) * config.clearance_weight);
+                }
+            }
+            let next = p + step;
+            if map.segment_free(out[i - 1], next) && map.segment_free(next, out[i + 1]) {
+                out[i] = next;
+            }
+        }
+    }
+    out
+}
+
+/// Closest point on any obstacle cell within `radius` of `p`, with its
+/// distance.
+pub fn nearest_obstacle(map: &GridMap, p: Vec2, radius: f32) -> Option<(Vec2, f32)> {
+    let x0 = libm::floorf(p.x - radius).max(0.0) as usize;
+    let y0 = libm::floorf(p.y - radius).max(0.0) as usize;
+    let x1 = (libm::floorf(p.x + radius).max(-1.0) + 1.0) as usize;
+    let y1 = (libm::floorf(p.y + radius).max(-1.0) + 1.0) as usize;
+    let mut best: Option<(Vec2, f32)> = None;
+    for y in y0..y1.min(map.height) {
+        for x in x0..x1.min(map.width) {
+            if !map.is_obstacle(x, y) {
+                continue;
+            }
+            let q = Vec2::new(
+                p.x.clamp(x as f32, x as f32 + 1.0),
+                p.y.clamp(y as f32, y as f32 + 1.0),
+            );
+            let d = p.distance(&q);
+            if d <= radius && best.is_none_or(|b| d < b.1) {
+                best = Some((q, d));
+            }
+        }
+    }
+    best
+}
+
+/// Smallest distance from any point of the path to an obstacle, capped at
+/// `radius`. Points are checked every `step` along the segments.
+pub fn clearance(map: &GridMap, points: &[Vec2], radius: f32, step: f32) -> f32 {
+    densify(points, step)
+        .iter()
+        .map(|&p| nearest_obstacle(map, p, radius).map_or(radius, |(_, d)| d))
+        .fold(radius, f32::min)
+}
+
+/// Cubic spline family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplineKind {
+    /// Interpolates every control point.
+    CatmullRom,
+    /// Uniform cubic B-spline: smoother (C2), passes through the end points
+    /// only and cuts inside the others.
+    BSpline,
+}
+
+// Arc-length table resolution per spline segment
+const TABLE_STEPS: usize = 16;
+
+/// A cubic spline through (or near) a list of points, parameterised by arc
+/// length for tracking.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spline {
+    pub kind: SplineKind,
+    pub points: Vec<Vec2>,
+    // Padded control polygon the segments are evaluated on
+    control: Vec<Vec2>,
+    // Cumulative length at every table step
+    table: Vec<f32>,
+}
+
+impl Spline {
+    pub fn new(kind: SplineKind, points: &[Vec2]) -> Self {
+        let mut control = Vec::with_capacity(points.len() + 4);
+        if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+            // Repeated ends make both kinds start and finish on the end points
+            let pad = match kind {
+                SplineKind::CatmullRom => 1,
+                SplineKind::BSpline => 2,
+            };
+            control.extend(core::iter::repeat_n(first, pad));
+            control.extend_from_slice(points);
+            control.extend(core::iter::repeat_n(last, pad));
+        }
+        let mut spline = Self {
+            kind,
+            points: points.to_vec(),
+            control,
+            table: alloc::vec![0.0],
+        };
+        let n = spline.segments() * TABLE_STEPS;
+        let mut prev = spline.point(0.0);
+        let mut total = 0.0;
+        for k in 1..=n {
+            let p = spline.point(k as f32 / TABLE_STEPS as f32);
+            total += prev.distance(&p);
+            spline.table.push(total);
+            prev = p;
+        }
+        spline
+    }
+
+    pub fn catmull_rom(points: &[Vec2]) -> Self {
+        Self::new(SplineKind::CatmullRom, points)
+    }
+
+    pub fn b_spline(points: &[Vec2]) -> Self {
+        Self::new(SplineKind::BSpline, points)
+    }
+
+    /// Number of cubic segments.
+    pub fn segments(&self) -> usize {
+        self.control.len().saturating_sub(3)
+    }
+
+    /// Point at curve parameter `t` in `[0, segments]`.
+    pub fn point(&self, t: f32) -> Vec2 {
+        let n = self.segments();
+        if n == 0 {
+            return self.points.first().copied().unwrap_or(Vec2::zero());
+        }
+        let t = t.clamp(0.0, n as f32);
+        let i = (t as usize).min(n - 1);
+        let u = t - i as f32;
+        let (u2, u3) = (u * u, u * u * u);
+        let w = match self.kind {
+            SplineKind::CatmullRom => [
+                0.5 * (-u3 + 2.0 * u2 - u),
+                0.5 * (3.0 * u3 - 5.0 * u2 + 2.0),
+                0.5 * (-3.0 * u3 + 4.0 * u2 + u),
+                0.5 * (u3 - u2),
+            ],
+            SplineKind::BSpline => [
+                (1.0 - u) * (1.0 - u) * (1.0 - u) / 6.0,
+                (3.0 * u3 - 6.0 * u2 + 4.0) / 6.0,
+                (-3.0 * u3 + 3.0 * u2 + 3.0 * u + 1.0) / 6.0,
+                u3 / 6.0,
+            ],
+        };
+        let c = &self.control[i..i + 4];
+        c[0] * w[0] + c[1] * w[1] + c[2] * w[2] + c[3] * w[3]
+    }
+
+    /// Arc length of the whole curve.
+    pub fn length(&self) -> f32 {
+        self.table.last().copied().unwrap_or(0.0)
+    }
+
+    // Curve parameter at arc length `s`
+    fn parameter(&self, s: f32) -> f32 {
+        let s = s.clamp(0.0, self.length());
+        let k = self.table.partition_point(|&l| l < s);
+        if k == 0 {
+            return 0.0;
+        }
+        let (a, b) = (self.table[k - 1], self.table[k]);
+        let frac = if b > a { (s - a) / (b - a) } else { 0.0 };
+        ((k - 1) as f32 + frac) / TABLE_STEPS as f32
+    }
+
+    /// Point at arc length `s` from the start (clamped to the curve).
+    pub fn sample(&self, s: f32) -> Vec2 {
+        self.point(self.parameter(s))
+    }
+
+    /// Heading in radians of the curve at arc length `s`.
+    pub fn heading(&self, s: f32) -> f32 {
+        let t = self.parameter(s);
+        let h = 1e-3;
+        let n = self.segments() as f32;
+        let d = self.point((t + h).min(n)) - self.point((t - h).max(0.0));
+        libm::atan2f(d.y, d.x)
+    }
+
+    /// Points every `step` of arc length (at least 1e-3), including both
+    /// ends.
+    pub fn sample_many(&self, step: f32) -> Vec<Vec2> {
+        let step = step.max(1e-3);
+        let total = self.length();
+        let n = libm::ceilf(total / step) as usize;
+        let mut out: Vec<Vec2> = (0..n).map(|i| self.sample(i as f32 * step)).collect();
+        out.push(self.sample(total));
+        out
+    }
+}
+
+#[cfg(test)]
+#[path = "path_tests.rs"]
+mod tests;
@@ -0,0 +1,258 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::CollisionChecker;
+    use crate::dstar::GridMap;
+    use crate::grid_search::GridSearch;
+    use crate::map_io::MapInfo;
+    use crate::math::{Pose, Vec2};
+    use crate::path::{
+        cell_centers, clearance, densify, nearest_obstacle, shortcut, smooth, to_world,
+        SmoothConfig, Spline,
+    };
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::f32::consts::FRAC_PI_2;
+
+    fn close(a: Vec2, b: Vec2, tol: f32) -> bool {
+        a.distance(&b) < tol
+    }
+
+    fn length(points: &[Vec2]) -> f32 {
+        points.windows(2).map(|w| w[0].distance(&w[1])).sum()
+    }
+
+    // Sum of absolute heading changes between segments
+    fn turning(points: &[Vec2]) -> f32 {
+        points
+            .windows(3)
+            .map(|w| {
+                let (a, b) = (w[1] - w[0], w[2] - w[1]);
+                libm::atan2f(a.cross(&b), a.dot(&b)).abs()
+            })
+            .sum()
+    }
+
+    fn assert_free(map: &GridMap, points: &[Vec2]) {
+        for w in points.windows(2) {
+            assert!(map.segment_free(w[0], w[1]), "{:?} -> {:?}", w[0], w[1]);
+        }
+    }
+
+    fn wall_map() -> GridMap {
+        // Wall at x = 10 with a gap at the top
+        let mut map = GridMap::new(20, 20);
+        for y in 0..15 {
+            map.set_obstacle(10, y, true);
+        }
+        map
+    }
+
+    // ==================== WORLD CONVERSION ====================
+
+    #[test]
+    fn test_cell_centers_to_world() {
+        let centres = cell_centers(&[(0, 0), (3, 1)]);
+        assert_eq!(centres, vec![Vec2::new(0.5, 0.5), Vec2::new(3.5, 1.5)]);
+
+        let info = MapInfo::new(0.05, Pose::new(-1.0, 2.0, FRAC_PI_2));
+        let world = to_world(&info, &centres);
+        assert_eq!(world[1], info.cell_to_world((3, 1)));
+        assert!(close(world[0], Vec2::new(-1.025, 2.025), 1e-5));
+    }
+
+    // ==================== SHORTCUTTING ====================
+
+    #[test]
+    fn test_shortcut_open_map() {
+        let map = GridMap::new(20, 20);
+        let path = GridSearch::default().plan(&map, (0, 0), (19, 7)).unwrap();
+        assert!(path.cells.len() > 2);
+        assert_eq!(shortcut(&map, &path.cells), vec![(0, 0), (19, 7)]);
+    }
+
+    #[test]
+    fn test_shortcut_keeps_corners() {
+        let map = wall_map();
+        let path = GridSearch::default().plan(&map, (2, 2), (17, 2)).unwrap();
+        let short = shortcut(&map, &path.cells);
+        assert_eq!(short[0], (2, 2));
+        assert_eq!(*short.last().unwrap(), (17, 2));
+        assert!(short.len() >= 3 && short.len() < path.cells.len());
+        assert_free(&map, &cell_centers(&short));
+        assert!(length(&cell_centers(&short)) < path.cost);
+    }
+
+    #[test]
+    fn test_shortcut_trivial() {
+        let map = GridMap::new(5, 5);
+        assert!(shortcut(&map, &[]).is_empty());
+        assert_eq!(shortcut(&map, &[(1, 1)]), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_densify_spacing() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(3.0, 0.0),
+            Vec2::new(3.0, 1.0),
+        ];
+        let dense = densify(&points, 0.5);
+        assert_eq!(dense.len(), 9);
+        assert_eq!(dense[0], points[0]);
+        assert_eq!(*dense.last().unwrap(), points[2]);
+        assert!(dense.windows(2).all(|w| w[0].distance(&w[1]) <= 0.5 + 1e-5));
+    }
+
+    // ==================== SMOOTHING ====================
+
+    #[test]
+    fn test_nearest_obstacle() {
+        let map = wall_map();
+        let (q, d) = nearest_obstacle(&map, Vec2::new(8.5, 5.5), 3.0).unwrap();
+        assert!(close(q, Vec2::new(10.0, 5.5), 1e-5));
+        assert!((d - 1.5).abs() < 1e-5);
+        assert!(nearest_obstacle(&map, Vec2::new(3.5, 5.5), 3.0).is_none());
+    }
+
+    #[test]
+    fn test_smoothing_reduces_turning_and_keeps_ends() {
+        let map = wall_map();
+        let path = GridSearch::default().plan(&map, (2, 2), (17, 2)).unwrap();
+        let raw = densify(&cell_centers(&path.cells), 0.5);
+        let smoothed = smooth(&map, &raw, &SmoothConfig::default());
+        assert_eq!(smoothed.len(), raw.len());
+        assert_eq!(smoothed[0], raw[0]);
+        assert_eq!(smoothed.last(), raw.last());
+        assert_free(&map, &smoothed);
+        assert!(turning(&smoothed) < turning(&raw));
+    }
+
+    #[test]
+    fn test_smoothing_adds_clearance() {
+        // A path hugging the wall gets pushed out
+        let map = wall_map();
+        let hugging: Vec<Vec2> = (2..13).map(|y| Vec2::new(9.3, y as f32)).collect();
+        let before = clearance(&map, &hugging, 3.0, 0.1);
+        let config = SmoothConfig {
+            clearance: 2.0,
+            ..SmoothConfig::default()
+        };
+        let smoothed = smooth(&map, &hugging, &config);
+        let after = clearance(&map, &smoothed[1..smoothed.len() - 1], 3.0, 0.1);
+        assert!(before < 0.8);
+        assert!(after > before + 0.5, "{} -> {}", before, after);
+        assert_free(&map, &smoothed);
+    }
+
+    // ==================== SPLINES ====================
+
+    #[test]
+    fn test_catmull_rom_interpolates() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(8.0, 5.0),
+        ];
+        let spline = Spline::catmull_rom(&points);
+        assert_eq!(spline.segments(), 3);
+        for (i, &p) in points.iter().enumerate() {
+            assert!(close(spline.point(i as f32), p, 1e-5));
+        }
+        assert!(spline.length() >= length(&points) - 1e-3);
+    }
+
+    #[test]
+    fn test_b_spline_hits_ends_only() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(8.0, 4.0),
+        ];
+        let spline = Spline::b_spline(&points);
+        assert!(close(spline.sample(0.0), points[0], 1e-5));
+        assert!(close(spline.sample(spline.length()), points[3], 1e-5));
+        // Corners are cut
+        let nearest = spline
+            .sample_many(0.05)
+            .iter()
+            .map(|p| p.distance(&points[1]))
+            .fold(f32::INFINITY, f32::min);
+        assert!(nearest > 0.3);
+        assert!(spline.length() < length(&points));
+    }
+
+    #[test]
+    fn test_straight_spline_length() {
+        let points = [Vec2::new(1.0, 1.0), Vec2::new(4.0, 5.0)];
+        for spline in [Spline::catmull_rom(&points), Spline::b_spline(&points)] {
+            assert!((spline.length() - 5.0).abs() < 1e-3);
+            assert!(close(spline.sample(2.5), Vec2::new(2.5, 3.0), 1e-2));
+            assert!((spline.heading(1.0) - libm::atan2f(4.0, 3.0)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_arc_length_sampling_is_even() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(9.0, 4.0),
+            Vec2::new(10.0, 0.0),
+        ];
+        for spline in [Spline::catmull_rom(&points), Spline::b_spline(&points)] {
+            let samples = spline.sample_many(0.25);
+            let gaps: Vec<f32> = samples.windows(2).map(|w| w[0].distance(&w[1])).collect();
+            for &g in &gaps[..gaps.len() - 1] {
+                assert!((g - 0.25).abs() < 0.01, "{}", g);
+            }
+            assert!(close(*samples.last().unwrap(), points[4], 1e-4));
+        }
+    }
+
+    #[test]
+    fn test_degenerate_splines() {
+        let single = Spline::catmull_rom(&[Vec2::new(2.0, 3.0)]);
+        assert_eq!(single.length(), 0.0);
+        assert_eq!(single.sample(1.0), Vec2::new(2.0, 3.0));
+        assert_eq!(Spline::b_spline(&[]).sample_many(1.0), vec![Vec2::zero()]);
+    }
+
+    #[test]
+    fn test_zero_step_samples_whole_spline() {
+        let spline = Spline::catmull_rom(&[Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0)]);
+        for step in [0.0, -1.0] {
+            let samples = spline.sample_many(step);
+            assert_eq!(samples.len(), 2001);
+            assert!(close(samples[1000], Vec2::new(1.0, 0.0), 1e-2));
+            assert!(close(*samples.last().unwrap(), Vec2::new(2.0, 0.0), 1e-4));
+        }
+    }
+
+    // ==================== PIPELINE ====================
+
+    #[test]
+    fn test_plan_to_world_spline() {
+        let map = wall_map();
+        let cells = GridSearch::default()
+            .plan(&map, (2, 2), (17, 2))
+            .unwrap()
+            .cells;
+        let points = densify(&cell_centers(&shortcut(&map, &cells)), 0.5);
+        let smoothed = smooth(&map, &points, &SmoothConfig::default());
+        let spline = Spline::catmull_rom(&smoothed);
+        let samples = spline.sample_many(0.2);
+        assert_free(&map, &samples);
+
+        let info = MapInfo::new(0.1, Pose::new(5.0, 5.0, 0.0));
+        let world = to_world(&info, &samples);
+        assert!(close(world[0], info.cell_to_world((2, 2)), 1e-4));
+        assert!(close(
+            *world.last().unwrap(),
+            info.cell_to_world((17, 2)),
+            1e-4
+        ));
+    }
+}
@@ -0,0 +1,351 @@
+use crate::collision::CollisionChecker;
+use crate::dstar::GridMap;
+use crate::grid_search::MinScored;
+use crate::math::Vec2;
+use crate::rng::Rng;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrmConfig {
+    /// Free configurations to sample when building.
+    pub samples: usize,
+    /// Neighbours each sample tries to connect to.
+    pub k: usize,
+    /// Lazy PRM: skip edge checks while building and only check the edges a
+    /// query actually uses.
+    pub lazy: bool,
+}
+
+impl PrmConfig {
+    pub fn new(samples: usize, k: usize) -> Self {
+        Self {
+            samples,
+            k,
+            lazy: false,
+        }
+    }
+
+    pub fn lazy(samples: usize, k: usize) -> Self {
+        Self {
+            lazy: true,
+            ..Self::new(samples, k)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeState {
+    /// Not collision-checked since it was added or last invalidated.
+    Unchecked,
+    Free,
+    Blocked,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoadmapEdge {
+    pub a: usize,
+    pub b: usize,
+    pub length: f32,
+    pub state: EdgeState,
+}
+
+/// Probabilistic roadmap over any [`CollisionChecker`].
+///
+/// The roadmap is built once and reused for many queries; start and goal are
+/// joined to it temporarily for each query. Blocked candidate edges are kept
+/// so that freeing space later can bring them back. With a `GridMap` world,
+/// [`Prm::set_obstacle`] marks the edges near a changed cell for rechecking
+/// instead of rebuilding the roadmap.
+#[derive(Clone, Debug)]
+pub struct Prm<C: CollisionChecker> {
+    pub config: PrmConfig,
+    pub bounds_min: Vec2,
+    pub bounds_max: Vec2,
+    /// Segment checks performed so far, for comparing eager and lazy modes.
+    pub collision_checks: usize,
+    world: C,
+    nodes: Vec<Vec2>,
+    edges: Vec<RoadmapEdge>,
+    adjacency: Vec<Vec<usize>>,
+    rng: Rng,
+}
+
+impl<C: CollisionChecker> Prm<C> {
+    /// Build a roadmap with `config.samples` free samples inside the bounds.
+    pub fn new(world: C, config: PrmConfig, bounds_min: Vec2, bounds_max: Vec2, seed: u64) -> Self {
+        let mut prm = Self {
+            config,
+            bounds_min,
+            bounds_max,
+            collision_checks: 0,
+            world,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            adjacency: Vec::new(),
+            rng: Rng::new(seed),
+        };
+        prm.add_samples(config.samples);
+        prm
+    }
+
+    pub fn world(&self) -> &C {
+        &self.world
+    }
+
+    pub fn nodes(&self) -> &[Vec2] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[RoadmapEdge] {
+        &self.edges
+    }
+
+    /// Grow the roadmap by up to `count` free samples. Gives up after
+    /// `100 * count` rejected draws, so a mostly blocked world cannot hang.
+    pub fn add_samples(&mut self, count: usize) {
+        let mut added = 0;
+        let mut attempts = 0;
+        while added < count && attempts < count * 100 {
+            attempts += 1;
+            let p = Vec2::new(
+                self.rng.range(self.bounds_min.x, self.bounds_max.x),
+                self.rng.range(self.bounds_min.y, self.bounds_max.y),
+            );
+            if self.world.is_free(p) {
+                self.add_node(p);
+                added += 1;
+            }
+        }
+    }
+
+    /// Insert a node and connect it to its k nearest neighbours.
+    pub fn add_node(&mut self, p: Vec2) -> usize {
+        let id = self.nodes.len();
+        let nearest = self.k_nearest(p, self.config.k);
+        self.nodes.push(p);
+        self.adjacency.push(Vec::new());
+        for j in nearest {
+            let state = if self.config.lazy {
+                EdgeState::Unchecked
+            } else if self.check(self.nodes[j], p) {
+                EdgeState::Free
+            } else {
+                EdgeState::Blocked
+            };
+            let e = self.edges.len();
+            self.edges.push(RoadmapEdge {
+                a: j,
+                b: id,
+                length: self.nodes[j].distance(&p),
+                state,
+            });
+            self.adjacency[j].push(e);
+            self.adjacency[id].push(e);
+        }
+        id
+    }
+
+    /// Shortest roadmap path from `start` to `goal`, or `None` if they cannot
+    /// be joined to the same connected part of the roadmap.
+    ///
+    /// Unchecked edges are assumed free during the search; the ones on the
+    /// resulting path are then checked and the search repeats until the path
+    /// is fully verified.
+    pub fn query(&mut self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        if !self.world.is_free(start) || !self.world.is_free(goal) {
+            return None;
+        }
+        let start_links = self.links(start);
+        let goal_links = self.links(goal);
+        let direct = self.check(start, goal);
+
+        loop {
+            let route = self.search(start, goal, &start_links, &goal_links, direct)?;
+            let mut verified = true;
+            for w in route.windows(2) {
+                if let Some(e) = self.edge_between(w[0], w[1]) {
+                    if self.edges[e].state == EdgeState::Unchecked {
+                        let (a, b) = (self.nodes[self.edges[e].a], self.nodes[self.edges[e].b]);
+                        self.edges[e].state = if self.check(a, b) {
+                            EdgeState::Free
+                        } else {
+                            verified = false;
+                            EdgeState::Blocked
+                        };
+                    }
+                }
+            }
+            if verified {
+                return Some(self.to_points(&route, start, goal));
+            }
+        }
+    }
+
+    fn check(&mut self, a: Vec2, b: Vec2) -> bool {
+        self.collision_checks += 1;
+        self.world.segment_free(a, b)
+    }
+
+    fn other(&self, edge: usize, node: usize) -> usize {
+        let e = &self.edges[edge];
+        if e.a == node {
+            e.b
+        } else {
+            e.a
+        }
+    }
+
+    fn edge_between(&self, a: usize, b: usize) -> Option<usize> {
+        let n = self.nodes.len();
+        if a >= n || b >= n {
+            return None;
+        }
+        self.adjacency[a]
+            .iter()
+            .copied()
+            .find(|&e| self.other(e, a) == b)
+    }
+
+    fn k_nearest(&self, p: Vec2, k: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by(|&i, &j| {
+            self.nodes[i]
+                .distance_sq(&p)
+                .total_cmp(&self.nodes[j].distance_sq(&p))
+        });
+        order.truncate(k);
+        order
+    }
+
+    // Nearest roadmap nodes that a query point can reach in a straight line
+    fn links(&mut self, p: Vec2) -> Vec<usize> {
+        let mut links = Vec::new();
+        for i in self.k_nearest(p, self.nodes.len()) {
+            if links.len() >= self.config.k.max(1) {
+                break;
+            }
+            if self.check(p, self.nodes[i]) {
+                links.push(i);
+            }
+        }
+        links
+    }
+
+    // A* over the roadmap plus two virtual nodes: `n` is start, `n + 1` goal
+    fn search(
+        &self,
+        start: Vec2,
+        goal: Vec2,
+        start_links: &[usize],
+        goal_links: &[usize],
+        direct: bool,
+    ) -> Option<Vec<usize>> {
+        let n = self.nodes.len();
+        let (s, t) = (n, n + 1);
+        let pos = |i: usize| match i {
+            _ if i == s => start,
+            _ if i == t => goal,
+            _ => self.nodes[i],
+        };
+        let mut g = alloc::vec![f32::INFINITY; n + 2];
+        let mut parent = alloc::vec![usize::MAX; n + 2];
+        let mut closed = alloc::vec![false; n + 2];
+        let mut open = BinaryHeap::new();
+        g[s] = 0.0;
+        open.push(MinScored::new(start.distance(&goal), s));
+
+        let mut successors = Vec::new();
+        while let Some(MinScored { item: current, .. }) = open.pop() {
+            if closed[current] {
+                continue;
+            }
+            closed[current] = true;
+            if current == t {
+                let mut route = alloc::vec![t];
+                while let Some(&last) = route.last() {
+                    if parent[last] == usize::MAX {
+                        break;
+                    }
+                    route.push(parent[last]);
+                }
+                route.reverse();
+                return Some(route);
+            }
+
+            successors.clear();
+            if current == s {
+                successors.extend_from_slice(start_links);
+                if direct {
+                    successors.push(t);
+                }
+            } else {
+                for &e in &self.adjacency[current] {
+                    if self.edges[e].state != EdgeState::Blocked {
+                        successors.push(self.other(e, current));
+                    }
+                }
+                if goal_links.contains(&current) {
+                    successors.push(t);
+                }
+            }
+
+            for &next in &successors {
+                if closed[next] {
+                    continue;
+                }
+                let cost = g[current] + pos(current).distance(&pos(next));
+                if cost < g[next] {
+                    g[next] = cost;
+                    parent[next] = current;
+                    open.push(MinScored::new(cost + pos(next).distance(&goal), next));
+                }
+            }
+        }
+        None
+    }
+
+    fn to_points(&self, route: &[usize], start: Vec2, goal: Vec2) -> Vec<Vec2> {
+        let n = self.nodes.len();
+        route
+            .iter()
+            .map(|&i| match i {
+                _ if i == n => start,
+                _ if i == n + 1 => goal,
+                _ => self.nodes[i],
+            })
+            .collect()
+    }
+}
+
+impl Prm<GridMap> {
+    /// Change a cell of the underlying map and mark every edge whose bounding
+    /// box touches it for rechecking on the next query. Returns how many edges
+    /// were invalidated.
+    pub fn set_obstacle(&mut self, x: usize, y: usize, is_obs: bool) -> usize {
+        if x >= self.world.width || y >= self.world.height || self.world.is_obstacle(x, y) == is_obs
+        {
+            return 0;
+        }
+        self.world.set_obstacle(x, y, is_obs);
+
+        let (cx, cy) = (x as f32, y as f32);
+        let mut invalidated = 0;
+        for e in &mut self.edges {
+            let (a, b) = (self.nodes[e.a], self.nodes[e.b]);
+            let overlaps = a.x.min(b.x) < cx + 1.0
+                && a.x.max(b.x) >= cx
+                && a.y.min(b.y) < cy + 1.0
+                && a.y.max(b.y) >= cy;
+            if overlaps && e.state != EdgeState::Unchecked {
+                e.state = EdgeState::Unchecked;
+                invalidated += 1;
+            }
+        }
+        invalidated
+    }
+}
+
+#[cfg(test)]
+#[path = "prm_tests.rs"]
+mod tests;
@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::{Circle, CollisionChecker, ObstacleSet, Polygon};
+    use crate::dstar::GridMap;
+    use crate::math::Vec2;
+    use crate::prm::{EdgeState, Prm, PrmConfig};
+
+    fn assert_path_free<C: CollisionChecker>(checker: &C, path: &[Vec2]) {
+        for w in path.windows(2) {
+            assert!(checker.segment_free(w[0], w[1]), "{:?} -> {:?}", w[0], w[1]);
+        }
+    }
+
+    fn wall_map() -> GridMap {
+        // Wall at x = 10 with a gap at y = 16..18
+        let mut map = GridMap::new(20, 20);
+        for y in 0..20 {
+            if !(16..18).contains(&y) {
+                map.set_obstacle(10, y, true);
+            }
+        }
+        map
+    }
+
+    fn grid_prm(config: PrmConfig, seed: u64) -> Prm<GridMap> {
+        Prm::new(
+            wall_map(),
+            config,
+            Vec2::zero(),
+            Vec2::new(20.0, 20.0),
+            seed,
+        )
+    }
+
+    // ==================== BUILDING ====================
+
+    #[test]
+    fn test_roadmap_samples_are_free() {
+        let prm = grid_prm(PrmConfig::new(200, 8), 1);
+        assert_eq!(prm.nodes().len(), 200);
+        for &p in prm.nodes() {
+            assert!(prm.world().is_free(p));
+        }
+    }
+
+    #[test]
+    fn test_edges_are_unique_and_checked() {
+        let prm = grid_prm(PrmConfig::new(150, 6), 2);
+        let mut seen = alloc::vec::Vec::new();
+        for e in prm.edges() {
+            let key = (e.a.min(e.b), e.a.max(e.b));
+            assert!(!seen.contains(&key));
+            seen.push(key);
+            let free = prm.world().segment_free(prm.nodes()[e.a], prm.nodes()[e.b]);
+            let expected = if free {
+                EdgeState::Free
+            } else {
+                EdgeState::Blocked
+            };
+            assert_eq!(e.state, expected);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_roadmap() {
+        let a = grid_prm(PrmConfig::new(100, 5), 7);
+        let b = grid_prm(PrmConfig::new(100, 5), 7);
+        assert_eq!(a.nodes(), b.nodes());
+        assert_eq!(a.edges(), b.edges());
+    }
+
+    // ==================== QUERIES ====================
+
+    #[test]
+    fn test_multi_query_reuses_roadmap() {
+        let mut prm = grid_prm(PrmConfig::new(300, 10), 3);
+        let nodes = prm.nodes().len();
+        let edges = prm.edges().len();
+        let queries = [
+            (Vec2::new(2.5, 2.5), Vec2::new(17.5, 2.5)),
+            (Vec2::new(1.5, 18.5), Vec2::new(18.5, 10.5)),
+            (Vec2::new(5.5, 5.5), Vec2::new(6.5, 12.5)),
+        ];
+        for (start, goal) in queries {
+            let path = prm.query(start, goal).expect("query should succeed");
+            assert_eq!(path[0], start);
+            assert_eq!(*path.last().unwrap(), goal);
+            assert_path_free(prm.world(), &path);
+        }
+        assert_eq!(prm.nodes().len(), nodes);
+        assert_eq!(prm.edges().len(), edges);
+    }
+
+    #[test]
+    fn test_direct_query() {
+        let mut prm = grid_prm(PrmConfig::new(50, 5), 4);
+        let path = prm.query(Vec2::new(2.5, 2.5), Vec2::new(4.5, 6.5)).unwrap();
+        assert_eq!(path.len(), 2);
+    }
+
+    #[test]
+    fn test_query_from_obstacle_fails() {
+        let mut prm = grid_prm(PrmConfig::new(50, 5), 4);
+        assert!(prm
+            .query(Vec2::new(10.5, 2.5), Vec2::new(4.5, 6.5))
+            .is_none());
+    }
+
+    #[test]
+    fn test_polygon_world() {
+        let mut world = ObstacleSet::new();
+        world
+            .polygons
+            .push(Polygon::rect(Vec2::new(4.0, 0.0), Vec2::new(6.0, 8.0)));
+        world.circles.push(Circle::new(Vec2::new(5.0, 10.0), 1.0));
+        let mut prm = Prm::new(
+            world,
+            PrmConfig::new(200, 8),
+            Vec2::zero(),
+            Vec2::new(10.0, 12.0),
+            5,
+        );
+        let path = prm.query(Vec2::new(1.0, 1.0), Vec2::new(9.0, 1.0)).unwrap();
+        assert_path_free(prm.world(), &path);
+        assert!(path.iter().any(|p| p.y > 8.0));
+    }
+
+    // ==================== LAZY PRM ====================
+
+    #[test]
+    fn test_lazy_build_skips_checks() {
+        let eager = grid_prm(PrmConfig::new(200, 8), 6);
+        let lazy = grid_prm(PrmConfig::lazy(200, 8), 6);
+        assert!(eager.collision_checks > 0);
+        assert_eq!(lazy.collision_checks, 0);
+        assert!(lazy.edges().iter().all(|e| e.state == EdgeState::Unchecked));
+    }
+
+    #[test]
+    fn test_lazy_query_matches_eager_validity() {
+        let mut lazy = grid_prm(PrmConfig::lazy(300, 10), 8);
+        let path = lazy
+            .query(Vec2::new(2.5, 2.5), Vec2::new(17.5, 2.5))
+            .unwrap();
+        assert_path_free(lazy.world(), &path);
+        // Only a fraction of the roadmap needed checking
+        assert!(lazy.collision_checks < lazy.edges().len());
+    }
+
+    #[test]
+    fn test_set_obstacle_invalidates_and_replans() {
+        let mut prm = grid_prm(PrmConfig::new(300, 10), 9);
+        let start = Vec2::new(2.5, 2.5);
+        let goal = Vec2::new(17.5, 2.5);
+        assert!(prm.query(start, goal).is_some());
+
+        // Closing the gap separates the two halves
+        let invalidated = prm.set_obstacle(10, 16, true) + prm.set_obstacle(10, 17, true);
+        assert!(invalidated > 0);
+        assert!(prm.query(start, goal).is_none());
+
+        // Reopening it brings the blocked edges back
+        prm.set_obstacle(10, 16, false);
+        prm.set_obstacle(10, 17, false);
+        let path = prm.query(start, goal).unwrap();
+        assert_path_free(prm.world(), &path);
+    }
+
+    #[test]
+    fn test_set_obstacle_on_path_forces_detour() {
+        let mut prm = Prm::new(
+            GridMap::new(20, 20),
+            PrmConfig::new(300, 10),
+            Vec2::zero(),
+            Vec2::new(20.0, 20.0),
+            10,
+        );
+        let start = Vec2::new(2.5, 10.5);
+        let goal = Vec2::new(17.5, 10.5);
+        let before = prm.query(start, goal).unwrap();
+        for y in 4..17 {
+            prm.set_obstacle(10, y, true);
+        }
+        let after = prm.query(start, goal).unwrap();
+        assert_path_free(prm.world(), &after);
+        assert!(after.len() > before.len());
+    }
+
+    #[test]
+    fn test_set_obstacle_noop() {
+        let mut prm = grid_prm(PrmConfig::new(50, 5), 11);
+        assert_eq!(prm.set_obstacle(10, 0, true), 0);
+        assert_eq!(prm.set_obstacle(99, 0, true), 0);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::dubins::{sample_many, sample_segments, Steer};
+use crate::math::{wrap_angle, Pose};
+use alloc::vec::Vec;
+use core::f32::consts::{FRAC_PI_2, PI};
+
+const ZERO: f32 = 1e-5;
+
+use Steer::{Left as L, Right as R, Straight as S};
+
+fn polar(x: f32, y: f32) -> (f32, f32) {
+    (libm::sqrtf(x * x + y * y), libm::atan2f(y, x))
+}
+
+fn tau_omega(u: f32, v: f32, xi: f32, eta: f32, phi: f32) -> (f32, f32) {
+    let delta = wrap_angle(u - v);
+    let a = libm::sinf(u) - libm::sinf(delta);
+    let b = libm::cosf(u) - libm::cosf(delta) - 1.0;
+    let t1 = libm::atan2f(eta * a - xi * b, xi * a + eta * b);
+    let t2 = 2.0 * (libm::cosf(delta) - libm::cosf(v) - libm::cosf(u)) + 3.0;
+    let tau = if t2 < 0.0 {
+        wrap_angle(t1 + PI)
+    } else {
+        wrap_angle(t1)
+    };
+    (tau, wrap_angle(tau - u + v - phi))
+}
+
+// The base formulas of Reeds & Shepp (1990), numbered as in the paper, for a
+// goal at (x, y, phi) relative to the start with unit turning radius. Each
+// returns the signed segment parameters (t, u, v).
+
+// 8.1
+fn lp_sp_lp(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let (u, t) = polar(x - libm::sinf(phi), y - 1.0 + libm::cosf(phi));
+    if t >= -ZERO {
+        let v = wrap_angle(phi - t);
+        if v >= -ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+// 8.2
+fn lp_sp_rp(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let (u1, t1) = polar(x + libm::sinf(phi), y - 1.0 - libm::cosf(phi));
+    let u1 = u1 * u1;
+    if u1 >= 4.0 {
+        let u = libm::sqrtf(u1 - 4.0);
+        let t = wrap_angle(t1 + libm::atan2f(2.0, u));
+        let v = wrap_angle(t - phi);
+        if t >= -ZERO && v >= -ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+// 8.3 / 8.4
+fn lp_rm_l(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let (u1, theta) = polar(x - libm::sinf(phi), y - 1.0 + libm::cosf(phi));
+    if u1 <= 4.0 {
+        let u = -2.0 * libm::asinf(0.25 * u1);
+        let t = wrap_angle(theta + 0.5 * u + PI);
+        let v = wrap_angle(phi - t + u);
+        if t >= -ZERO && u <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+// 8.7
+fn lp_rup_lum_rm(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let xi = x + libm::sinf(phi);
+    let eta = y - 1.0 - libm::cosf(phi);
+    let rho = 0.25 * (2.0 + libm::sqrtf(xi * xi + eta * eta));
+    if rho <= 1.0 {
+        let u = libm::acosf(rho);
+        let (t, v) = tau_omega(u, -u, xi, eta, phi);
+        if t >= -ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+// 8.8
+fn lp_rum_lum_rp(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let xi = x + libm::sinf(phi);
+    let eta = y - 1.0 - libm::cosf(phi);
+    let rho = (20.0 - xi * xi - eta * eta) / 16.0;
+    if (0.0..=1.0).contains(&rho) {
+        let u = -libm::acosf(rho);
+        if u >= -FRAC_PI_2 {
+            let (t, v) = tau_omega(u, u, xi, eta, phi);
+            if t >= -ZERO && v >= -ZERO {
+                return Some((t, u, v));
+            }
+        }
+    }
+    None
+}
+
+// 8.9
+fn lp_rm_sm_lm(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let (rho, theta) = polar(x - libm::sinf(phi), y - 1.0 + libm::cosf(phi));
+    if rho >= 2.0 {
+        let r = libm::sqrtf(rho * rho - 4.0);
+        let u = 2.0 - r;
+        let t = wrap_angle(theta + libm::atan2f(r, -2.0));
+        let v = wrap_angle(phi - FRAC_PI_2 - t);
+        if t >= -ZERO && u <= ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+// 8.10
+fn lp_rm_sm_rm(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let xi = x + libm::sinf(phi);
+    let eta = y - 1.0 - libm::cosf(phi);
+    let (rho, theta) = polar(-eta, xi);
+    if rho >= 2.0 {
+        let t = theta;
+        let u = 2.0 - rho;
+        let v = wrap_angle(t + FRAC_PI_2 - phi);
+        if t >= -ZERO && u <= ZERO && v <= ZERO {
+            return Some((t, u, v));
+        }
+    }
+    None
+}
+
+// 8.11
+fn lp_rm_slm_rp(x: f32, y: f32, phi: f32) -> Option<(f32, f32, f32)> {
+    let xi = x + libm::sinf(phi);
+    let eta = y - 1.0 - libm::cosf(phi);
+    let (rho, _) = polar(xi, eta);
+    if rho >= 2.0 {
+        let u = 4.0 - libm::sqrtf(rho * rho - 4.0);
+        if u <= ZERO {
+            let t = wrap_angle(libm::atan2f(
+                (4.0 - u) * xi - 2.0 * eta,
+                -2.0 * xi + (u - 4.0) * eta,
+            ));
+            let v = wrap_angle(t - phi);
+            if t >= -ZERO && v >= -ZERO {
+                return Some((t, u, v));
+            }
+        }
+    }
+    None
+}
+
+type Formula = fn(f32, f32, f32) -> Option<(f32, f32, f32)>;
+
+// Collects the shortest candidate in unit-radius segments
+struct Best {
+    segments: Vec<(Steer, f32)>,
+    length: f32,
+}
+
+impl Best {
+    fn offer(&mut self, steer: &[Steer], lengths: &[f32]) {
+        let length: f32 = lengths.iter().map(|l| l.abs()).sum();
+        if length < self.length {
+            self.length = length;
+            self.segments = steer.iter().copied().zip(lengths.iter().copied()).collect();
+        }
+    }
+
+    // Try a formula under the four symmetries: identity, time flip,
+    // reflection, and both. `build` maps (t, u, v) to segment lengths.
+    fn symmetric(
+        &mut self,
+        formula: Formula,
+        (x, y, phi): (f32, f32, f32),
+        steer: &[Steer],
+        reflected: &[Steer],
+        build: impl Fn(f32, f32, f32) -> Vec<f32>,
+    ) {
+        if let Some((t, u, v)) = formula(x, y, phi) {
+            self.offer(steer, &build(t, u, v));
+        }
+        if let Some((t, u, v)) = formula(-x, y, -phi) {
+            let lengths: Vec<f32> = build(t, u, v).iter().map(|l| -l).collect();
+            self.offer(steer, &lengths);
+        }
+        if let Some((t, u, v)) = formula(x, -y, -phi) {
+            self.offer(reflected, &build(t, u, v));
+        }
+        if let Some((t, u, v)) = formula(-x, -y, phi) {
+            let lengths: Vec<f32> = build(t, u, v).iter().map(|l| -l).collect();
+            self.offer(reflected, &lengths);
+        }
+    }
+}
+
+/// Shortest path between two poses for a car that can drive forwards and
+/// backwards with a minimum turning radius.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReedsSheppPath {
+    pub start: Pose,
+    pub radius: f32,
+    /// Segments with signed lengths in world units; negative means reverse.
+    pub segments: Vec<(Steer, f32)>,
+}
+
+impl ReedsSheppPath {
+    /// Shortest Reeds-Shepp path, or `None` if `radius` is not positive.
+    pub fn shortest(start: Pose, goal: Pose, radius: f32) -> Option<Self> {
+        if radius <= 0.0 {
+            return None;
+        }
+        // Goal in the start frame, scaled to unit radius
+        let dx = goal.x - start.x;
+        let dy = goal.y - start.y;
+        let (s, c) = (libm::sinf(start.theta), libm::cosf(start.theta));
+        let x = (c * dx + s * dy) / radius;
+        let y = (-s * dx + c * dy) / radius;
+        let phi = wrap_angle(goal.theta - start.theta);
+        let goal = (x, y, phi);
+        // The "backwards" variants swap the roles of start and goal
+        let back = (
+            x * libm::cosf(phi) + y * libm::sinf(phi),
+            x * libm::sinf(phi) - y * libm::cosf(phi),
+            phi,
+        );
+
+        let mut best = Best {
+            segments: Vec::new(),
+            length: f32::INFINITY,
+        };
+        let h = FRAC_PI_2;
+
+        // CSC
+        best.symmetric(lp_sp_lp, goal, &[L, S, L], &[R, S, R], |t, u, v| {
+            alloc::vec![t, u, v]
+        });
+        best.symmetric(lp_sp_rp, goal, &[L, S, R], &[R, S, L], |t, u, v| {
+            alloc::vec![t, u, v]
+        });
+        // CCC
+        best.symmetric(lp_rm_l, goal, &[L, R, L], &[R, L, R], |t, u, v| {
+            alloc::vec![t, u, v]
+        });
+        best.symmetric(lp_rm_l, back, &[L, R, L], &[R, L, R], |t, u, v| {
+            alloc::vec![v, u, t]
+        });
+        // CCCC
+        best.symmetric(
+            lp_rup_lum_rm,
+            goal,
+            &[L, R, L, R],
+            &[R, L, R, L],
+            |t, u, v| alloc::vec![t, u, -u, v],
+        );
+        best.symmetric(
+            lp_rum_lum_rp,
+            goal,
+            &[L, R, L, R],
+            &[R, L, R, L],
+            |t, u, v| alloc::vec![t, u, u, v],
+        );
+        // CCSC
+        best.symmetric(
+            lp_rm_sm_lm,
+            goal,
+            &[L, R, S, L],
+            &[R, L, S, R],
+            |t, u, v| alloc::vec![t, -h, u, v],
+        );
+        best.symmetric(
+            lp_rm_sm_rm,
+            goal,
+            &[L, R, S, R],
+            &[R, L, S, L],
+            |t, u, v| alloc::vec![t, -h, u, v],
+        );
+        best.symmetric(
+            lp_rm_sm_lm,
+            back,
+            &[L, S, R, L],
+            &[R, S, L, R],
+            |t, u, v| alloc::vec![v, u, -h, t],
+        );
+        best.symmetric(
+            lp_rm_sm_rm,
+            back,
+            &[R, S, R, L],
+            &[L, S, L, R],
+            |t, u, v| alloc::vec![v, u, -h, t],
+        );
+        // CCSCC
+        best.symmetric(
+            lp_rm_slm_rp,
+            goal,
+            &[L, R, S, L, R],
+            &[R, L, S, R, L],
+            |t, u, v| alloc::vec![t, -h, u, -h, v],
+        );
+
+        let segments = best
+            .segments
+            .into_iter()
+            .filter(|&(_, l)| l.abs() > ZERO)
+            .map(|(steer, l)| (steer, l * radius))
+            .collect();
+        Some(Self {
+            start,
+            radius,
+            segments,
+        })
+    }
+
+    pub fn length(&self) -> f32 {
+        self.segments.iter().map(|s| s.1.abs()).sum()
+    }
+
+    /// Number of direction changes along the path.
+    pub fn cusps(&self) -> usize {
+        self.segments
+            .windows(2)
+            .filter(|w| (w[0].1 < 0.0) != (w[1].1 < 0.0))
+            .count()
+    }
+
+    /// Pose after driving `s` along the path (clamped to the path).
+    pub fn sample(&self, s: f32) -> Pose {
+        sample_segments(self.start, &self.segments, self.radius, s)
+    }
+
+    /// Poses every `step` along the path (at least 1e-3), including both
+    /// ends.
+    pub fn sample_many(&self, step: f32) -> Vec<Pose> {
+        sample_many(self.start, &self.segments, self.radius, step)
+    }
+
+    pub fn end(&self) -> Pose {
+        self.sample(self.length())
+    }
+}
+
+#[cfg(test)]
+#[path = "reeds_shepp_tests.rs"]
+mod tests;
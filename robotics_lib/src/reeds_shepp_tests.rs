@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::dubins::DubinsPath;
+    use crate::math::{wrap_angle, Pose};
+    use crate::reeds_shepp::ReedsSheppPath;
+    use crate::rng::Rng;
+    use core::f32::consts::PI;
+
+    fn assert_pose_near(a: Pose, b: Pose, tol: f32) {
+        assert!((a.x - b.x).abs() < tol, "{:?} vs {:?}", a, b);
+        assert!((a.y - b.y).abs() < tol, "{:?} vs {:?}", a, b);
+        assert!(
+            wrap_angle(a.theta - b.theta).abs() < tol,
+            "{:?} vs {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_straight_forward_and_back() {
+        let start = Pose::new(1.0, 1.0, 0.0);
+        let ahead = ReedsSheppPath::shortest(start, Pose::new(6.0, 1.0, 0.0), 1.0).unwrap();
+        assert!((ahead.length() - 5.0).abs() < 1e-4);
+        assert_eq!(ahead.cusps(), 0);
+
+        let behind = ReedsSheppPath::shortest(start, Pose::new(-4.0, 1.0, 0.0), 1.0).unwrap();
+        assert!((behind.length() - 5.0).abs() < 1e-4);
+        assert!(behind.segments.iter().all(|s| s.1 < 0.0));
+        assert_pose_near(behind.end(), Pose::new(-4.0, 1.0, 0.0), 1e-4);
+    }
+
+    #[test]
+    fn test_random_poses_reach_goal() {
+        let mut rng = Rng::new(17);
+        for _ in 0..500 {
+            let start = Pose::new(
+                rng.range(-6.0, 6.0),
+                rng.range(-6.0, 6.0),
+                rng.range(-PI, PI),
+            );
+            let goal = Pose::new(
+                rng.range(-6.0, 6.0),
+                rng.range(-6.0, 6.0),
+                rng.range(-PI, PI),
+            );
+            let radius = rng.range(0.5, 2.0);
+            let path = ReedsSheppPath::shortest(start, goal, radius).unwrap();
+            assert_pose_near(path.end(), goal, 3e-3);
+        }
+    }
+
+    #[test]
+    fn test_never_longer_than_dubins() {
+        let mut rng = Rng::new(23);
+        for _ in 0..300 {
+            let start = Pose::new(
+                rng.range(-4.0, 4.0),
+                rng.range(-4.0, 4.0),
+                rng.range(-PI, PI),
+            );
+            let goal = Pose::new(
+                rng.range(-4.0, 4.0),
+                rng.range(-4.0, 4.0),
+                rng.range(-PI, PI),
+            );
+            let rs = ReedsSheppPath::shortest(start, goal, 1.0).unwrap();
+            let dubins = DubinsPath::shortest(start, goal, 1.0).unwrap();
+            assert!(rs.length() <= dubins.length() + 1e-3);
+            assert!(rs.length() >= start.position().distance(&goal.position()) - 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_parallel_parking_uses_reverse() {
+        // Sideways shift by one radius: much shorter with a direction change
+        let start = Pose::new(0.0, 0.0, 0.0);
+        let goal = Pose::new(0.0, 1.0, 0.0);
+        let rs = ReedsSheppPath::shortest(start, goal, 1.0).unwrap();
+        let dubins = DubinsPath::shortest(start, goal, 1.0).unwrap();
+        assert!(rs.cusps() > 0);
+        assert!(rs.length() < 0.5 * dubins.length());
+        assert_pose_near(rs.end(), goal, 1e-3);
+    }
+
+    #[test]
+    fn test_same_pose_is_empty() {
+        let p = Pose::new(2.0, 3.0, 1.0);
+        let path = ReedsSheppPath::shortest(p, p, 1.0).unwrap();
+        assert!(path.length() < 1e-4);
+        assert_pose_near(path.sample_many(0.5)[0], p, 1e-6);
+    }
+
+    #[test]
+    fn test_scales_with_radius() {
+        let start = Pose::new(0.0, 0.0, 0.0);
+        let a = ReedsSheppPath::shortest(start, Pose::new(1.0, 2.0, 2.0), 1.0).unwrap();
+        let b = ReedsSheppPath::shortest(start, Pose::new(2.0, 4.0, 2.0), 2.0).unwrap();
+        assert!((2.0 * a.length() - b.length()).abs() < 1e-3);
+    }
+}
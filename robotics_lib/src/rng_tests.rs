@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_same_seed_same_stream() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let mut a = Rng::new(1);
+        let mut b = Rng::new(2);
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_zero_seed_works() {
+        let mut rng = Rng::new(0);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn test_next_f32_range_and_mean() {
+        let mut rng = Rng::new(7);
+        let mut sum = 0.0;
+        for _ in 0..10_000 {
+            let v = rng.next_f32();
+            assert!((0.0..1.0).contains(&v));
+            sum += v;
+        }
+        assert!((sum / 10_000.0 - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_range_and_index() {
+        let mut rng = Rng::new(9);
+        for _ in 0..1000 {
+            let v = rng.range(-3.0, 5.0);
+            assert!((-3.0..5.0).contains(&v));
+            assert!(rng.index(7) < 7);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_moments() {
+        let mut rng = Rng::new(11);
+        let n = 20_000;
+        let (mut sum, mut sum_sq) = (0.0f32, 0.0f32);
+        for _ in 0..n {
+            let v = rng.gaussian();
+            assert!(v.is_finite());
+            sum += v;
+            sum_sq += v * v;
+        }
+        let mean = sum / n as f32;
+        let var = sum_sq / n as f32 - mean * mean;
+        assert!(mean.abs() < 0.05);
+        assert!((var - 1.0).abs() < 0.05);
+    }
+}
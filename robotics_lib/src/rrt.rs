@@ -0,0 +1,257 @@
+use crate::collision::CollisionChecker;
+use crate::math::Vec2;
+use crate::rng::Rng;
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RrtConfig {
+    /// Longest edge added per iteration.
+    pub step_size: f32,
+    /// Probability of sampling the goal instead of a random point.
+    pub goal_bias: f32,
+    /// A node this close to the goal (with a free segment to it) reaches it.
+    pub goal_tolerance: f32,
+    pub max_iterations: usize,
+    /// `Some(radius)` turns on RRT* parent selection and rewiring.
+    pub rewire_radius: Option<f32>,
+}
+
+impl RrtConfig {
+    pub fn rrt(step_size: f32) -> Self {
+        Self {
+            step_size,
+            goal_bias: 0.05,
+            goal_tolerance: step_size,
+            max_iterations: 5000,
+            rewire_radius: None,
+        }
+    }
+
+    pub fn rrt_star(step_size: f32, rewire_radius: f32) -> Self {
+        Self {
+            rewire_radius: Some(rewire_radius),
+            ..Self::rrt(step_size)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TreeNode {
+    pub position: Vec2,
+    pub parent: Option<usize>,
+    /// Path length from the root.
+    pub cost: f32,
+}
+
+/// RRT / RRT* over a continuous rectangular region.
+///
+/// The tree is kept between calls to [`Rrt::step`], so a caller can draw it
+/// while it grows; [`Rrt::run`] just steps until the iteration budget is used
+/// (RRT* keeps improving) or, for plain RRT, until the goal is first reached.
+#[derive(Clone, Debug)]
+pub struct Rrt {
+    pub config: RrtConfig,
+    pub start: Vec2,
+    pub goal: Vec2,
+    pub bounds_min: Vec2,
+    pub bounds_max: Vec2,
+    pub iterations: usize,
+    nodes: Vec<TreeNode>,
+    children: Vec<Vec<usize>>,
+    goal_nodes: Vec<usize>,
+    rng: Rng,
+}
+
+impl Rrt {
+    pub fn new(
+        config: RrtConfig,
+        start: Vec2,
+        goal: Vec2,
+        bounds_min: Vec2,
+        bounds_max: Vec2,
+        seed: u64,
+    ) -> Self {
+        Self {
+            config,
+            start,
+            goal,
+            bounds_min,
+            bounds_max,
+            iterations: 0,
+            nodes: alloc::vec![TreeNode {
+                position: start,
+                parent: None,
+                cost: 0.0,
+            }],
+            children: alloc::vec![Vec::new()],
+            goal_nodes: Vec::new(),
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn tree(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    /// Tree edges as (parent, child) positions, ready for drawing.
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.nodes
+            .iter()
+            .filter_map(|n| n.parent.map(|p| (self.nodes[p].position, n.position)))
+    }
+
+    /// Run one sample/extend iteration. Returns the index of the added node.
+    pub fn step<C: CollisionChecker>(&mut self, checker: &C) -> Option<usize> {
+        self.iterations += 1;
+        let sample = if self.rng.next_f32() < self.config.goal_bias {
+            self.goal
+        } else {
+            Vec2::new(
+                self.rng.range(self.bounds_min.x, self.bounds_max.x),
+                self.rng.range(self.bounds_min.y, self.bounds_max.y),
+            )
+        };
+
+        let nearest = self.nearest(sample);
+        let from = self.nodes[nearest].position;
+        let offset = sample - from;
+        let dist = offset.mag();
+        if dist <= f32::EPSILON {
+            return None;
+        }
+        let new_pos = if dist > self.config.step_size {
+            from + offset * (self.config.step_size / dist)
+        } else {
+            sample
+        };
+        if !checker.is_free(new_pos) || !checker.segment_free(from, new_pos) {
+            return None;
+        }
+
+        let mut parent = nearest;
+        let mut cost = self.nodes[nearest].cost + from.distance(&new_pos);
+        let near = match self.config.rewire_radius {
+            Some(radius) => self.near(new_pos, radius),
+            None => Vec::new(),
+        };
+
+        // RRT*: choose the cheapest visible parent among the neighbours
+        for &i in &near {
+            let candidate = self.nodes[i].cost + self.nodes[i].position.distance(&new_pos);
+            if candidate < cost && checker.segment_free(self.nodes[i].position, new_pos) {
+                parent = i;
+                cost = candidate;
+            }
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(TreeNode {
+            position: new_pos,
+            parent: Some(parent),
+            cost,
+        });
+        self.children.push(Vec::new());
+        self.children[parent].push(id);
+
+        // RRT*: reroute neighbours through the new node when that is shorter
+        for &i in &near {
+            let through = cost + new_pos.distance(&self.nodes[i].position);
+            if through < self.nodes[i].cost && checker.segment_free(new_pos, self.nodes[i].position)
+            {
+                self.reparent(i, id, through);
+            }
+        }
+
+        if new_pos.distance(&self.goal) <= self.config.goal_tolerance
+            && checker.segment_free(new_pos, self.goal)
+        {
+            self.goal_nodes.push(id);
+        }
+        Some(id)
+    }
+
+    /// Step until the iteration budget is spent (or the goal is reached, for
+    /// plain RRT) and return the best path.
+    pub fn run<C: CollisionChecker>(&mut self, checker: &C) -> Option<Vec<Vec2>> {
+        while self.iterations < self.config.max_iterations {
+            self.step(checker);
+            if self.config.rewire_radius.is_none() && !self.goal_nodes.is_empty() {
+                break;
+            }
+        }
+        self.best_path()
+    }
+
+    /// Length of the best path found so far.
+    pub fn best_cost(&self) -> Option<f32> {
+        self.best_goal_node().map(|(_, cost)| cost)
+    }
+
+    /// Best path from start to goal found so far.
+    pub fn best_path(&self) -> Option<Vec<Vec2>> {
+        let (mut node, _) = self.best_goal_node()?;
+        let mut path = alloc::vec![self.goal];
+        loop {
+            let n = &self.nodes[node];
+            if n.position != self.goal {
+                path.push(n.position);
+            }
+            match n.parent {
+                Some(p) => node = p,
+                None => break,
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    fn best_goal_node(&self) -> Option<(usize, f32)> {
+        self.goal_nodes
+            .iter()
+            .map(|&i| {
+                (
+                    i,
+                    self.nodes[i].cost + self.nodes[i].position.distance(&self.goal),
+                )
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    fn nearest(&self, p: Vec2) -> usize {
+        let mut best = (0, f32::INFINITY);
+        for (i, n) in self.nodes.iter().enumerate() {
+            let d = n.position.distance_sq(&p);
+            if d < best.1 {
+                best = (i, d);
+            }
+        }
+        best.0
+    }
+
+    fn near(&self, p: Vec2, radius: f32) -> Vec<usize> {
+        let r_sq = radius * radius;
+        (0..self.nodes.len())
+            .filter(|&i| self.nodes[i].position.distance_sq(&p) <= r_sq)
+            .collect()
+    }
+
+    fn reparent(&mut self, node: usize, new_parent: usize, cost: f32) {
+        if let Some(old) = self.nodes[node].parent {
+            self.children[old].retain(|&c| c != node);
+        }
+        self.nodes[node].parent = Some(new_parent);
+        self.children[new_parent].push(node);
+
+        // Shift the whole subtree by the same saving
+        let delta = self.nodes[node].cost - cost;
+        let mut stack = alloc::vec![node];
+        while let Some(i) = stack.pop() {
+            self.nodes[i].cost -= delta;
+            stack.extend_from_slice(&self.children[i]);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "rrt_tests.rs"]
+mod tests;
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::{Circle, CollisionChecker, ObstacleSet, Polygon};
+    use crate::dstar::GridMap;
+    use crate::math::Vec2;
+    use crate::rrt::{Rrt, RrtConfig};
+    use alloc::vec::Vec;
+
+    fn path_length(path: &[Vec2]) -> f32 {
+        path.windows(2).map(|w| w[0].distance(&w[1])).sum()
+    }
+
+    fn assert_path_free<C: CollisionChecker>(checker: &C, path: &[Vec2]) {
+        for w in path.windows(2) {
+            assert!(checker.segment_free(w[0], w[1]), "{:?} -> {:?}", w[0], w[1]);
+        }
+    }
+
+    fn wall_world() -> ObstacleSet {
+        let mut world = ObstacleSet::new();
+        world
+            .polygons
+            .push(Polygon::rect(Vec2::new(9.0, 0.0), Vec2::new(11.0, 15.0)));
+        world.circles.push(Circle::new(Vec2::new(15.0, 14.0), 2.0));
+        world
+    }
+
+    fn planner(config: RrtConfig, seed: u64) -> Rrt {
+        Rrt::new(
+            config,
+            Vec2::new(2.0, 2.0),
+            Vec2::new(18.0, 2.0),
+            Vec2::zero(),
+            Vec2::new(20.0, 20.0),
+            seed,
+        )
+    }
+
+    // ==================== RRT ====================
+
+    #[test]
+    fn test_rrt_finds_path_around_wall() {
+        let world = wall_world();
+        let mut rrt = planner(RrtConfig::rrt(1.0), 1);
+        let path = rrt.run(&world).expect("RRT should reach the goal");
+        assert_eq!(path[0], Vec2::new(2.0, 2.0));
+        assert_eq!(*path.last().unwrap(), Vec2::new(18.0, 2.0));
+        assert_path_free(&world, &path);
+        assert!(path.iter().any(|p| p.y > 15.0));
+    }
+
+    #[test]
+    fn test_rrt_on_gridmap() {
+        let mut map = GridMap::new(20, 20);
+        for y in 0..15 {
+            map.set_obstacle(10, y, true);
+        }
+        let mut rrt = planner(RrtConfig::rrt(1.0), 3);
+        let path = rrt.run(&map).unwrap();
+        assert_path_free(&map, &path);
+    }
+
+    #[test]
+    fn test_rrt_same_seed_same_tree() {
+        let world = wall_world();
+        let mut a = planner(RrtConfig::rrt(1.0), 5);
+        let mut b = planner(RrtConfig::rrt(1.0), 5);
+        a.run(&world);
+        b.run(&world);
+        assert_eq!(a.tree(), b.tree());
+    }
+
+    #[test]
+    fn test_tree_edges_respect_step_and_collisions() {
+        let world = wall_world();
+        let mut rrt = planner(RrtConfig::rrt(1.5), 9);
+        for _ in 0..500 {
+            rrt.step(&world);
+        }
+        assert!(rrt.tree().len() > 1);
+        assert_eq!(rrt.edges().count(), rrt.tree().len() - 1);
+        for (a, b) in rrt.edges() {
+            assert!(a.distance(&b) <= 1.5 + 1e-4);
+            assert!(world.segment_free(a, b));
+        }
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let mut world = ObstacleSet::new();
+        world.circles.push(Circle::new(Vec2::new(18.0, 2.0), 3.0));
+        let mut config = RrtConfig::rrt(1.0);
+        config.max_iterations = 500;
+        let mut rrt = planner(config, 2);
+        assert!(rrt.run(&world).is_none());
+        assert!(rrt.best_cost().is_none());
+        assert_eq!(rrt.iterations, 500);
+    }
+
+    // ==================== RRT* ====================
+
+    #[test]
+    fn test_rrt_star_costs_are_consistent() {
+        let world = wall_world();
+        let mut config = RrtConfig::rrt_star(1.0, 3.0);
+        config.max_iterations = 1500;
+        let mut rrt = planner(config, 4);
+        rrt.run(&world);
+        let nodes = rrt.tree();
+        for n in nodes {
+            if let Some(p) = n.parent {
+                let expected = nodes[p].cost + nodes[p].position.distance(&n.position);
+                assert!((n.cost - expected).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rrt_star_shorter_than_rrt() {
+        let world = ObstacleSet::new();
+        let mut rrt_total = 0.0;
+        let mut star_total = 0.0;
+        for seed in 0..5 {
+            let mut rrt = planner(RrtConfig::rrt(1.0), seed);
+            rrt_total += path_length(&rrt.run(&world).unwrap());
+
+            let mut config = RrtConfig::rrt_star(1.0, 3.0);
+            config.max_iterations = 1500;
+            let mut star = planner(config, seed);
+            let path = star.run(&world).unwrap();
+            assert!((path_length(&path) - star.best_cost().unwrap()).abs() < 1e-3);
+            star_total += path_length(&path);
+        }
+        assert!(star_total < rrt_total);
+        // Straight-line distance is 16; RRT* should get close
+        assert!(star_total / 5.0 < 17.5);
+    }
+
+    #[test]
+    fn test_rrt_star_improves_over_time() {
+        let world = wall_world();
+        let mut config = RrtConfig::rrt_star(1.0, 3.0);
+        config.max_iterations = usize::MAX;
+        let mut rrt = planner(config, 8);
+        let mut costs = Vec::new();
+        for _ in 0..6 {
+            for _ in 0..400 {
+                rrt.step(&world);
+            }
+            if let Some(c) = rrt.best_cost() {
+                costs.push(c);
+            }
+        }
+        assert!(!costs.is_empty());
+        for w in costs.windows(2) {
+            assert!(w[1] <= w[0] + 1e-4);
+        }
+        assert_path_free(&world, &rrt.best_path().unwrap());
+    }
+}
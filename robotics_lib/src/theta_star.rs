@@ -0,0 +1,158 @@
+use crate::dstar::GridMap;
+use crate::grid_search::{reconstruct, Connectivity, GridSearch, Heuristic, MinScored, Path};
+use alloc::collections::BinaryHeap;
+
+/// True if the straight segment between two cell centres only crosses free
+/// cells. Uses a supercover traversal, so every touched cell is checked; a
+/// segment passing exactly through a corner needs both side cells free.
+pub fn line_of_sight(map: &GridMap, a: (usize, usize), b: (usize, usize)) -> bool {
+    let nx = (b.0 as i64 - a.0 as i64).abs();
+    let ny = (b.1 as i64 - a.1 as i64).abs();
+    let sx: i64 = if b.0 >= a.0 { 1 } else { -1 };
+    let sy: i64 = if b.1 >= a.1 { 1 } else { -1 };
+    let blocked = |x: i64, y: i64| x < 0 || y < 0 || map.is_obstacle(x as usize, y as usize);
+
+    let (mut x, mut y) = (a.0 as i64, a.1 as i64);
+    if blocked(x, y) {
+        return false;
+    }
+    let (mut ix, mut iy) = (0, 0);
+    while ix < nx || iy < ny {
+        // Compare where the segment crosses the next vertical vs horizontal edge
+        let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+        if decision == 0 {
+            if blocked(x + sx, y) || blocked(x, y + sy) {
+                return false;
+            }
+            x += sx;
+            y += sy;
+            ix += 1;
+            iy += 1;
+        } else if decision < 0 {
+            x += sx;
+            ix += 1;
+        } else {
+            y += sy;
+            iy += 1;
+        }
+        if blocked(x, y) {
+            return false;
+        }
+    }
+    true
+}
+
+fn euclidean(a: (usize, usize), b: (usize, usize)) -> f32 {
+    let dx = a.0 as f32 - b.0 as f32;
+    let dy = a.1 as f32 - b.1 as f32;
+    libm::sqrtf(dx * dx + dy * dy)
+}
+
+/// Any-angle planner: A* whose nodes may take any visible ancestor as parent.
+///
+/// Paths are cell-centre waypoints joined by straight segments, with `cost`
+/// the Euclidean length in cells. Cells below `LETHAL` are treated as free.
+/// Lazy Theta* defers line-of-sight checks until a node is expanded, which
+/// saves most of them at the price of slightly longer paths in rare cases.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThetaStar {
+    pub lazy: bool,
+}
+
+impl ThetaStar {
+    pub fn new() -> Self {
+        Self { lazy: false }
+    }
+
+    pub fn lazy() -> Self {
+        Self { lazy: true }
+    }
+
+    pub fn plan(&self, map: &GridMap, start: (usize, usize), goal: (usize, usize)) -> Option<Path> {
+        if map.is_obstacle(start.0, start.1) || map.is_obstacle(goal.0, goal.1) {
+            return None;
+        }
+
+        let n = map.width * map.height;
+        let mut g = alloc::vec![f32::INFINITY; n];
+        let mut parent = alloc::vec![usize::MAX; n];
+        let mut closed = alloc::vec![false; n];
+        let mut open = BinaryHeap::new();
+        let mut expansions = 0;
+
+        let coords = |i: usize| (i % map.width, i / map.width);
+        let grid = GridSearch::new(Connectivity::Eight, Heuristic::Euclidean);
+
+        let start_idx = map.index(start.0, start.1);
+        let goal_idx = map.index(goal.0, goal.1);
+        g[start_idx] = 0.0;
+        parent[start_idx] = start_idx;
+        open.push(MinScored::new(euclidean(start, goal), start_idx));
+
+        while let Some(MinScored { item: current, .. }) = open.pop() {
+            if closed[current] {
+                continue;
+            }
+            let pos = coords(current);
+
+            if self.lazy
+                && parent[current] != current
+                && !line_of_sight(map, coords(parent[current]), pos)
+            {
+                // Assumed shortcut was blocked: fall back to the best closed neighbour
+                let mut best = (f32::INFINITY, usize::MAX);
+                for (next, _) in grid.neighbors(map, pos.0, pos.1) {
+                    let idx = map.index(next.0, next.1);
+                    let cost = g[idx] + euclidean(next, pos);
+                    if closed[idx] && cost < best.0 {
+                        best = (cost, idx);
+                    }
+                }
+                g[current] = best.0;
+                parent[current] = best.1;
+            }
+
+            closed[current] = true;
+            if current == goal_idx {
+                parent[start_idx] = usize::MAX;
+                return Some(Path {
+                    cells: reconstruct(map, &parent, current),
+                    cost: g[current],
+                    expansions,
+                });
+            }
+            expansions += 1;
+
+            for (next, _) in grid.neighbors(map, pos.0, pos.1) {
+                let idx = map.index(next.0, next.1);
+                if closed[idx] {
+                    continue;
+                }
+                let grandparent = parent[current];
+                let (via, cost) = if self.lazy || line_of_sight(map, coords(grandparent), next) {
+                    (
+                        grandparent,
+                        g[grandparent] + euclidean(coords(grandparent), next),
+                    )
+                } else {
+                    (current, g[current] + euclidean(pos, next))
+                };
+                if cost < g[idx] {
+                    g[idx] = cost;
+                    parent[idx] = via;
+                    open.push(MinScored {
+                        score: cost + euclidean(next, goal),
+                        tie: euclidean(next, goal),
+                        item: idx,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+#[path = "theta_star_tests.rs"]
+mod tests;
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::grid_search::{GridSearch, Path};
+    use crate::theta_star::{line_of_sight, ThetaStar};
+
+    fn segment_length(path: &Path) -> f32 {
+        path.cells
+            .windows(2)
+            .map(|w| {
+                let dx = w[0].0 as f32 - w[1].0 as f32;
+                let dy = w[0].1 as f32 - w[1].1 as f32;
+                libm::sqrtf(dx * dx + dy * dy)
+            })
+            .sum()
+    }
+
+    fn assert_visible(map: &GridMap, path: &Path) {
+        for w in path.cells.windows(2) {
+            assert!(line_of_sight(map, w[0], w[1]), "{:?} -> {:?}", w[0], w[1]);
+        }
+    }
+
+    fn pillar_map() -> GridMap {
+        let mut map = GridMap::new(30, 30);
+        for x in 10..20 {
+            for y in 8..22 {
+                map.set_obstacle(x, y, true);
+            }
+        }
+        map
+    }
+
+    // ==================== LINE OF SIGHT ====================
+
+    #[test]
+    fn test_los_open_map() {
+        let map = GridMap::new(10, 10);
+        assert!(line_of_sight(&map, (0, 0), (9, 4)));
+        assert!(line_of_sight(&map, (9, 4), (0, 0)));
+        assert!(line_of_sight(&map, (3, 3), (3, 3)));
+    }
+
+    #[test]
+    fn test_los_blocked_by_wall() {
+        let mut map = GridMap::new(10, 10);
+        map.set_obstacle(5, 2, true);
+        assert!(!line_of_sight(&map, (0, 2), (9, 2)));
+        assert!(line_of_sight(&map, (0, 3), (9, 3)));
+    }
+
+    #[test]
+    fn test_los_supercover_catches_grazed_cells() {
+        let mut map = GridMap::new(10, 10);
+        // Segment (0,0)->(4,1) passes through (2,0) and (2,1)
+        map.set_obstacle(2, 1, true);
+        assert!(!line_of_sight(&map, (0, 0), (4, 1)));
+    }
+
+    #[test]
+    fn test_los_no_corner_squeeze() {
+        let mut map = GridMap::new(4, 4);
+        map.set_obstacle(1, 0, true);
+        assert!(!line_of_sight(&map, (0, 0), (1, 1)));
+        assert!(!line_of_sight(&map, (0, 0), (3, 3)));
+        assert!(line_of_sight(&map, (0, 1), (3, 3)));
+    }
+
+    #[test]
+    fn test_los_out_of_bounds() {
+        let map = GridMap::new(5, 5);
+        assert!(!line_of_sight(&map, (0, 0), (7, 0)));
+    }
+
+    // ==================== THETA* ====================
+
+    #[test]
+    fn test_open_map_is_straight_line() {
+        let map = GridMap::new(20, 20);
+        for planner in [ThetaStar::new(), ThetaStar::lazy()] {
+            let path = planner.plan(&map, (0, 0), (19, 7)).unwrap();
+            assert_eq!(path.cells, [(0, 0), (19, 7)]);
+            let straight = libm::sqrtf(19.0 * 19.0 + 7.0 * 7.0);
+            assert!((path.cost - straight).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_start_equals_goal() {
+        let map = GridMap::new(5, 5);
+        let path = ThetaStar::new().plan(&map, (2, 2), (2, 2)).unwrap();
+        assert_eq!(path.cells, [(2, 2)]);
+        assert_eq!(path.cost, 0.0);
+    }
+
+    #[test]
+    fn test_paths_are_visible_and_consistent() {
+        let map = pillar_map();
+        for planner in [ThetaStar::new(), ThetaStar::lazy()] {
+            let path = planner.plan(&map, (2, 15), (27, 15)).unwrap();
+            assert_eq!(path.cells[0], (2, 15));
+            assert_eq!(*path.cells.last().unwrap(), (27, 15));
+            assert_visible(&map, &path);
+            assert!((segment_length(&path) - path.cost).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_shorter_than_grid_path() {
+        let map = pillar_map();
+        let grid = GridSearch::default().plan(&map, (2, 15), (27, 15)).unwrap();
+        let theta = ThetaStar::new().plan(&map, (2, 15), (27, 15)).unwrap();
+        assert!(theta.cost < grid.cost);
+        assert!(theta.cells.len() < grid.cells.len());
+    }
+
+    #[test]
+    fn test_lazy_close_to_eager() {
+        let map = pillar_map();
+        let eager = ThetaStar::new().plan(&map, (0, 0), (29, 29)).unwrap();
+        let lazy = ThetaStar::lazy().plan(&map, (0, 0), (29, 29)).unwrap();
+        assert!((lazy.cost - eager.cost).abs() < 0.05 * eager.cost);
+    }
+
+    #[test]
+    fn test_unreachable_and_blocked() {
+        let mut map = GridMap::new(10, 10);
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        assert!(ThetaStar::new().plan(&map, (0, 0), (9, 9)).is_none());
+        assert!(ThetaStar::lazy().plan(&map, (0, 0), (9, 9)).is_none());
+        assert!(ThetaStar::new().plan(&map, (5, 5), (0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_maze_like_map() {
+        let mut map = GridMap::new(25, 25);
+        for y in 0..20 {
+            map.set_obstacle(6, y, true);
+            map.set_obstacle(18, y, true);
+        }
+        for y in 5..25 {
+            map.set_obstacle(12, y, true);
+        }
+        for planner in [ThetaStar::new(), ThetaStar::lazy()] {
+            let path = planner.plan(&map, (0, 0), (24, 0)).unwrap();
+            assert_visible(&map, &path);
+            let grid = GridSearch::default().plan(&map, (0, 0), (24, 0)).unwrap();
+            assert!(path.cost <= grid.cost + 1e-3);
+        }
+    }
+}
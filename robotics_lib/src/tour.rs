@@ -0,0 +1,240 @@
+use crate::dstar::GridMap;
+use crate::grid_search::GridSearch;
+use alloc::vec::Vec;
+
+type Cell = (usize, usize);
+
+// Smallest change counted as an improvement
+const IMPROVEMENT_EPS: f32 = 1e-4;
+
+/// Pairwise travel costs between points, `costs[i][j]` from `points[i]` to
+/// `points[j]` and `f32::INFINITY` where no path exists. One Dijkstra cost
+/// field per point instead of a search per pair.
+pub fn cost_matrix(search: &GridSearch, map: &GridMap, points: &[Cell]) -> Vec<Vec<f32>> {
+    points
+        .iter()
+        .map(|&from| {
+            // Move costs are symmetric, so the field to `from` is the cost from it
+            let field = search.cost_field(map, from);
+            points
+                .iter()
+                .map(|&to| {
+                    if to.0 < map.width && to.1 < map.height {
+                        field[map.index(to.0, to.1)]
+                    } else {
+                        f32::INFINITY
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Cost of the edge from `a` to `b`, nothing when the tour ends after `a`
+fn edge(costs: &[Vec<f32>], a: usize, b: Option<usize>) -> f32 {
+    b.map_or(0.0, |b| costs[a][b])
+}
+
+// Stop after the last point, or return to the first on a closed tour
+fn after(order: &[usize], i: usize, closed: bool) -> Option<usize> {
+    match order.get(i + 1) {
+        Some(&next) => Some(next),
+        None if closed => Some(order[0]),
+        None => None,
+    }
+}
+
+/// Cost of visiting `order`, plus the way back to `order[0]` if `closed`.
+pub fn tour_cost(costs: &[Vec<f32>], order: &[usize], closed: bool) -> f32 {
+    (0..order.len())
+        .map(|i| edge(costs, order[i], after(order, i, closed)))
+        .sum()
+}
+
+/// Nearest-neighbour construction from point 0: always go to the cheapest
+/// unvisited point next.
+pub fn nearest_neighbor(costs: &[Vec<f32>]) -> Vec<usize> {
+    let n = costs.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut visited = alloc::vec![false; n];
+    let mut order = alloc::vec![0];
+    visited[0] = true;
+    while order.len() < n {
+        let last = order[order.len() - 1];
+        let next = (0..n)
+            .filter(|&j| !visited[j])
+            .min_by(|&a, &b| costs[last][a].total_cmp(&costs[last][b]))
+            .unwrap();
+        visited[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+/// 2-opt: reverse the stretch `order[i..=j]` wherever that shortens the
+/// tour, until no reversal helps. `order[0]` stays first. Costs must be
+/// symmetric. Returns true if the tour changed.
+pub fn two_opt(costs: &[Vec<f32>], order: &mut [usize], closed: bool) -> bool {
+    let n = order.len();
+    let mut changed = false;
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..n {
+            for j in i + 1..n {
+                let (a, b, c) = (order[i - 1], order[i], order[j]);
+                let d = after(order, j, closed);
+                let delta = costs[a][c] + edge(costs, b, d) - costs[a][b] - edge(costs, c, d);
+                if delta < -IMPROVEMENT_EPS {
+                    order[i..=j].reverse();
+                    improved = true;
+                    changed = true;
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// Or-opt: move a run of one to three consecutive points, possibly
+/// reversed, to wherever it is cheaper, until no move helps.
+/// `order[0]` stays first. Returns true if the tour changed.
+pub fn or_opt(costs: &[Vec<f32>], order: &mut Vec<usize>, closed: bool) -> bool {
+    let n = order.len();
+    let mut changed = false;
+    let mut improved = true;
+    while improved {
+        improved = false;
+        'search: for len in 1..=3.min(n.saturating_sub(1)) {
+            for i in 1..=n - len {
+                let run = &order[i..i + len];
+                let (first, last) = (run[0], run[len - 1]);
+                let prev = order[i - 1];
+                let next = after(order, i + len - 1, closed);
+                let removed =
+                    costs[prev][first] + edge(costs, last, next) - edge(costs, prev, next);
+                let rest: Vec<usize> = order[..i]
+                    .iter()
+                    .chain(&order[i + len..])
+                    .copied()
+                    .collect();
+                for q in 0..rest.len() {
+                    let (a, b) = (rest[q], after(&rest, q, closed));
+                    for reversed in [false, true] {
+                        let (head, tail) = if reversed {
+                            (last, first)
+                        } else {
+                            (first, last)
+                        };
+                        let added = costs[a][head] + edge(costs, tail, b) - edge(costs, a, b);
+                        if added - removed < -IMPROVEMENT_EPS {
+                            let mut run = run.to_vec();
+                            if reversed {
+                                run.reverse();
+                            }
+                            *order = rest[..=q]
+                                .iter()
+                                .chain(&run)
+                                .chain(&rest[q + 1..])
+                                .copied()
+                                .collect();
+                            improved = true;
+                            changed = true;
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// An ordered multi-waypoint route.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tour {
+    /// Visiting order as indices into the waypoints given.
+    pub order: Vec<usize>,
+    /// Travel cost of the whole route.
+    pub cost: f32,
+    /// Cells from the start through every waypoint in order, and back to
+    /// the start on a round trip.
+    pub path: Vec<Cell>,
+}
+
+/// Visit-order planning for a list of waypoints from a fixed start.
+///
+/// Builds the cost matrix with `search`, orders the waypoints by nearest
+/// neighbour and improves the order with 2-opt and Or-opt until neither
+/// helps, then stitches the legs into one path with `search`.
+#[derive(Clone, Copy, Debug)]
+pub struct TourPlanner {
+    pub search: GridSearch,
+    /// Come back to the start after the last waypoint.
+    pub return_to_start: bool,
+}
+
+impl Default for TourPlanner {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl TourPlanner {
+    pub fn new(return_to_start: bool) -> Self {
+        Self {
+            search: GridSearch::default(),
+            return_to_start,
+        }
+    }
+
+    /// Order in which to visit `waypoints`, as indices into it, with the
+    /// route cost. None if a waypoint cannot be reached from the start.
+    pub fn order(
+        &self,
+        map: &GridMap,
+        start: Cell,
+        waypoints: &[Cell],
+    ) -> Option<(Vec<usize>, f32)> {
+        let mut points = alloc::vec![start];
+        points.extend_from_slice(waypoints);
+        let costs = cost_matrix(&self.search, map, &points);
+        if costs[0].iter().any(|c| !c.is_finite()) {
+            return None;
+        }
+        let mut order = nearest_neighbor(&costs);
+        let closed = self.return_to_start;
+        loop {
+            let a = two_opt(&costs, &mut order, closed);
+            let b = or_opt(&costs, &mut order, closed);
+            if !a && !b {
+                break;
+            }
+        }
+        let cost = tour_cost(&costs, &order, closed);
+        Some((order[1..].iter().map(|&i| i - 1).collect(), cost))
+    }
+
+    pub fn plan(&self, map: &GridMap, start: Cell, waypoints: &[Cell]) -> Option<Tour> {
+        let (order, cost) = self.order(map, start, waypoints)?;
+        let mut stops = alloc::vec![start];
+        stops.extend(order.iter().map(|&i| waypoints[i]));
+        if self.return_to_start {
+            stops.push(start);
+        }
+        let mut path = alloc::vec![start];
+        for leg in stops.windows(2) {
+            if leg[0] != leg[1] {
+                let cells = self.search.plan(map, leg[0], leg[1])?.cells;
+                path.extend_from_slice(&cells[1..]);
+            }
+        }
+        Some(Tour { order, cost, path })
+    }
+}
+
+#[cfg(test)]
+#[path = "tour_tests.rs"]
+mod tests;
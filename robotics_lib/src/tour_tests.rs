@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use crate::dstar::GridMap;
+    use crate::grid_search::GridSearch;
+    use crate::mapgen::random_rects;
+    use crate::rng::Rng;
+    use crate::tour::{cost_matrix, nearest_neighbor, or_opt, tour_cost, two_opt, TourPlanner};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    // Straight-line costs between points in the plane
+    fn euclidean(points: &[(f32, f32)]) -> Vec<Vec<f32>> {
+        points
+            .iter()
+            .map(|a| {
+                points
+                    .iter()
+                    .map(|b| libm::hypotf(a.0 - b.0, a.1 - b.1))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Exhaustive optimum with point 0 fixed first
+    fn brute_force(costs: &[Vec<f32>], closed: bool) -> f32 {
+        fn permute(
+            costs: &[Vec<f32>],
+            order: &mut Vec<usize>,
+            k: usize,
+            closed: bool,
+            best: &mut f32,
+        ) {
+            if k == order.len() {
+                *best = best.min(tour_cost(costs, order, closed));
+                return;
+            }
+            for i in k..order.len() {
+                order.swap(k, i);
+                permute(costs, order, k + 1, closed, best);
+                order.swap(k, i);
+            }
+        }
+        let mut order: Vec<usize> = (0..costs.len()).collect();
+        let mut best = f32::INFINITY;
+        permute(costs, &mut order, 1, closed, &mut best);
+        best
+    }
+
+    // ==================== COST MATRIX ====================
+
+    #[test]
+    fn test_cost_matrix_matches_planner() {
+        let generated = random_rects(25, 25, 0.2, 4, 2);
+        let map = &generated.map;
+        let points = vec![generated.start, generated.goal, (12, 3), (3, 20)];
+        let points: Vec<_> = points
+            .into_iter()
+            .filter(|&(x, y)| !map.is_obstacle(x, y))
+            .collect();
+        let search = GridSearch::default();
+        let costs = cost_matrix(&search, map, &points);
+        for i in 0..points.len() {
+            assert_eq!(costs[i][i], 0.0);
+            for j in 0..points.len() {
+                assert!((costs[i][j] - costs[j][i]).abs() < 1e-3);
+                if let Some(path) = search.plan(map, points[i], points[j]) {
+                    assert!((costs[i][j] - path.cost).abs() < 1e-3);
+                } else {
+                    assert!(costs[i][j].is_infinite());
+                }
+            }
+        }
+    }
+
+    // ==================== HEURISTICS ====================
+
+    #[test]
+    fn test_nearest_neighbor_follows_a_line() {
+        let costs = euclidean(&[(0.0, 0.0), (3.0, 0.0), (1.0, 0.0), (7.0, 0.0), (2.0, 0.0)]);
+        assert_eq!(nearest_neighbor(&costs), vec![0, 2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn test_two_opt_uncrosses() {
+        let costs = euclidean(&[(0.0, 0.0), (4.0, 4.0), (4.0, 0.0), (0.0, 4.0)]);
+        let mut order = vec![0, 1, 2, 3];
+        let before = tour_cost(&costs, &order, true);
+        assert!(two_opt(&costs, &mut order, true));
+        assert_eq!(order[0], 0);
+        assert!((tour_cost(&costs, &order, true) - 16.0).abs() < 1e-4);
+        assert!(tour_cost(&costs, &order, true) < before);
+        assert!(!two_opt(&costs, &mut order, true));
+    }
+
+    #[test]
+    fn test_or_opt_moves_stray_point() {
+        // 5 belongs between 1 and 2 on the way out
+        let costs = euclidean(&[
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (3.0, 0.0),
+            (4.0, 0.0),
+            (5.0, 0.0),
+            (2.0, 0.1),
+        ]);
+        let mut order = vec![0, 1, 2, 3, 4, 5];
+        assert!(or_opt(&costs, &mut order, false));
+        assert_eq!(order, vec![0, 1, 5, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_open_tour_does_not_pay_for_return() {
+        let costs = euclidean(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        assert!((tour_cost(&costs, &[0, 1, 2], false) - 2.0).abs() < 1e-6);
+        assert!((tour_cost(&costs, &[0, 1, 2], true) - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_improvement_near_optimal() {
+        let mut rng = Rng::new(4);
+        for _ in 0..10 {
+            let points: Vec<(f32, f32)> = (0..8)
+                .map(|_| (rng.range(0.0, 10.0), rng.range(0.0, 10.0)))
+                .collect();
+            let costs = euclidean(&points);
+            for closed in [false, true] {
+                let mut order = nearest_neighbor(&costs);
+                let greedy = tour_cost(&costs, &order, closed);
+                while two_opt(&costs, &mut order, closed) | or_opt(&costs, &mut order, closed) {}
+                let improved = tour_cost(&costs, &order, closed);
+                let best = brute_force(&costs, closed);
+                assert!(improved <= greedy + 1e-4);
+                assert!(improved <= best * 1.05, "{} vs {}", improved, best);
+                let mut sorted = order.clone();
+                sorted.sort();
+                assert_eq!(sorted, (0..8).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    // ==================== TOURS ====================
+
+    #[test]
+    fn test_tour_path_visits_waypoints_in_order() {
+        let generated = random_rects(30, 30, 0.15, 4, 9);
+        let map = &generated.map;
+        let mut rng = Rng::new(1);
+        let mut waypoints = Vec::new();
+        while waypoints.len() < 7 {
+            let cell = (rng.index(30), rng.index(30));
+            if crate::mapgen::connected(map, generated.start, cell) {
+                waypoints.push(cell);
+            }
+        }
+        let tour = TourPlanner::new(false)
+            .plan(map, generated.start, &waypoints)
+            .unwrap();
+        let mut sorted = tour.order.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..7).collect::<Vec<_>>());
+        assert_eq!(tour.path[0], generated.start);
+        assert_eq!(*tour.path.last().unwrap(), waypoints[tour.order[6]]);
+
+        // Waypoints appear along the path in the planned order
+        let mut from = 0;
+        for &i in &tour.order {
+            from += tour.path[from..]
+                .iter()
+                .position(|&c| c == waypoints[i])
+                .unwrap();
+        }
+        // Adjacent, free cells whose cost adds up to the tour cost
+        let mut cost = 0.0;
+        for w in tour.path.windows(2) {
+            let step = map.move_cost(w[0], w[1]);
+            assert!(step.is_finite());
+            cost += step;
+        }
+        assert!((cost - tour.cost).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_round_trip_returns_to_start() {
+        let map = GridMap::new(20, 20);
+        let waypoints = [(15, 2), (2, 15), (15, 15)];
+        let open = TourPlanner::new(false)
+            .plan(&map, (1, 1), &waypoints)
+            .unwrap();
+        let round = TourPlanner::new(true)
+            .plan(&map, (1, 1), &waypoints)
+            .unwrap();
+        assert_eq!(*round.path.last().unwrap(), (1, 1));
+        assert!(round.cost > open.cost);
+        // Around the square either way
+        assert_eq!(round.order[1], 2);
+        let expected = 2.0 * (13.0 + core::f32::consts::SQRT_2) + 26.0;
+        assert!((round.cost - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_unreachable_waypoint() {
+        let mut map = GridMap::new(10, 10);
+        for y in 0..10 {
+            map.set_obstacle(5, y, true);
+        }
+        let planner = TourPlanner::default();
+        assert!(planner.plan(&map, (1, 1), &[(3, 3), (8, 8)]).is_none());
+        assert!(planner.plan(&map, (1, 1), &[(3, 3), (5, 5)]).is_none());
+        let tour = planner.plan(&map, (1, 1), &[]).unwrap();
+        assert_eq!(tour.path, vec![(1, 1)]);
+        assert_eq!(tour.cost, 0.0);
+    }
+}
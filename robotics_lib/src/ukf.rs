@@ -0,0 +1,190 @@
+use crate::math::{wrap_angle, Matrix, Vector};
+use alloc::vec::Vec;
+
+/// Merwe scaled sigma point parameters.
+///
+/// `alpha` sets the spread of the points around the mean, `beta` folds in
+/// prior knowledge of the distribution (2 is optimal for Gaussians) and
+/// `kappa` is a secondary scaling term, usually 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SigmaPoints {
+    pub alpha: f32,
+    pub beta: f32,
+    pub kappa: f32,
+}
+
+impl Default for SigmaPoints {
+    // A small alpha gives huge opposing weights that f32 cannot resolve
+    fn default() -> Self {
+        Self::new(1.0, 2.0, 0.0)
+    }
+}
+
+impl SigmaPoints {
+    pub fn new(alpha: f32, beta: f32, kappa: f32) -> Self {
+        Self { alpha, beta, kappa }
+    }
+
+    /// Scaling lambda = alpha^2 (n + kappa) - n for `n` states.
+    pub fn lambda(&self, n: usize) -> f32 {
+        let n = n as f32;
+        self.alpha * self.alpha * (n + self.kappa) - n
+    }
+
+    /// Mean and covariance weights of the 2n + 1 points.
+    pub fn weights(&self, n: usize) -> (Vec<f32>, Vec<f32>) {
+        let lambda = self.lambda(n);
+        let spread = n as f32 + lambda;
+        let mut mean = alloc::vec![0.5 / spread; 2 * n + 1];
+        let mut cov = mean.clone();
+        mean[0] = lambda / spread;
+        cov[0] = mean[0] + 1.0 - self.alpha * self.alpha + self.beta;
+        (mean, cov)
+    }
+
+    /// The mean followed by the mean plus and minus each column of
+    /// sqrt((n + lambda) P). None if P is not positive definite.
+    pub fn generate<const N: usize>(
+        &self,
+        mean: &Vector<N>,
+        covariance: &Matrix<N, N>,
+    ) -> Option<Vec<Vector<N>>> {
+        let root = (covariance.symmetrize() * (N as f32 + self.lambda(N))).cholesky()?;
+        let mut points = alloc::vec![*mean];
+        for sign in [1.0, -1.0] {
+            for j in 0..N {
+                points.push(*mean + root.column(j) * sign);
+            }
+        }
+        Some(points)
+    }
+}
+
+// Weighted mean with angle components averaged on the circle, so points
+// either side of the +-PI seam average to the seam rather than to zero
+fn weighted_mean<const K: usize>(
+    points: &[Vector<K>],
+    weights: &[f32],
+    angles: &[bool; K],
+) -> Vector<K> {
+    Vector::from_array(core::array::from_fn(|k| {
+        if angles[k] {
+            let (s, c) = points
+                .iter()
+                .zip(weights)
+                .fold((0.0, 0.0), |(s, c), (p, w)| {
+                    (s + w * libm::sinf(p[k]), c + w * libm::cosf(p[k]))
+                });
+            libm::atan2f(s, c)
+        } else {
+            points.iter().zip(weights).map(|(p, w)| w * p[k]).sum()
+        }
+    }))
+}
+
+// a - b with angle components wrapped into (-PI, PI]
+fn residual<const K: usize>(a: &Vector<K>, b: &Vector<K>, angles: &[bool; K]) -> Vector<K> {
+    let mut d = *a - *b;
+    for (k, &angle) in angles.iter().enumerate() {
+        if angle {
+            d[k] = wrap_angle(d[k]);
+        }
+    }
+    d
+}
+
+/// Unscented Kalman filter with `N` states and `M` measurements.
+///
+/// Instead of linearising, the filter pushes a set of sigma points through
+/// the motion and measurement functions and fits a Gaussian to the
+/// results, which captures the mean and covariance of a nonlinear
+/// transform to second order without any Jacobians. States and
+/// measurement components flagged as angles are averaged on the circle and
+/// differenced with wrapping.
+#[derive(Clone, Copy, Debug)]
+pub struct UnscentedKalmanFilter<const N: usize, const M: usize> {
+    pub state: Vector<N>,
+    pub covariance: Matrix<N, N>,
+    pub process_noise: Matrix<N, N>,     // Q
+    pub measurement_noise: Matrix<M, M>, // R
+    pub sigma_points: SigmaPoints,
+    /// State components that are angles in radians.
+    pub state_angles: [bool; N],
+    /// Measurement components that are angles in radians.
+    pub measurement_angles: [bool; M],
+}
+
+impl<const N: usize, const M: usize> UnscentedKalmanFilter<N, M> {
+    pub fn new(
+        state: Vector<N>,
+        covariance: Matrix<N, N>,
+        process_noise: Matrix<N, N>,
+        measurement_noise: Matrix<M, M>,
+    ) -> Self {
+        Self {
+            state,
+            covariance,
+            process_noise,
+            measurement_noise,
+            sigma_points: SigmaPoints::default(),
+            state_angles: [false; N],
+            measurement_angles: [false; M],
+        }
+    }
+
+    /// Propagate the estimate through `motion`. Returns false, leaving the
+    /// estimate unchanged, if the covariance is not positive definite.
+    pub fn predict(&mut self, motion: impl Fn(&Vector<N>) -> Vector<N>) -> bool {
+        let Some(points) = self.sigma_points.generate(&self.state, &self.covariance) else {
+            return false;
+        };
+        let (wm, wc) = self.sigma_points.weights(N);
+        let moved: Vec<Vector<N>> = points.iter().map(&motion).collect();
+        let mean = weighted_mean(&moved, &wm, &self.state_angles);
+        let mut covariance = self.process_noise;
+        for (p, w) in moved.iter().zip(&wc) {
+            let d = residual(p, &mean, &self.state_angles);
+            covariance = covariance + d * d.transpose() * *w;
+        }
+        self.state = mean;
+        self.covariance = covariance.symmetrize();
+        true
+    }
+
+    /// Correct with measurement `z` of the model `measure`. Returns false,
+    /// leaving the estimate unchanged, if the covariance is not positive
+    /// definite or the innovation covariance is singular.
+    pub fn update(&mut self, z: &Vector<M>, measure: impl Fn(&Vector<N>) -> Vector<M>) -> bool {
+        let Some(points) = self.sigma_points.generate(&self.state, &self.covariance) else {
+            return false;
+        };
+        let (wm, wc) = self.sigma_points.weights(N);
+        let measured: Vec<Vector<M>> = points.iter().map(&measure).collect();
+        let expected = weighted_mean(&measured, &wm, &self.measurement_angles);
+        let mut s = self.measurement_noise;
+        let mut cross = Matrix::<N, M>::zeros();
+        for ((p, zp), w) in points.iter().zip(&measured).zip(&wc) {
+            let dz = residual(zp, &expected, &self.measurement_angles);
+            s = s + dz * dz.transpose() * *w;
+            cross = cross + residual(p, &self.state, &self.state_angles) * dz.transpose() * *w;
+        }
+        // K = Pxz S^-1, solved as S K' = Pxz' since S is symmetric
+        let Some(kt) = s.solve(&cross.transpose()) else {
+            return false;
+        };
+        let k = kt.transpose();
+        let y = residual(z, &expected, &self.measurement_angles);
+        self.state = self.state + k * y;
+        for (i, &angle) in self.state_angles.iter().enumerate() {
+            if angle {
+                self.state[i] = wrap_angle(self.state[i]);
+            }
+        }
+        self.covariance = (self.covariance - k * s * k.transpose()).symmetrize();
+        true
+    }
+}
+
+#[cfg(test)]
+#[path = "ukf_tests.rs"]
+mod tests;
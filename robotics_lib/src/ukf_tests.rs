@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use crate::ekf::{ExtendedKalmanFilter, KalmanFilter};
+    use crate::math::{wrap_angle, Matrix, Vec2, Vector};
+    use crate::rng::Rng;
+    use crate::ukf::{SigmaPoints, UnscentedKalmanFilter};
+    use alloc::vec::Vec;
+    use core::f32::consts::PI;
+
+    fn assert_close<const R: usize, const C: usize>(a: &Matrix<R, C>, b: &Matrix<R, C>, tol: f32) {
+        assert!((*a - *b).max_abs() < tol, "{:?} != {:?}", a, b);
+    }
+
+    // ==================== SIGMA POINTS ====================
+
+    #[test]
+    fn test_weights_sum_to_one() {
+        for sigma in [
+            SigmaPoints::default(),
+            SigmaPoints::new(0.5, 2.0, 0.0),
+            SigmaPoints::new(1.0, 0.0, 1.0),
+        ] {
+            let (wm, wc) = sigma.weights(3);
+            assert_eq!(wm.len(), 7);
+            assert!((wm.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            let excess = 1.0 - sigma.alpha * sigma.alpha + sigma.beta;
+            assert!((wc.iter().sum::<f32>() - 1.0 - excess).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_sigma_points_reproduce_mean_and_covariance() {
+        let sigma = SigmaPoints::new(0.5, 2.0, 1.0);
+        let mean = Vector::from_array([1.0, -2.0, 0.5]);
+        let cov = Matrix::new([[2.0, 0.3, 0.1], [0.3, 1.0, -0.2], [0.1, -0.2, 0.5]]);
+        let points = sigma.generate(&mean, &cov).unwrap();
+        assert_eq!(points.len(), 7);
+        let (wm, _) = sigma.weights(3);
+        let mut m = Vector::zeros();
+        for (p, w) in points.iter().zip(&wm) {
+            m = m + *p * *w;
+        }
+        assert_close(&m, &mean, 1e-4);
+        // The centre point adds no spread, so the mean weights recover P too
+        let mut c = Matrix::zeros();
+        for (p, w) in points.iter().zip(&wm) {
+            c = c + (*p - mean) * (*p - mean).transpose() * *w;
+        }
+        assert_close(&c, &cov, 1e-4);
+        assert!(sigma.generate(&mean, &Matrix::zeros()).is_none());
+    }
+
+    // ==================== FILTERING ====================
+
+    #[test]
+    fn test_linear_model_matches_kalman_filter() {
+        let dt = 0.2;
+        let f = Matrix::new([[1.0, dt], [0.0, 1.0]]);
+        let h = Matrix::new([[1.0, 0.0]]);
+        let (p0, q, r) = (
+            Matrix::diagonal([2.0, 1.0]),
+            Matrix::diagonal([0.01, 0.05]),
+            Matrix::new([[0.5]]),
+        );
+        let mut kf = KalmanFilter::new(Vector::zeros(), p0, f, h, q, r);
+        let mut ukf = UnscentedKalmanFilter::new(Vector::zeros(), p0, q, r);
+        for i in 0..30 {
+            kf.predict();
+            assert!(ukf.predict(|x| f * *x));
+            let z = Vector::from_array([0.3 * i as f32]);
+            kf.update(&z);
+            assert!(ukf.update(&z, |x| h * *x));
+        }
+        assert_close(&ukf.state, &kf.state, 1e-3);
+        assert_close(&ukf.covariance, &kf.covariance, 1e-4);
+    }
+
+    #[test]
+    fn test_angle_mean_across_seam() {
+        // A heading near PI with spread across the seam stays near PI
+        let mut ukf = UnscentedKalmanFilter::<1, 1>::new(
+            Vector::from_array([PI - 0.05]),
+            Matrix::new([[0.09]]),
+            Matrix::new([[0.01]]),
+            Matrix::new([[0.01]]),
+        );
+        ukf.state_angles = [true];
+        ukf.measurement_angles = [true];
+        assert!(ukf.predict(|x| Vector::from_array([wrap_angle(x[0] + 0.1)])));
+        assert!((wrap_angle(ukf.state[0] - (PI + 0.05))).abs() < 1e-3);
+        assert!(ukf.covariance[(0, 0)] < 0.2);
+        // A measurement on the other side of the seam pulls the short way
+        let before = ukf.state[0];
+        assert!(ukf.update(&Vector::from_array([-PI + 0.2]), |x| *x));
+        assert!(wrap_angle(ukf.state[0] - before) > 0.0);
+        assert!(ukf.state[0] > -PI && ukf.state[0] <= PI);
+    }
+
+    // ==================== RANGE-BEARING BENCHMARK ====================
+
+    const BEACONS: [Vec2; 3] = [
+        Vec2 { x: 0.0, y: 0.0 },
+        Vec2 { x: 4.0, y: 1.0 },
+        Vec2 { x: 1.0, y: 5.0 },
+    ];
+
+    // Unicycle [x, y, theta] driven by known speed and turn rate
+    fn drive(x: &Vector<3>, v: f32, w: f32, dt: f32) -> Vector<3> {
+        Vector::from_array([
+            x[0] + v * libm::cosf(x[2]) * dt,
+            x[1] + v * libm::sinf(x[2]) * dt,
+            wrap_angle(x[2] + w * dt),
+        ])
+    }
+
+    fn drive_jacobian(x: &Vector<3>, v: f32, dt: f32) -> Matrix<3, 3> {
+        Matrix::new([
+            [1.0, 0.0, -v * libm::sinf(x[2]) * dt],
+            [0.0, 1.0, v * libm::cosf(x[2]) * dt],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+
+    // Range and bearing of a beacon relative to the robot
+    fn observe(x: &Vector<3>, beacon: Vec2) -> Vector<2> {
+        let (dx, dy) = (beacon.x - x[0], beacon.y - x[1]);
+        Vector::from_array([
+            libm::hypotf(dx, dy),
+            wrap_angle(libm::atan2f(dy, dx) - x[2]),
+        ])
+    }
+
+    fn observe_jacobian(x: &Vector<3>, beacon: Vec2) -> Matrix<2, 3> {
+        let (dx, dy) = (beacon.x - x[0], beacon.y - x[1]);
+        let q = dx * dx + dy * dy;
+        let r = libm::sqrtf(q);
+        Matrix::new([[-dx / r, -dy / r, 0.0], [dy / q, -dx / q, -1.0]])
+    }
+
+    // Mean position error of the EKF and the UKF over one run. The initial
+    // guess is drawn from the prior, the heading wanders, and a beacon fix
+    // only comes every fifth step, so the dead-reckoned spread grows into a
+    // curved banana that linearisation misrepresents. Both filters get the
+    // same controls and measurements.
+    fn benchmark(seed: u64) -> (f32, f32) {
+        let (v, w, dt) = (1.0, 0.4, 0.5);
+        let (range_sd, bearing_sd, heading_sd) = (0.1, 0.05, 0.01);
+        let mut rng = Rng::new(seed);
+        let mut truth = Vector::from_array([2.0, 1.0, 0.5]);
+        let p0 = Matrix::diagonal([1.0, 1.0, 0.3]);
+        let guess = Vector::from_array([
+            truth[0] + rng.gaussian(),
+            truth[1] + rng.gaussian(),
+            wrap_angle(truth[2] + libm::sqrtf(0.3) * rng.gaussian()),
+        ]);
+        let q = Matrix::diagonal([1e-4, 1e-4, heading_sd * heading_sd]);
+        let r = Matrix::diagonal([range_sd * range_sd, bearing_sd * bearing_sd]);
+        let mut ekf = ExtendedKalmanFilter::new(guess, p0, q, r);
+        let mut ukf = UnscentedKalmanFilter::new(guess, p0, q, r);
+        ukf.state_angles = [false, false, true];
+        ukf.measurement_angles = [false, true];
+
+        let (mut ekf_error, mut ukf_error) = (0.0, 0.0);
+        let steps = 200usize;
+        for step in 0..steps {
+            truth = drive(&truth, v, w, dt);
+            truth[0] += 0.01 * rng.gaussian();
+            truth[1] += 0.01 * rng.gaussian();
+            truth[2] = wrap_angle(truth[2] + heading_sd * rng.gaussian());
+            ekf.predict(|x| drive(x, v, w, dt), |x| drive_jacobian(x, v, dt));
+            ukf.predict(|x| drive(x, v, w, dt));
+
+            if step.is_multiple_of(5) {
+                let beacon = BEACONS[(step / 5) % BEACONS.len()];
+                let mut z = observe(&truth, beacon);
+                z[0] += range_sd * rng.gaussian();
+                z[1] = wrap_angle(z[1] + bearing_sd * rng.gaussian());
+                ekf.update_with(
+                    &z,
+                    |x| observe(x, beacon),
+                    |x| observe_jacobian(x, beacon),
+                    |z, h| Vector::from_array([z[0] - h[0], wrap_angle(z[1] - h[1])]),
+                );
+                ekf.state[2] = wrap_angle(ekf.state[2]);
+                ukf.update(&z, |x| observe(x, beacon));
+            }
+
+            let error = |x: &Vector<3>| libm::hypotf(x[0] - truth[0], x[1] - truth[1]);
+            ekf_error += error(&ekf.state);
+            ukf_error += error(&ukf.state);
+        }
+        (ekf_error / steps as f32, ukf_error / steps as f32)
+    }
+
+    #[test]
+    fn test_ukf_beats_ekf_on_range_bearing() {
+        let runs: Vec<(f32, f32)> = (0..20).map(benchmark).collect();
+        let ekf: f32 = runs.iter().map(|r| r.0).sum::<f32>() / runs.len() as f32;
+        let ukf: f32 = runs.iter().map(|r| r.1).sum::<f32>() / runs.len() as f32;
+        assert!(ukf.is_finite() && ekf.is_finite());
+        assert!(ukf < 0.9 * ekf, "UKF {} vs EKF {}", ukf, ekf);
+        assert!(ukf < 0.25, "UKF {}", ukf);
+    }
+}
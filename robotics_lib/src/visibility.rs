@@ -0,0 +1,137 @@
+use crate::collision::{CollisionChecker, PolygonWorld};
+use crate::grid_search::MinScored;
+use crate::math::Vec2;
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+/// Visibility graph over the vertices of a [`PolygonWorld`].
+///
+/// Shortest paths among polygonal obstacles bend only at convex obstacle
+/// vertices, so connecting every pair of mutually visible convex vertices
+/// and searching that graph gives the exact shortest path. The graph is
+/// built once; start and goal are joined to it for each query.
+#[derive(Clone, Debug)]
+pub struct VisibilityGraph {
+    world: PolygonWorld,
+    nodes: Vec<Vec2>,
+    adjacency: Vec<Vec<(usize, f32)>>,
+}
+
+impl VisibilityGraph {
+    pub fn new(world: PolygonWorld) -> Self {
+        let mut nodes = Vec::new();
+        for poly in &world.obstacles {
+            let n = poly.vertices.len();
+            // Positive for counter-clockwise winding
+            let area: f32 = poly.edges().map(|(a, b)| a.cross(&b)).sum();
+            for i in 0..n {
+                let prev = poly.vertices[(i + n - 1) % n];
+                let v = poly.vertices[i];
+                let next = poly.vertices[(i + 1) % n];
+                let turn = (v - prev).cross(&(next - v));
+                if turn * area > 0.0 && world.is_free(v) {
+                    nodes.push(v);
+                }
+            }
+        }
+        let mut adjacency = alloc::vec![Vec::new(); nodes.len()];
+        for i in 0..nodes.len() {
+            for j in i + 1..nodes.len() {
+                if world.segment_free(nodes[i], nodes[j]) {
+                    let d = nodes[i].distance(&nodes[j]);
+                    adjacency[i].push((j, d));
+                    adjacency[j].push((i, d));
+                }
+            }
+        }
+        Self {
+            world,
+            nodes,
+            adjacency,
+        }
+    }
+
+    pub fn world(&self) -> &PolygonWorld {
+        &self.world
+    }
+
+    /// Convex obstacle vertices the graph is built on.
+    pub fn nodes(&self) -> &[Vec2] {
+        &self.nodes
+    }
+
+    /// Number of undirected edges.
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// Shortest path from `start` to `goal` as a list of points including
+    /// both ends, or None if either is blocked or they are not connected.
+    pub fn plan(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        if !self.world.is_free(start) || !self.world.is_free(goal) {
+            return None;
+        }
+        if self.world.segment_free(start, goal) {
+            return Some(alloc::vec![start, goal]);
+        }
+        // A* with start and goal as two extra nodes after the graph's own
+        let n = self.nodes.len();
+        let (s, g) = (n, n + 1);
+        let point = |i: usize| match i {
+            _ if i == s => start,
+            _ if i == g => goal,
+            _ => self.nodes[i],
+        };
+        let goal_links: Vec<bool> = self
+            .nodes
+            .iter()
+            .map(|&v| self.world.segment_free(v, goal))
+            .collect();
+
+        let mut dist = alloc::vec![f32::INFINITY; n + 2];
+        let mut parent = alloc::vec![usize::MAX; n + 2];
+        let mut open = BinaryHeap::new();
+        dist[s] = 0.0;
+        open.push(MinScored::new(start.distance(&goal), s));
+        while let Some(MinScored { item: current, .. }) = open.pop() {
+            if current == g {
+                let mut path = alloc::vec![goal];
+                let mut c = g;
+                while parent[c] != usize::MAX {
+                    c = parent[c];
+                    path.push(point(c));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let p = point(current);
+            let mut relax = |next: usize, cost: f32, open: &mut BinaryHeap<_>| {
+                let d = dist[current] + cost;
+                if d < dist[next] {
+                    dist[next] = d;
+                    parent[next] = current;
+                    open.push(MinScored::new(d + point(next).distance(&goal), next));
+                }
+            };
+            if current == s {
+                for (i, &v) in self.nodes.iter().enumerate() {
+                    if self.world.segment_free(start, v) {
+                        relax(i, start.distance(&v), &mut open);
+                    }
+                }
+            } else {
+                for &(next, cost) in &self.adjacency[current] {
+                    relax(next, cost, &mut open);
+                }
+                if goal_links[current] {
+                    relax(g, p.distance(&goal), &mut open);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+#[path = "visibility_tests.rs"]
+mod tests;
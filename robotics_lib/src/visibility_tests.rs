@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use crate::collision::{CollisionChecker, Polygon, PolygonWorld};
+    use crate::math::Vec2;
+    use crate::path::polyline_length;
+    use crate::visibility::VisibilityGraph;
+    use alloc::vec;
+
+    fn world(obstacles: alloc::vec::Vec<Polygon>) -> PolygonWorld {
+        PolygonWorld::with_obstacles(Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0), obstacles)
+    }
+
+    fn square() -> Polygon {
+        Polygon::rect(Vec2::new(4.0, 4.0), Vec2::new(6.0, 6.0))
+    }
+
+    // ==================== GRAPH ====================
+
+    #[test]
+    fn test_nodes_are_convex_vertices() {
+        let l = Polygon::new(vec![
+            Vec2::new(2.0, 2.0),
+            Vec2::new(6.0, 2.0),
+            Vec2::new(6.0, 3.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(3.0, 6.0),
+            Vec2::new(2.0, 6.0),
+        ]);
+        let graph = VisibilityGraph::new(world(vec![l]));
+        assert_eq!(graph.nodes().len(), 5);
+        assert!(!graph.nodes().contains(&Vec2::new(3.0, 3.0)));
+
+        // Clockwise winding gives the same nodes
+        let mut reversed = square();
+        reversed.vertices.reverse();
+        assert_eq!(VisibilityGraph::new(world(vec![reversed])).nodes().len(), 4);
+    }
+
+    #[test]
+    fn test_edges_follow_obstacle_sides() {
+        let graph = VisibilityGraph::new(world(vec![square()]));
+        // Four sides; the diagonals cross the square
+        assert_eq!(graph.edge_count(), 4);
+    }
+
+    // ==================== PLANNING ====================
+
+    #[test]
+    fn test_straight_line_when_visible() {
+        let graph = VisibilityGraph::new(world(vec![square()]));
+        let path = graph
+            .plan(Vec2::new(1.0, 1.0), Vec2::new(9.0, 2.0))
+            .unwrap();
+        assert_eq!(path, vec![Vec2::new(1.0, 1.0), Vec2::new(9.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_detour_around_square_is_exact() {
+        let graph = VisibilityGraph::new(world(vec![square()]));
+        let path = graph
+            .plan(Vec2::new(2.0, 5.0), Vec2::new(8.0, 5.0))
+            .unwrap();
+        assert_eq!(path.len(), 4);
+        let expected = 2.0 * libm::sqrtf(5.0) + 2.0;
+        assert!((polyline_length(&path) - expected).abs() < 1e-4);
+        for w in path.windows(2) {
+            assert!(graph.world().segment_free(w[0], w[1]));
+        }
+    }
+
+    #[test]
+    fn test_wraps_around_concave_obstacle() {
+        // A cup open to the right with the start inside
+        let cup = Polygon::new(vec![
+            Vec2::new(2.0, 2.0),
+            Vec2::new(7.0, 2.0),
+            Vec2::new(7.0, 3.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(3.0, 7.0),
+            Vec2::new(7.0, 7.0),
+            Vec2::new(7.0, 8.0),
+            Vec2::new(2.0, 8.0),
+        ]);
+        let graph = VisibilityGraph::new(world(vec![cup]));
+        let path = graph
+            .plan(Vec2::new(4.0, 5.0), Vec2::new(1.0, 5.0))
+            .unwrap();
+        // Out of the mouth, round a tip and the back corner
+        assert_eq!(path.len(), 5);
+        let expected = libm::sqrtf(9.0 + 4.0) + 1.0 + 5.0 + libm::sqrtf(1.0 + 9.0);
+        assert!((polyline_length(&path) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_unreachable_and_blocked() {
+        // Poking out of the bounds, the wall cuts the world in two
+        let wall = Polygon::rect(Vec2::new(4.0, -1.0), Vec2::new(6.0, 11.0));
+        let graph = VisibilityGraph::new(world(vec![wall]));
+        assert!(graph
+            .plan(Vec2::new(1.0, 5.0), Vec2::new(9.0, 5.0))
+            .is_none());
+        assert!(graph
+            .plan(Vec2::new(1.0, 5.0), Vec2::new(1.0, 9.0))
+            .is_some());
+        assert!(graph
+            .plan(Vec2::new(5.0, 5.0), Vec2::new(1.0, 9.0))
+            .is_none());
+        assert!(graph
+            .plan(Vec2::new(1.0, 5.0), Vec2::new(-1.0, 5.0))
+            .is_none());
+    }
+}